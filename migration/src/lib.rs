@@ -13,6 +13,10 @@ mod m20250214_000001_add_indexes;
 mod m20250216_000001_add_thumbnail_url_to_portfolios;
 mod m20250216_000002_add_message_perf_indexes;
 mod m20250226_000001_add_category_to_gigs;
+mod m20250301_000001_add_status_to_users;
+mod m20250305_000001_create_api_keys_table;
+mod m20250310_000001_add_profile_sync_to_users;
+mod m20250315_000001_create_audit_events_table;
 
 pub struct Migrator;
 
@@ -33,6 +37,10 @@ impl MigratorTrait for Migrator {
             Box::new(m20250216_000001_add_thumbnail_url_to_portfolios::Migration),
             Box::new(m20250216_000002_add_message_perf_indexes::Migration),
             Box::new(m20250226_000001_add_category_to_gigs::Migration),
+            Box::new(m20250301_000001_add_status_to_users::Migration),
+            Box::new(m20250305_000001_create_api_keys_table::Migration),
+            Box::new(m20250310_000001_add_profile_sync_to_users::Migration),
+            Box::new(m20250315_000001_create_audit_events_table::Migration),
 
         ]
     }
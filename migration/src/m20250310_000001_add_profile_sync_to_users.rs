@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    LastSyncedAt,
+    EmailLocked,
+    DisplayNameLocked,
+    AvatarUrlLocked,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Add `last_synced_at` — when the profile was last updated from JWT claims.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(
+                        ColumnDef::new(Users::LastSyncedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Add lock flags — set when the user edits a field, so claim sync
+        //    no longer overwrites it.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(
+                        ColumnDef::new(Users::EmailLocked)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column(
+                        ColumnDef::new(Users::DisplayNameLocked)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column(
+                        ColumnDef::new(Users::AvatarUrlLocked)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 3. Existing profiles may already contain manual edits; protect them.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE users SET display_name_locked = TRUE, avatar_url_locked = TRUE WHERE updated_at IS NOT NULL",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::LastSyncedAt)
+                    .drop_column(Users::EmailLocked)
+                    .drop_column(Users::DisplayNameLocked)
+                    .drop_column(Users::AvatarUrlLocked)
+                    .to_owned(),
+            )
+            .await
+    }
+}
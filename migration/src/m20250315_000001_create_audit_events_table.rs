@@ -0,0 +1,126 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Identifiers for the `audit_events` table and its columns.
+#[derive(DeriveIden)]
+enum AuditEvents {
+    Table,
+    Id,
+    ActorId,
+    Action,
+    TargetType,
+    TargetId,
+    Before,
+    After,
+    Ip,
+    RequestId,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create the audit_events table. `actor_id` and `target_id` have no
+        //    foreign keys so events outlive the users and rows they mention.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEvents::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditEvents::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditEvents::ActorId).uuid().null())
+                    .col(ColumnDef::new(AuditEvents::Action).string().not_null())
+                    .col(ColumnDef::new(AuditEvents::TargetType).string().not_null())
+                    .col(ColumnDef::new(AuditEvents::TargetId).uuid().null())
+                    .col(ColumnDef::new(AuditEvents::Before).json_binary().null())
+                    .col(ColumnDef::new(AuditEvents::After).json_binary().null())
+                    .col(ColumnDef::new(AuditEvents::Ip).string().null())
+                    .col(ColumnDef::new(AuditEvents::RequestId).string().null())
+                    .col(
+                        ColumnDef::new(AuditEvents::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Indexes for the admin queries: by actor, by target and by time.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_actor_created")
+                    .table(AuditEvents::Table)
+                    .col(AuditEvents::ActorId)
+                    .col(AuditEvents::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_target_created")
+                    .table(AuditEvents::Table)
+                    .col(AuditEvents::TargetType)
+                    .col(AuditEvents::TargetId)
+                    .col(AuditEvents::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_created_id")
+                    .table(AuditEvents::Table)
+                    .col(AuditEvents::CreatedAt)
+                    .col(AuditEvents::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 3. Make the table append-only: reject every UPDATE and DELETE.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE OR REPLACE FUNCTION audit_events_append_only() RETURNS trigger AS $$
+                BEGIN
+                    RAISE EXCEPTION 'audit_events is append-only';
+                END;
+                $$ LANGUAGE plpgsql",
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE TRIGGER trg_audit_events_append_only
+                BEFORE UPDATE OR DELETE ON audit_events
+                FOR EACH ROW EXECUTE FUNCTION audit_events_append_only()",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditEvents::Table).to_owned())
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("DROP FUNCTION IF EXISTS audit_events_append_only()")
+            .await?;
+
+        Ok(())
+    }
+}
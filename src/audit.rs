@@ -0,0 +1,134 @@
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest, dev::Payload, web};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::future::{Ready, ready};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::db::audit_events as audit_db;
+use crate::middleware::request_id::RequestId;
+use crate::models::audit_events::NewAuditEvent;
+use crate::rate_limit::client_ip;
+
+/// The row an audited action was performed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    User(Uuid),
+    Gig(Uuid),
+    Portfolio(Uuid),
+    Contract(Uuid),
+    ApiKey(Uuid),
+}
+
+impl Target {
+    /// Stored as `target_type` and accepted by the admin query.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::User(_) => "user",
+            Self::Gig(_) => "gig",
+            Self::Portfolio(_) => "portfolio",
+            Self::Contract(_) => "contract",
+            Self::ApiKey(_) => "api_key",
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        match *self {
+            Self::User(id)
+            | Self::Gig(id)
+            | Self::Portfolio(id)
+            | Self::Contract(id)
+            | Self::ApiKey(id) => id,
+        }
+    }
+}
+
+/// One audited action: who did what to which row, with optional snapshots of
+/// the row before and after.
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    pub actor_id: Uuid,
+    /// `<target kind>.<verb>`, e.g. `gig.delete` or `contract.status_change`.
+    pub action: &'static str,
+    pub target: Target,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl AuditEvent {
+    pub fn new(actor_id: Uuid, action: &'static str, target: Target) -> Self {
+        Self {
+            actor_id,
+            action,
+            target,
+            before: None,
+            after: None,
+        }
+    }
+
+    /// Snapshot of the target before the action. Pass a response DTO (e.g.
+    /// `UserResponse`) rather than a model holding secrets.
+    pub fn before(mut self, snapshot: &impl Serialize) -> Self {
+        self.before = serde_json::to_value(snapshot).ok();
+        self
+    }
+
+    /// Snapshot of the target after the action.
+    pub fn after(mut self, snapshot: &impl Serialize) -> Self {
+        self.after = serde_json::to_value(snapshot).ok();
+        self
+    }
+}
+
+/// Request context for audit events: the client IP and request id.
+///
+/// Add it to a handler's arguments and call [`Audit::record`] once the action
+/// has succeeded. The IP follows `rate_limit.trust_proxy`, like rate limiting.
+pub struct Audit {
+    ip: Option<String>,
+    request_id: Option<String>,
+}
+
+impl Audit {
+    pub fn ip(&self) -> Option<&str> {
+        self.ip.as_deref()
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Append `event` to the audit log.
+    ///
+    /// Best effort: the action already happened, so a failed write is logged
+    /// rather than turned into an error response.
+    pub async fn record(&self, db: &DatabaseConnection, event: AuditEvent) {
+        let input = NewAuditEvent {
+            actor_id: Some(event.actor_id),
+            action: event.action.to_string(),
+            target_type: event.target.kind().to_string(),
+            target_id: Some(event.target.id()),
+            before: event.before,
+            after: event.after,
+            ip: self.ip.clone(),
+            request_id: self.request_id.clone(),
+        };
+        if let Err(e) = audit_db::insert_audit_event(db, input).await {
+            tracing::error!(action = event.action, "Failed to write audit event: {e}");
+        }
+    }
+}
+
+impl FromRequest for Audit {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let trust_proxy = req
+            .app_data::<web::Data<AppConfig>>()
+            .is_some_and(|config| config.rate_limit.trust_proxy);
+        let ip = client_ip(&req.connection_info(), trust_proxy).map(str::to_string);
+        let request_id = req.extensions().get::<RequestId>().map(|id| id.0.clone());
+        ready(Ok(Self { ip, request_id }))
+    }
+}
@@ -0,0 +1,33 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Prefix of every key, so leaked keys are easy to recognise (e.g. by secret scanners).
+pub const KEY_PREFIX: &str = "gwk_";
+
+/// Number of leading key characters stored in clear text for display.
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// A freshly generated API key. `key` is shown to the user once; only `hash` is stored.
+pub struct GeneratedKey {
+    pub key: String,
+    pub prefix: String,
+    pub hash: String,
+}
+
+/// Generate a new random API key (256 bits of entropy).
+pub fn generate() -> GeneratedKey {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    let key = format!("{KEY_PREFIX}{}", hex::encode(bytes));
+    GeneratedKey {
+        prefix: key[..DISPLAY_PREFIX_LEN].to_string(),
+        hash: hash_key(&key),
+        key,
+    }
+}
+
+/// Hex-encoded SHA-256 of a key. Keys are random, so a fast hash is sufficient.
+pub fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
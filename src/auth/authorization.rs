@@ -1,66 +1,80 @@
-use actix_web::HttpResponse;
 use sea_orm::DatabaseConnection;
 use uuid::Uuid;
 
+use crate::auth::policy::{Action, Actor, ContractParties, Policy, Resource};
 use crate::db::contracts as contract_db;
 use crate::db::gigs as gig_db;
-use crate::models::contracts::{Model, Status};
+use crate::db::portfolio as portfolio_db;
+use crate::error::ApiError;
+use crate::models::{contracts, gigs, portfolio};
 
-pub async fn verify_contract_party(
+/// Fetch a contract together with the parties the policy needs.
+pub async fn load_contract(
     db: &DatabaseConnection,
     contract_id: Uuid,
-    user_id: Uuid,
-) -> Result<Model, HttpResponse> {
+) -> Result<(contracts::Model, ContractParties), ApiError> {
     let contract = contract_db::get_contract_by_id(db, contract_id)
-        .await
-        .map_err(|e| {
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }))
-        })?
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Contract {contract_id} not found")))?;
+
+    let gig = gig_db::get_gig_by_id(db, contract.gig_id)
+        .await?
         .ok_or_else(|| {
-            HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Contract {contract_id} not found"),
-            }))
+            ApiError::not_found("The gig associated with this contract no longer exists")
         })?;
 
-    if contract.status != Status::Accepted {
-        return Err(HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "Chat is only available for accepted contracts",
-        })));
-    }
-
-    let is_client = contract.user_id == user_id;
-
-    let is_freelancer = match gig_db::get_gig_by_id(db, contract.gig_id).await {
-        Ok(Some(gig)) => gig.user_id == user_id,
-        _ => false,
+    let parties = ContractParties {
+        client_id: contract.user_id,
+        freelancer_id: gig.user_id,
+        status: contract.status.clone(),
     };
+    Ok((contract, parties))
+}
 
-    if !is_client && !is_freelancer {
-        return Err(HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You are not a party to this contract",
-        })));
-    }
+/// Check that `actor` may use the chat of a contract (REST history and WebSocket).
+pub async fn verify_contract_party(
+    db: &DatabaseConnection,
+    contract_id: Uuid,
+    actor: &Actor,
+) -> Result<contracts::Model, ApiError> {
+    let (contract, parties) = load_contract(db, contract_id).await?;
 
+    Policy::can(actor, Action::Read, &Resource::Chat(parties))?;
     Ok(contract)
 }
 
-pub async fn verify_gig_owner(
+/// Fetch a gig and check that `actor` may perform `action` on it.
+pub async fn authorize_gig(
     db: &DatabaseConnection,
     gig_id: Uuid,
-    user_id: Uuid,
-) -> Result<(), HttpResponse> {
-    match gig_db::get_gig_by_id(db, gig_id).await {
-        Ok(Some(gig)) if gig.user_id == user_id => Ok(()),
-        Ok(Some(_)) => Err(HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You do not own this gig",
-        }))),
-        Ok(None) => Err(HttpResponse::NotFound().json(serde_json::json!({
-            "error": format!("Gig {gig_id} not found"),
-        }))),
-        Err(e) => Err(HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Database error: {e}"),
-        }))),
-    }
+    actor: &Actor,
+    action: Action,
+) -> Result<gigs::Model, ApiError> {
+    let gig = gig_db::get_gig_by_id(db, gig_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Gig {gig_id} not found")))?;
+
+    let resource = Resource::Gig {
+        owner_id: gig.user_id,
+    };
+    Policy::can(actor, action, &resource)?;
+    Ok(gig)
+}
+
+/// Fetch a portfolio item and check that `actor` may perform `action` on it.
+pub async fn authorize_portfolio(
+    db: &DatabaseConnection,
+    id: Uuid,
+    actor: &Actor,
+    action: Action,
+) -> Result<portfolio::Model, ApiError> {
+    let item = portfolio_db::get_portfolio_by_id(db, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Portfolio item {id} not found")))?;
+
+    let resource = Resource::Portfolio {
+        freelancer_id: item.freelancer_id,
+    };
+    Policy::can(actor, action, &resource)?;
+    Ok(item)
 }
@@ -0,0 +1,159 @@
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use jsonwebtoken::errors::ErrorKind;
+
+use crate::error::error_envelope;
+
+/// Authentication failures shared by the REST extractor and the WebSocket handshake.
+///
+/// Every variant maps to a stable machine-readable `code` so clients can tell an
+/// expired session apart from a misconfigured project or a forged token.
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("Missing Authorization header")]
+    MissingToken,
+    #[error("Authorization header must be: Bearer <token>")]
+    InvalidScheme,
+    #[error("Malformed token: {0}")]
+    MalformedToken(String),
+    #[error("Invalid token signature")]
+    InvalidSignature,
+    #[error("Token algorithm is not accepted")]
+    InvalidAlgorithm,
+    #[error("Token has expired")]
+    TokenExpired,
+    #[error("Token is not valid yet")]
+    TokenNotYetValid,
+    #[error("Token has been revoked")]
+    TokenRevoked,
+    #[error("Token was issued by an untrusted issuer")]
+    InvalidIssuer,
+    #[error("Token audience is not accepted")]
+    InvalidAudience,
+    #[error("Token has no subject")]
+    MissingSubject,
+    #[error("Invalid subject claim: {0}")]
+    InvalidSubject(String),
+    #[error("No email in token claims")]
+    MissingEmail,
+    #[error("Invalid API key")]
+    InvalidApiKey,
+    #[error("API key has been revoked")]
+    ApiKeyRevoked,
+    #[error("API key has expired")]
+    ApiKeyExpired,
+    #[error("WebSocket ticket is invalid, expired or already used")]
+    InvalidTicket,
+    #[error("Tokens in the query string are disabled; request a ticket from /api/chat/ws-ticket")]
+    QueryTokenDisabled,
+    #[error("API key is missing the '{0}' scope")]
+    MissingScope(String),
+    #[error("This action requires one of the roles: {0}")]
+    InsufficientRole(String),
+    #[error("{}", suspended_message(.until, .reason))]
+    AccountSuspended {
+        until: Option<chrono::DateTime<chrono::Utc>>,
+        reason: Option<String>,
+    },
+    #[error("Account is banned{}", reason_suffix(.reason))]
+    AccountBanned { reason: Option<String> },
+    #[error("Signing key {0} is not known")]
+    UnknownKey(String),
+    #[error("Signing keys are unavailable: {0}")]
+    KeysUnavailable(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AuthError {
+    /// Stable machine-readable error code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingToken => "missing_token",
+            Self::InvalidScheme => "invalid_auth_scheme",
+            Self::MalformedToken(_) => "malformed_token",
+            Self::InvalidSignature => "invalid_signature",
+            Self::InvalidAlgorithm => "invalid_algorithm",
+            Self::TokenExpired => "token_expired",
+            Self::TokenNotYetValid => "token_not_yet_valid",
+            Self::TokenRevoked => "token_revoked",
+            Self::InvalidIssuer => "invalid_issuer",
+            Self::InvalidAudience => "invalid_audience",
+            Self::MissingSubject => "missing_subject",
+            Self::InvalidSubject(_) => "invalid_subject",
+            Self::MissingEmail => "missing_email",
+            Self::InvalidApiKey => "invalid_api_key",
+            Self::ApiKeyRevoked => "api_key_revoked",
+            Self::ApiKeyExpired => "api_key_expired",
+            Self::InvalidTicket => "invalid_ws_ticket",
+            Self::QueryTokenDisabled => "query_token_disabled",
+            Self::MissingScope(_) => "insufficient_scope",
+            Self::InsufficientRole(_) => "insufficient_role",
+            Self::AccountSuspended { .. } => "account_suspended",
+            Self::AccountBanned { .. } => "account_banned",
+            Self::UnknownKey(_) => "unknown_signing_key",
+            Self::KeysUnavailable(_) => "signing_keys_unavailable",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+}
+
+fn suspended_message(
+    until: &Option<chrono::DateTime<chrono::Utc>>,
+    reason: &Option<String>,
+) -> String {
+    let until = match until {
+        Some(until) => format!(" until {}", until.to_rfc3339()),
+        None => String::new(),
+    };
+    format!("Account is suspended{until}{}", reason_suffix(reason))
+}
+
+fn reason_suffix(reason: &Option<String>) -> String {
+    match reason {
+        Some(reason) => format!(": {reason}"),
+        None => String::new(),
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for AuthError {
+    fn from(e: jsonwebtoken::errors::Error) -> Self {
+        match e.kind() {
+            ErrorKind::InvalidSignature => Self::InvalidSignature,
+            ErrorKind::InvalidAlgorithm | ErrorKind::MissingAlgorithm => Self::InvalidAlgorithm,
+            ErrorKind::ExpiredSignature => Self::TokenExpired,
+            ErrorKind::ImmatureSignature => Self::TokenNotYetValid,
+            ErrorKind::InvalidIssuer => Self::InvalidIssuer,
+            ErrorKind::InvalidAudience => Self::InvalidAudience,
+            ErrorKind::MissingRequiredClaim(claim) => match claim.as_str() {
+                "sub" => Self::MissingSubject,
+                "iss" => Self::InvalidIssuer,
+                "aud" => Self::InvalidAudience,
+                _ => Self::MalformedToken(format!("missing required claim '{claim}'")),
+            },
+            _ => Self::MalformedToken(e.to_string()),
+        }
+    }
+}
+
+impl ResponseError for AuthError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::MissingScope(_)
+            | Self::InsufficientRole(_)
+            | Self::AccountSuspended { .. }
+            | Self::AccountBanned { .. } => StatusCode::FORBIDDEN,
+            Self::KeysUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if let Self::Internal(detail) = self {
+            tracing::error!("Authentication failed: {detail}");
+            return error_envelope(self.status_code(), "Internal server error", self.code());
+        }
+        error_envelope(self.status_code(), &self.to_string(), self.code())
+    }
+}
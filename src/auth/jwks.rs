@@ -1,52 +1,256 @@
-use jsonwebtoken::{Algorithm, DecodingKey, TokenData, Validation, decode, decode_header};
+use jsonwebtoken::{Algorithm, DecodingKey, decode_header};
 use moka::future::Cache;
-use std::sync::Arc;
-use tracing::debug;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
 
-const JWKS_URL_TEMPLATE: &str = "https://{}.supabase.co/auth/v1/.well-known/jwks.json";
+use crate::auth::error::AuthError;
+use crate::auth::jwt::Claims;
+use crate::auth::validation::ValidationPolicy;
+use crate::auth::verifier::TokenVerifier;
+use crate::metrics::metrics;
+use crate::telemetry;
 
+const JWKS_PATH: &str = "/auth/v1/.well-known/jwks.json";
+
+/// A decoding key parsed from a single JWK, together with its signing algorithm.
 #[derive(Clone)]
-struct JwksKeyData {
-    x: String,
-    y: String,
+pub struct JwksKeyData {
+    key: DecodingKey,
     algorithm: Algorithm,
 }
 
-#[derive(Clone)]
+impl JwksKeyData {
+    /// Parse a JWK object. EC keys are read from `x`/`y`, RSA keys from `n`/`e`
+    /// and Ed25519 (`OKP`) keys from `x`.
+    ///
+    /// The algorithm comes from `alg` when present, otherwise it is inferred from
+    /// the key type and curve. Unsupported or mismatched algorithms are rejected
+    /// rather than guessed, so a key can never be used with the wrong algorithm.
+    pub fn from_jwk(jwk: &serde_json::Value) -> Result<Self, String> {
+        let kty = jwk["kty"].as_str().ok_or("Missing 'kty' in JWK")?;
+
+        let algorithm = match jwk["alg"].as_str() {
+            Some(alg) => parse_algorithm(alg)?,
+            None => default_algorithm(kty, jwk["crv"].as_str())?,
+        };
+
+        let key = match (kty, algorithm) {
+            ("EC", Algorithm::ES256 | Algorithm::ES384) => {
+                let x = jwk["x"].as_str().ok_or("Missing 'x' in JWK")?;
+                let y = jwk["y"].as_str().ok_or("Missing 'y' in JWK")?;
+                DecodingKey::from_ec_components(x, y)
+            }
+            (
+                "RSA",
+                Algorithm::RS256
+                | Algorithm::RS384
+                | Algorithm::RS512
+                | Algorithm::PS256
+                | Algorithm::PS384
+                | Algorithm::PS512,
+            ) => {
+                let n = jwk["n"].as_str().ok_or("Missing 'n' in JWK")?;
+                let e = jwk["e"].as_str().ok_or("Missing 'e' in JWK")?;
+                DecodingKey::from_rsa_components(n, e)
+            }
+            ("OKP", Algorithm::EdDSA) => {
+                let x = jwk["x"].as_str().ok_or("Missing 'x' in JWK")?;
+                DecodingKey::from_ed_components(x)
+            }
+            _ => {
+                return Err(format!(
+                    "JWK key type {kty} cannot be used with {algorithm:?}"
+                ));
+            }
+        }
+        .map_err(|e| format!("Failed to create decoding key: {e}"))?;
+
+        Ok(Self { key, algorithm })
+    }
+
+    /// Verify the token signature against this key and decode the claims.
+    pub fn decode(&self, token: &str, policy: &ValidationPolicy) -> Result<Claims, AuthError> {
+        policy.decode(token, &self.key, self.algorithm)
+    }
+}
+
+fn parse_algorithm(alg: &str) -> Result<Algorithm, String> {
+    match alg {
+        "ES256" => Ok(Algorithm::ES256),
+        "ES384" => Ok(Algorithm::ES384),
+        "RS256" => Ok(Algorithm::RS256),
+        "RS384" => Ok(Algorithm::RS384),
+        "RS512" => Ok(Algorithm::RS512),
+        "PS256" => Ok(Algorithm::PS256),
+        "PS384" => Ok(Algorithm::PS384),
+        "PS512" => Ok(Algorithm::PS512),
+        "EdDSA" => Ok(Algorithm::EdDSA),
+        other => Err(format!("Unsupported JWK algorithm: {other}")),
+    }
+}
+
+fn default_algorithm(kty: &str, crv: Option<&str>) -> Result<Algorithm, String> {
+    match (kty, crv) {
+        ("EC", Some("P-256")) => Ok(Algorithm::ES256),
+        ("EC", Some("P-384")) => Ok(Algorithm::ES384),
+        ("RSA", _) => Ok(Algorithm::RS256),
+        ("OKP", Some("Ed25519")) => Ok(Algorithm::EdDSA),
+        _ => Err(format!(
+            "Cannot infer algorithm for JWK key type {kty} (curve {crv:?})"
+        )),
+    }
+}
+
+/// Read the `kid` from a token header.
+pub fn token_kid(token: &str) -> Result<String, AuthError> {
+    let header = decode_header(token)
+        .map_err(|e| AuthError::MalformedToken(format!("Failed to decode header: {e}")))?;
+    header
+        .kid
+        .ok_or_else(|| AuthError::MalformedToken("No 'kid' in token header".to_string()))
+}
+
+/// Parse every usable key of a JWKS document, skipping (and logging) the rest.
+pub fn parse_jwks(jwks: &serde_json::Value) -> Result<HashMap<String, JwksKeyData>, String> {
+    let entries = jwks["keys"].as_array().ok_or("No keys in JWKS")?;
+
+    let mut keys = HashMap::with_capacity(entries.len());
+    for jwk in entries {
+        let Some(kid) = jwk["kid"].as_str() else {
+            warn!("Skipping JWK without 'kid'");
+            continue;
+        };
+        match JwksKeyData::from_jwk(jwk) {
+            Ok(key) => {
+                keys.insert(kid.to_string(), key);
+            }
+            Err(e) => warn!("Skipping JWK kid={kid}: {e}"),
+        }
+    }
+
+    Ok(keys)
+}
+
+/// Timing knobs for [`JwksCache`].
+#[derive(Debug, Clone)]
+pub struct JwksSettings {
+    /// How often the background task refreshes the key set.
+    pub refresh_interval: Duration,
+    /// Minimum time between two fetches triggered by unknown `kid`s.
+    pub min_refetch_interval: Duration,
+    /// How long a `kid` that was missing after a refetch is remembered as unknown.
+    pub negative_ttl: Duration,
+}
+
+impl Default for JwksSettings {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(3600), // 1 hour
+            min_refetch_interval: Duration::from_secs(30),
+            negative_ttl: Duration::from_secs(300), // 5 minutes
+        }
+    }
+}
+
+/// The currently trusted key set and the outcome of the last fetch.
+#[derive(Default)]
+struct KeySet {
+    keys: HashMap<String, JwksKeyData>,
+    /// Time of the last fetch attempt, successful or not.
+    last_attempt: Option<Instant>,
+    /// Error of the last fetch attempt, if it failed.
+    last_error: Option<String>,
+}
+
+/// Keys published by the Supabase Auth server, refreshed in the background.
+///
+/// The whole key set is replaced on every fetch, so keys removed during a
+/// rotation stop being trusted. A token with an unknown `kid` triggers at most
+/// one refetch per `min_refetch_interval` (concurrent misses share it), and a
+/// `kid` still missing afterwards is negatively cached, so forged `kid`s cannot
+/// force a network round trip per request.
 pub struct JwksCache {
-    cache: Arc<Cache<String, JwksKeyData>>,
+    key_set: RwLock<KeySet>,
+    unknown_kids: Cache<String, ()>,
+    refetch_lock: Mutex<()>,
     jwks_url: String,
     client: reqwest::Client,
     anon_key: String,
+    policy: ValidationPolicy,
+    settings: JwksSettings,
 }
 
 impl JwksCache {
-    pub fn new(project_ref: &str, anon_key: &str) -> Self {
-        let client = reqwest::Client::new();
-        let cache = Arc::new(
-            Cache::builder()
-                .time_to_live(std::time::Duration::from_secs(3600))
-                .max_capacity(10)
-                .build(),
-        );
+    /// Create a cache for the JWKS published by the Supabase instance at
+    /// `supabase_url` (hosted `https://<ref>.supabase.co` or self-hosted).
+    pub fn new(supabase_url: &str, anon_key: &str, policy: ValidationPolicy) -> Self {
+        Self::with_settings(supabase_url, anon_key, policy, JwksSettings::default())
+    }
 
-        let jwks_url = JWKS_URL_TEMPLATE.replace("{}", project_ref);
+    pub fn with_settings(
+        supabase_url: &str,
+        anon_key: &str,
+        policy: ValidationPolicy,
+        settings: JwksSettings,
+    ) -> Self {
+        let unknown_kids = Cache::builder()
+            .time_to_live(settings.negative_ttl)
+            .max_capacity(1_000)
+            .build();
 
         Self {
-            cache,
-            jwks_url,
-            client,
+            key_set: RwLock::new(KeySet::default()),
+            unknown_kids,
+            refetch_lock: Mutex::new(()),
+            jwks_url: format!("{}{JWKS_PATH}", supabase_url.trim_end_matches('/')),
+            client: reqwest::Client::new(),
             anon_key: anon_key.to_string(),
+            policy,
+            settings,
         }
     }
 
+    /// Fetch the keys now and keep refreshing them every `refresh_interval`, so
+    /// the first requests after startup don't block on the network.
+    pub fn spawn_refresh(self: &Arc<Self>) {
+        let cache = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                let delay = match cache.refresh().await {
+                    Ok(count) => {
+                        debug!("Refreshed JWKS ({count} keys)");
+                        cache.settings.refresh_interval
+                    }
+                    Err(e) => {
+                        warn!("JWKS refresh failed: {e}");
+                        cache.settings.min_refetch_interval
+                    }
+                };
+                tokio::time::sleep(delay).await;
+            }
+        });
+    }
+
+    /// Number of keys currently trusted.
+    pub fn key_count(&self) -> usize {
+        self.key_set.read().map(|set| set.keys.len()).unwrap_or(0)
+    }
+
+    #[tracing::instrument(name = "jwks.fetch", skip_all, fields(url = %self.jwks_url))]
     async fn fetch_jwks(&self) -> Result<serde_json::Value, String> {
         debug!("Fetching JWKS from {}", self.jwks_url);
 
-        let response: reqwest::Response = self
+        let mut request = self
             .client
             .get(&self.jwks_url)
-            .header("apikey", &self.anon_key)
+            .header("apikey", &self.anon_key);
+        for (name, value) in telemetry::trace_headers() {
+            request = request.header(name, value);
+        }
+        let response: reqwest::Response = request
             .send()
             .await
             .map_err(|e| format!("Failed to fetch JWKS: {e}"))?;
@@ -64,57 +268,104 @@ impl JwksCache {
         serde_json::from_str(&text).map_err(|e| format!("Failed to parse JWKS JSON: {e}"))
     }
 
-    async fn get_key_data(&self, kid: &str) -> Result<JwksKeyData, String> {
-        if let Some(cached) = self.cache.get(kid).await {
-            return Ok(cached);
+    /// Fetch the JWKS and replace the trusted key set. Returns the number of keys.
+    pub async fn refresh(&self) -> Result<usize, String> {
+        let result = self.fetch_jwks().await.and_then(|jwks| parse_jwks(&jwks));
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        metrics().jwks_fetches.with_label_values(&[outcome]).inc();
+
+        let mut set = self.key_set.write().map_err(|e| e.to_string())?;
+        set.last_attempt = Some(Instant::now());
+
+        match result {
+            Ok(keys) => {
+                let count = keys.len();
+                metrics().jwks_keys.set(count as i64);
+                set.keys = keys;
+                set.last_error = None;
+                drop(set);
+                // Previously unknown kids may have been published by this fetch.
+                self.unknown_kids.invalidate_all();
+                Ok(count)
+            }
+            Err(e) => {
+                set.last_error = Some(e.clone());
+                Err(e)
+            }
         }
+    }
+
+    fn cached_key(&self, kid: &str) -> Option<JwksKeyData> {
+        self.key_set.read().ok()?.keys.get(kid).cloned()
+    }
 
-        let jwks = self.fetch_jwks().await?;
-        let keys = jwks["keys"].as_array().ok_or("No keys in JWKS")?;
-
-        let key_data = keys
-            .iter()
-            .find(|k| k["kid"].as_str() == Some(kid))
-            .ok_or(format!("Key with kid={kid} not found in JWKS"))?;
-
-        let x = key_data["x"]
-            .as_str()
-            .ok_or("Missing 'x' in JWK")?
-            .to_string();
-        let y = key_data["y"]
-            .as_str()
-            .ok_or("Missing 'y' in JWK")?
-            .to_string();
-
-        let alg_str = key_data["alg"].as_str().unwrap_or("ES256");
-        let algorithm = match alg_str {
-            "ES256" => Algorithm::ES256,
-            "ES384" => Algorithm::ES384,
-            _ => Algorithm::ES256,
+    /// Refetch after an unknown `kid`, unless a fetch happened too recently.
+    async fn refetch_for_unknown_kid(&self) -> Result<(), AuthError> {
+        // Concurrent misses wait here and then see the fresh `last_attempt`.
+        let _guard = self.refetch_lock.lock().await;
+
+        let (recent, last_error, has_keys) = {
+            let set = self
+                .key_set
+                .read()
+                .map_err(|e| AuthError::Internal(e.to_string()))?;
+            let recent = set
+                .last_attempt
+                .is_some_and(|at| at.elapsed() < self.settings.min_refetch_interval);
+            (recent, set.last_error.clone(), !set.keys.is_empty())
         };
 
-        let key_data = JwksKeyData { x, y, algorithm };
+        if recent {
+            return match last_error {
+                Some(e) if !has_keys => Err(AuthError::KeysUnavailable(e)),
+                _ => Ok(()),
+            };
+        }
 
-        self.cache.insert(kid.to_string(), key_data.clone()).await;
-        Ok(key_data)
+        match self.refresh().await {
+            Ok(_) => Ok(()),
+            Err(e) if !has_keys => Err(AuthError::KeysUnavailable(e)),
+            Err(e) => {
+                warn!("JWKS refetch failed, keeping previous keys: {e}");
+                Ok(())
+            }
+        }
     }
 
-    pub async fn validate_token(
-        &self,
-        token: &str,
-    ) -> Result<TokenData<super::jwt::Claims>, String> {
-        let header = decode_header(token).map_err(|e| format!("Failed to decode header: {e}"))?;
-        let kid = header.kid.ok_or("No 'kid' in token header")?;
+    async fn get_key_data(&self, kid: &str) -> Result<JwksKeyData, AuthError> {
+        if let Some(key) = self.cached_key(kid) {
+            return Ok(key);
+        }
+
+        if self.unknown_kids.contains_key(kid) {
+            return Err(AuthError::UnknownKey(kid.to_string()));
+        }
 
-        let key_data = self.get_key_data(&kid).await?;
+        // Possibly a rotation: refresh the key set (rate-limited) and look again.
+        self.refetch_for_unknown_kid().await?;
 
-        let decoding_key = DecodingKey::from_ec_components(&key_data.x, &key_data.y)
-            .map_err(|e| format!("Failed to create decoding key: {e}"))?;
+        if let Some(key) = self.cached_key(kid) {
+            return Ok(key);
+        }
 
-        let mut validation = Validation::new(key_data.algorithm);
-        validation.validate_aud = false;
+        self.unknown_kids.insert(kid.to_string(), ()).await;
+        Err(AuthError::UnknownKey(kid.to_string()))
+    }
+
+    pub async fn validate_token(&self, token: &str) -> Result<Claims, AuthError> {
+        let kid = token_kid(token)?;
+        let key_data = self.get_key_data(&kid).await?;
+        key_data.decode(token, &self.policy)
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenVerifier for JwksCache {
+    async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+        self.validate_token(token).await
+    }
 
-        decode::<super::jwt::Claims>(token, &decoding_key, &validation)
-            .map_err(|e| format!("Token validation failed: {e}"))
+    fn trusted_keys(&self) -> Option<usize> {
+        Some(self.key_count())
     }
 }
@@ -1,4 +1,5 @@
-use crate::auth::jwks::JwksCache;
+use crate::auth::error::AuthError;
+use crate::auth::verifier::TokenVerifier;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -7,7 +8,7 @@ use uuid::Uuid;
 /// Supabase issues JWTs with these standard + custom fields.
 /// The `sub` field is the user's UUID in `auth.users`.
 /// `user_metadata` contains profile info from the OAuth provider (Google).
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     /// The Supabase auth user UUID.
     pub sub: String,
@@ -17,16 +18,32 @@ pub struct Claims {
     pub iat: Option<usize>,
     /// Issuer — should match your Supabase URL + `/auth/v1`.
     pub iss: Option<String>,
+    /// Audience — Supabase sets `"authenticated"` for signed-in users.
+    pub aud: Option<Audience>,
     /// User's email from Supabase auth.
     pub email: Option<String>,
     /// Supabase role (e.g. "authenticated").
     pub role: Option<String>,
     /// Metadata from the OAuth provider.
     pub user_metadata: Option<UserMetadata>,
+    /// Metadata managed by Supabase Auth (sign-in providers).
+    pub app_metadata: Option<AppMetadata>,
+    /// Unique token id, if the issuer sets one.
+    pub jti: Option<String>,
+    /// Supabase auth session the token belongs to.
+    pub session_id: Option<String>,
+}
+
+/// The `aud` claim, which may be a single string or a list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Audience {
+    One(String),
+    Many(Vec<String>),
 }
 
 /// Metadata populated by the OAuth provider (Google).
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserMetadata {
     pub full_name: Option<String>,
     pub name: Option<String>,
@@ -36,6 +53,15 @@ pub struct UserMetadata {
     pub email_verified: Option<bool>,
 }
 
+/// Metadata set by Supabase Auth itself; users cannot edit it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppMetadata {
+    /// Provider of the first sign-in (e.g. `"google"`, `"github"`, `"email"`).
+    pub provider: Option<String>,
+    /// Every provider linked to the account.
+    pub providers: Option<Vec<String>>,
+}
+
 impl Claims {
     /// Extract the user UUID from the `sub` claim.
     pub fn user_id(&self) -> Result<Uuid, String> {
@@ -56,6 +82,24 @@ impl Claims {
             .and_then(|m| m.avatar_url.clone().or_else(|| m.picture.clone()))
     }
 
+    /// Sign-in provider from `app_metadata`, falling back to the first linked
+    /// provider and then to `"email"` (Supabase's default).
+    pub fn auth_provider(&self) -> String {
+        self.app_metadata
+            .as_ref()
+            .and_then(|m| {
+                m.provider
+                    .clone()
+                    .or_else(|| m.providers.as_ref().and_then(|p| p.first().cloned()))
+            })
+            .unwrap_or_else(|| "email".to_string())
+    }
+
+    /// Identifier used to revoke this token on its own: `jti`, else `session_id`.
+    pub fn revocation_id(&self) -> Option<&str> {
+        self.jti.as_deref().or(self.session_id.as_deref())
+    }
+
     /// Best-effort email: prefer top-level, fall back to metadata.
     pub fn user_email(&self) -> Option<String> {
         self.email
@@ -66,7 +110,11 @@ impl Claims {
 
 /// Validate a Supabase JWT and return the decoded claims.
 ///
-/// Supabase signs JWTs with HS256 using the project's JWT secret.
-pub async fn validate_token(token: &str, jwks_cache: &JwksCache) -> Result<Claims, String> {
-    jwks_cache.validate_token(token).await.map(|td| td.claims)
+/// Depending on the project, Supabase signs JWTs with the shared HS256 secret or
+/// with asymmetric keys published as a JWKS; `verifier` encapsulates which.
+pub async fn validate_token(
+    token: &str,
+    verifier: &dyn TokenVerifier,
+) -> Result<Claims, AuthError> {
+    verifier.verify(token).await
 }
@@ -1,17 +1,104 @@
-use actix_web::FromRequest;
 use actix_web::{Error, HttpRequest, dev::Payload, web};
+use actix_web::{FromRequest, HttpMessage};
 use sea_orm::DatabaseConnection;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use uuid::Uuid;
 
-use crate::auth::jwks::JwksCache;
-use crate::auth::jwt;
+use crate::auth::error::AuthError;
+use crate::auth::jwt::{self, Claims};
+use crate::auth::revocation;
+use crate::auth::user_cache::UserCacheData;
+use crate::auth::verifier::VerifierData;
+use crate::cache::{CacheStore, keys};
 use crate::db::users::find_or_create_from_auth;
-use crate::models::users::{self, CreateUserFromAuth, Roles};
+use crate::error::ApiError;
+use crate::middleware::request_id;
+use crate::models::users::{self, AccountStatus, CreateUserFromAuth, Roles};
 
+/// The user behind a valid bearer token.
+///
+/// The token's [`Claims`] are also stored in the request extensions for
+/// handlers that need them (e.g. logout).
 pub struct AuthenticatedUser(pub users::Model);
 
+/// Extract the token from an `Authorization: Bearer <token>` header.
+pub fn bearer_token(req: &HttpRequest) -> Result<&str, AuthError> {
+    let auth_header = req
+        .headers()
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .ok_or(AuthError::MissingToken)?;
+
+    auth_header
+        .strip_prefix("Bearer ")
+        .ok_or(AuthError::InvalidScheme)
+}
+
+/// A token whose signature and claims were already checked for this request.
+struct ValidatedToken {
+    token: String,
+    claims: Claims,
+}
+
+/// Check `token` with the configured verifier, at most once per request: the
+/// rate limiter and the extractors both need its claims.
+pub async fn validate_once(req: &HttpRequest, token: &str) -> Result<Claims, AuthError> {
+    if let Some(validated) = req.extensions().get::<ValidatedToken>()
+        && validated.token == token
+    {
+        return Ok(validated.claims.clone());
+    }
+
+    let verifier = req
+        .app_data::<web::Data<VerifierData>>()
+        .ok_or_else(|| AuthError::Internal("Token verifier not configured".to_string()))?;
+    let claims = jwt::validate_token(token, verifier.get_ref().as_ref()).await?;
+    req.extensions_mut().insert(ValidatedToken {
+        token: token.to_string(),
+        claims: claims.clone(),
+    });
+    Ok(claims)
+}
+
+/// Validate a raw JWT with the configured verifier, resolve its subject and
+/// reject tokens revoked by logout or by an admin.
+///
+/// Shared by the REST extractor and the WebSocket handshake so both enforce the
+/// same validation policy and report the same error codes.
+pub async fn verify_token(req: &HttpRequest, token: &str) -> Result<(Uuid, Claims), AuthError> {
+    let claims = validate_once(req, token).await?;
+    let user_id = claims.user_id().map_err(AuthError::InvalidSubject)?;
+    request_id::record_user(user_id);
+
+    if let Some(cache) = req.app_data::<web::Data<Arc<CacheStore>>>() {
+        revocation::check(cache, user_id, &claims).await?;
+    }
+
+    Ok((user_id, claims))
+}
+
+/// Reject suspended and banned accounts.
+///
+/// Checked on every request rather than only at login, so a suspension takes
+/// effect immediately for tokens that are still valid.
+pub fn ensure_active(user: &users::Model) -> Result<(), AuthError> {
+    if user.is_active() {
+        return Ok(());
+    }
+
+    match user.status {
+        AccountStatus::Banned => Err(AuthError::AccountBanned {
+            reason: user.status_reason.clone(),
+        }),
+        _ => Err(AuthError::AccountSuspended {
+            until: user.suspended_until,
+            reason: user.status_reason.clone(),
+        }),
+    }
+}
+
 impl FromRequest for AuthenticatedUser {
     type Error = Error;
     type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
@@ -21,66 +108,54 @@ impl FromRequest for AuthenticatedUser {
 
         Box::pin(async move {
             // 1. Extract the Bearer token from the Authorization header.
-            let auth_header = req
-                .headers()
-                .get("Authorization")
-                .and_then(|v| v.to_str().ok())
-                .ok_or_else(|| {
-                    actix_web::error::ErrorUnauthorized("Missing Authorization header")
-                })?;
-
-            let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
-                actix_web::error::ErrorUnauthorized("Authorization header must be: Bearer <token>")
-            })?;
-
-            // 2. Get JWKS cache from app data
-            let jwks_cache = req.app_data::<web::Data<Arc<JwksCache>>>().ok_or_else(|| {
-                actix_web::error::ErrorInternalServerError("JWKS cache not configured")
-            })?;
-
-            // 3. Validate the JWT using JWKS
-            let claims = jwt::validate_token(token, jwks_cache.get_ref())
-                .await
-                .map_err(|e| actix_web::error::ErrorUnauthorized(format!("Invalid token: {e}")))?;
-
-            // 4. Extract user info from claims.
-            let user_id = claims
-                .user_id()
-                .map_err(actix_web::error::ErrorUnauthorized)?;
-
-            let email = claims
-                .user_email()
-                .ok_or_else(|| actix_web::error::ErrorUnauthorized("No email in token claims"))?;
-
-            // 5. Get the database connection.
+            let token = bearer_token(&req)?;
+
+            // 2. Validate the JWT and extract the user id.
+            let (user_id, claims) = verify_token(&req, token).await?;
+
+            // 3. Serve recently seen users from the user cache, if configured.
+            let user_cache = req.app_data::<web::Data<UserCacheData>>().cloned();
+            if let Some(user_cache) = &user_cache
+                && let Some(user) = user_cache.get(user_id).await
+            {
+                ensure_active(&user)?;
+                req.extensions_mut().insert(claims);
+                return Ok(AuthenticatedUser(user));
+            }
+
+            // 4. Get the database connection.
             let db = req
                 .app_data::<web::Data<DatabaseConnection>>()
-                .ok_or_else(|| {
-                    actix_web::error::ErrorInternalServerError("Database not configured")
-                })?;
+                .ok_or_else(|| ApiError::Internal("Database not configured".to_string()))?;
 
-            // 6. Find or create the user.
-            let user = find_or_create_from_auth(
+            // 5. Find or create the user, syncing the profile from the claims.
+            let (user, changed) = find_or_create_from_auth(
                 db.get_ref(),
                 CreateUserFromAuth {
                     id: user_id,
-                    email,
+                    email: claims.user_email(),
                     display_name: claims.display_name(),
                     avatar_url: claims.avatar_url(),
-                    auth_provider: "google".to_string(),
+                    auth_provider: claims.auth_provider(),
                     role: Roles::Client, // default role for new users
                 },
             )
             .await
-            .map_err(|e| {
-                actix_web::error::ErrorInternalServerError(format!("Database error: {e}"))
-            })?;
+            .map_err(ApiError::from)?;
+
+            // 6. Drop the cached profile if the sync changed it, and remember the user.
+            if changed && let Some(cache) = req.app_data::<web::Data<Arc<CacheStore>>>() {
+                let _ = cache.delete(&keys::user(&user_id.to_string())).await;
+            }
+            if let Some(user_cache) = &user_cache {
+                user_cache.insert(&user).await;
+            }
+
+            // 7. Reject suspended or banned accounts.
+            ensure_active(&user)?;
+            req.extensions_mut().insert(claims);
 
             Ok(AuthenticatedUser(user))
         })
     }
 }
-
-/// Wrapper type to store the JWT secret in Actix app data.
-#[derive(Clone)]
-pub struct JwtSecret(pub String);
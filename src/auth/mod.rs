@@ -1,4 +1,13 @@
+pub mod api_keys;
 pub mod authorization;
+pub mod error;
 pub mod jwks;
 pub mod jwt;
 pub mod middleware;
+pub mod policy;
+pub mod principal;
+pub mod revocation;
+pub mod roles;
+pub mod user_cache;
+pub mod validation;
+pub mod verifier;
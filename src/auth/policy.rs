@@ -0,0 +1,227 @@
+use uuid::Uuid;
+
+use crate::models::contracts::Status;
+use crate::models::users::{self, Roles};
+
+/// The user an authorization decision is made for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Actor {
+    pub id: Uuid,
+    pub role: Roles,
+}
+
+impl From<&users::Model> for Actor {
+    fn from(user: &users::Model) -> Self {
+        Self {
+            id: user.id,
+            role: user.role.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Read,
+    Create,
+    Update,
+    Delete,
+}
+
+/// Who is involved in a contract: the client who sent it and the freelancer who owns the gig.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractParties {
+    pub client_id: Uuid,
+    pub freelancer_id: Uuid,
+    pub status: Status,
+}
+
+impl ContractParties {
+    pub fn includes(&self, user_id: Uuid) -> bool {
+        self.client_id == user_id || self.freelancer_id == user_id
+    }
+}
+
+/// What an action targets, with the ownership facts needed to decide.
+///
+/// Handlers load these from the database (see [`crate::auth::authorization`]);
+/// the policy itself never does I/O.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resource {
+    /// A user account.
+    User {
+        id: Uuid,
+    },
+    /// A role being assigned to an account (`Update`).
+    Role(Roles),
+    Gig {
+        owner_id: Uuid,
+    },
+    /// Every gig of a user, for bulk deletion.
+    UserGigs {
+        owner_id: Uuid,
+    },
+    Portfolio {
+        freelancer_id: Uuid,
+    },
+    ApiKey {
+        owner_id: Uuid,
+    },
+    /// A contract. `Create` is a client sending a request, `Update` is the
+    /// freelancer accepting or rejecting it and `Delete` is the client withdrawing it.
+    Contract(ContractParties),
+    /// Every contract on one gig.
+    GigContracts {
+        owner_id: Uuid,
+    },
+    /// Every contract sent by one client.
+    UserContracts {
+        client_id: Uuid,
+    },
+    /// A contract's chat room: `Read` is history and joining, `Create` is sending.
+    Chat(ContractParties),
+    /// A chat message; `Update` marks it as read.
+    Message {
+        sender_id: Uuid,
+        contract: ContractParties,
+    },
+}
+
+/// Why an action was refused. The reason is safe to show to the caller.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{reason}")]
+pub struct Denied {
+    pub reason: &'static str,
+}
+
+impl Denied {
+    fn because(reason: &'static str) -> Result<(), Self> {
+        Err(Self { reason })
+    }
+}
+
+/// Ownership and role rules for every user-facing resource.
+///
+/// Admin moderation under `/api/admin` is guarded by `RequireRole<Admin>` and
+/// does not go through these rules; an admin acting on the regular routes is
+/// treated like any other user.
+pub struct Policy;
+
+impl Policy {
+    pub fn can(actor: &Actor, action: Action, resource: &Resource) -> Result<(), Denied> {
+        use Action::*;
+
+        let allow_if = |allowed: bool, reason| {
+            if allowed {
+                Ok(())
+            } else {
+                Denied::because(reason)
+            }
+        };
+
+        match (resource, action) {
+            (Resource::User { .. }, Read) => Ok(()),
+            (Resource::User { id }, Update) => {
+                allow_if(actor.id == *id, "You can only update your own account")
+            }
+            (Resource::User { id }, Delete) => {
+                allow_if(actor.id == *id, "You can only delete your own account")
+            }
+
+            (Resource::Role(role), Update) => allow_if(
+                *role != Roles::Admin || actor.role == Roles::Admin,
+                "You cannot assign the admin role",
+            ),
+
+            (Resource::Gig { .. }, Read) => Ok(()),
+            (Resource::Gig { owner_id }, Create) => allow_if(
+                actor.id == *owner_id,
+                "You can only create gigs for your own account",
+            ),
+            (Resource::Gig { owner_id }, Update | Delete) => {
+                allow_if(actor.id == *owner_id, "You do not own this gig")
+            }
+            (Resource::UserGigs { .. }, Read) => Ok(()),
+            (Resource::UserGigs { owner_id }, Delete) => {
+                allow_if(actor.id == *owner_id, "You can only delete your own gigs")
+            }
+
+            (Resource::Portfolio { .. }, Read) => Ok(()),
+            (Resource::Portfolio { freelancer_id }, Create) => allow_if(
+                actor.id == *freelancer_id,
+                "You can only create portfolio items for your own account",
+            ),
+            (Resource::Portfolio { freelancer_id }, Update) => allow_if(
+                actor.id == *freelancer_id,
+                "You can only update your own portfolio items",
+            ),
+            (Resource::Portfolio { freelancer_id }, Delete) => allow_if(
+                actor.id == *freelancer_id,
+                "You can only delete your own portfolio items",
+            ),
+
+            (Resource::ApiKey { owner_id }, _) => {
+                allow_if(actor.id == *owner_id, "You do not own this API key")
+            }
+
+            (Resource::Contract(parties), Read) => allow_if(
+                parties.includes(actor.id),
+                "You can only view contracts you are involved in",
+            ),
+            (Resource::Contract(parties), Create) => {
+                allow_if(
+                    actor.id == parties.client_id,
+                    "You can only send contract requests as yourself",
+                )?;
+                allow_if(
+                    parties.client_id != parties.freelancer_id,
+                    "You cannot create a contract on your own gig",
+                )
+            }
+            (Resource::Contract(parties), Update) => allow_if(
+                actor.id == parties.freelancer_id,
+                "Only the gig owner (freelancer) can accept or reject contracts",
+            ),
+            (Resource::Contract(parties), Delete) => allow_if(
+                actor.id == parties.client_id,
+                "You can only withdraw your own contract requests",
+            ),
+            (Resource::GigContracts { owner_id }, Read) => allow_if(
+                actor.id == *owner_id,
+                "Only the gig owner can view contracts for this gig",
+            ),
+            (Resource::UserContracts { client_id }, Read) => allow_if(
+                actor.id == *client_id,
+                "You can only view your own contracts",
+            ),
+
+            (Resource::Chat(parties), Read | Create) => Self::can_chat(actor, parties),
+            (Resource::Message { contract, .. }, Read) => Self::can_chat(actor, contract),
+            (
+                Resource::Message {
+                    sender_id,
+                    contract,
+                },
+                Update,
+            ) => {
+                Self::can_chat(actor, contract)?;
+                allow_if(
+                    actor.id != *sender_id,
+                    "You cannot mark your own message as read",
+                )
+            }
+
+            _ => Denied::because("This action is not permitted"),
+        }
+    }
+
+    /// Chat is open to both parties, and only once the contract is accepted.
+    fn can_chat(actor: &Actor, parties: &ContractParties) -> Result<(), Denied> {
+        if parties.status != Status::Accepted {
+            return Denied::because("Chat is only available for accepted contracts");
+        }
+        if !parties.includes(actor.id) {
+            return Denied::because("You are not a party to this contract");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use actix_web::{Error, FromRequest, HttpRequest, dev::Payload, web};
+use sea_orm::DatabaseConnection;
+use std::future::Future;
+use std::pin::Pin;
+use uuid::Uuid;
+
+use crate::auth::api_keys::{KEY_PREFIX, hash_key};
+use crate::auth::error::AuthError;
+use crate::auth::middleware::{AuthenticatedUser, ensure_active};
+use crate::auth::user_cache::{UserCache, UserCacheData};
+use crate::db::api_keys as api_key_db;
+use crate::db::users as user_db;
+use crate::error::ApiError;
+use crate::middleware::request_id;
+use crate::models::api_keys::ApiScope;
+use crate::models::users;
+
+/// How a [`Principal`] authenticated.
+#[derive(Debug, Clone)]
+pub enum Credential {
+    /// A Supabase session JWT; holds every scope.
+    Session,
+    /// An API key limited to `scopes`.
+    ApiKey { key_id: Uuid, scopes: Vec<ApiScope> },
+}
+
+/// An authenticated caller, accepting either `Authorization: Bearer <jwt>` or
+/// `Authorization: ApiKey <key>`.
+///
+/// Use it instead of [`AuthenticatedUser`] on routes that API keys may call, and
+/// check the scope the route needs with [`Principal::require`].
+pub struct Principal(pub users::Model, pub Credential);
+
+impl Principal {
+    /// Fail with `403 insufficient_scope` unless the credential grants `scope`.
+    pub fn require(&self, scope: ApiScope) -> Result<(), AuthError> {
+        match &self.1 {
+            Credential::Session => Ok(()),
+            Credential::ApiKey { scopes, .. } if scopes.contains(&scope) => Ok(()),
+            Credential::ApiKey { .. } => Err(AuthError::MissingScope(scope.as_str().to_string())),
+        }
+    }
+}
+
+/// The key of an `Authorization: ApiKey <key>` header, if the request has one.
+pub fn api_key_header(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("ApiKey "))
+        .map(str::trim)
+}
+
+/// Only refresh `last_used_at` this often, to avoid a write per request.
+const TOUCH_INTERVAL_SECS: i64 = 60;
+
+/// Resolve an `ApiKey` credential to its owner.
+async fn authenticate_api_key(
+    db: &DatabaseConnection,
+    user_cache: Option<&UserCache>,
+    key: &str,
+) -> Result<(users::Model, Credential), Error> {
+    if !key.starts_with(KEY_PREFIX) {
+        return Err(AuthError::InvalidApiKey.into());
+    }
+
+    let api_key = api_key_db::get_api_key_by_hash(db, &hash_key(key))
+        .await
+        .map_err(ApiError::from)?
+        .ok_or(AuthError::InvalidApiKey)?;
+
+    if api_key.revoked_at.is_some() {
+        return Err(AuthError::ApiKeyRevoked.into());
+    }
+    if api_key.is_expired() {
+        return Err(AuthError::ApiKeyExpired.into());
+    }
+
+    let cached = match user_cache {
+        Some(user_cache) => user_cache.get(api_key.user_id).await,
+        None => None,
+    };
+    let user = match cached {
+        Some(user) => user,
+        None => {
+            let user = user_db::get_user_by_id(db, api_key.user_id)
+                .await
+                .map_err(ApiError::from)?
+                .ok_or(AuthError::InvalidApiKey)?;
+            if let Some(user_cache) = user_cache {
+                user_cache.insert(&user).await;
+            }
+            user
+        }
+    };
+    ensure_active(&user)?;
+
+    let stale = api_key
+        .last_used_at
+        .is_none_or(|at| chrono::Utc::now() - at > chrono::Duration::seconds(TOUCH_INTERVAL_SECS));
+    if stale {
+        let db = db.clone();
+        let key_id = api_key.id;
+        actix_web::rt::spawn(async move {
+            if let Err(e) = api_key_db::touch_api_key(&db, key_id).await {
+                tracing::warn!("Failed to update last_used_at of API key {key_id}: {e}");
+            }
+        });
+    }
+
+    let credential = Credential::ApiKey {
+        key_id: api_key.id,
+        scopes: api_key.scope_list(),
+    };
+    Ok((user, credential))
+}
+
+impl FromRequest for Principal {
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let api_key = api_key_header(req).map(str::to_string);
+
+        // Anything that is not an API key goes through the JWT extractor.
+        let Some(api_key) = api_key else {
+            let authenticated = AuthenticatedUser::from_request(req, payload);
+            return Box::pin(async move {
+                let AuthenticatedUser(user) = authenticated.await?;
+                Ok(Principal(user, Credential::Session))
+            });
+        };
+
+        let db = req.app_data::<web::Data<DatabaseConnection>>().cloned();
+        let user_cache = req.app_data::<web::Data<UserCacheData>>().cloned();
+        Box::pin(async move {
+            let db = db.ok_or_else(|| ApiError::Internal("Database not configured".to_string()))?;
+            let user_cache = user_cache.as_ref().map(|c| c.get_ref().as_ref());
+            let (user, credential) =
+                authenticate_api_key(db.get_ref(), user_cache, &api_key).await?;
+            request_id::record_user(user.id);
+            Ok(Principal(user, credential))
+        })
+    }
+}
@@ -0,0 +1,77 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::auth::error::AuthError;
+use crate::auth::jwt::Claims;
+use crate::cache::{CacheResult, CacheStore, keys};
+
+/// Extra time a revoked token id is remembered past `exp`, covering clock-skew leeway.
+const REVOKED_TOKEN_GRACE_SECS: u64 = 300;
+
+/// Reject tokens revoked by logout or by a "revoke all sessions" cutoff.
+///
+/// Redis errors are logged and the token is accepted, like every other cache
+/// lookup in the app; revocation is a best-effort layer on top of `exp`.
+pub async fn check(cache: &CacheStore, user_id: Uuid, claims: &Claims) -> Result<(), AuthError> {
+    if let Some(id) = claims.revocation_id() {
+        match cache.exists(&keys::revoked_token(id)).await {
+            Ok(true) => return Err(AuthError::TokenRevoked),
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Cache error: {}", e),
+        }
+    }
+
+    match cache
+        .get::<i64>(&keys::revoked_before(&user_id.to_string()))
+        .await
+    {
+        Ok(Some(cutoff)) if issued_before_cutoff(claims.iat, cutoff) => {
+            Err(AuthError::TokenRevoked)
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::warn!("Cache error: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Whether a token issued at `iat` falls under a cutoff set at `cutoff` (both Unix seconds).
+///
+/// The comparison is strict: `iat` only has one-second granularity, so a token
+/// issued in the same second as the cutoff, such as one from signing in again
+/// right after "revoke all sessions", stays valid. Tokens without `iat` cannot
+/// prove they are newer, so they are treated as revoked.
+pub fn issued_before_cutoff(iat: Option<usize>, cutoff: i64) -> bool {
+    iat.is_none_or(|iat| (iat as i64) < cutoff)
+}
+
+/// Revoke a single token (by `jti`, or `session_id` for Supabase tokens) until it expires.
+///
+/// Returns `false` if the token carries neither claim and so cannot be revoked on its own.
+pub async fn revoke_token(cache: &CacheStore, claims: &Claims) -> CacheResult<bool> {
+    let Some(id) = claims.revocation_id() else {
+        return Ok(false);
+    };
+
+    let remaining = (claims.exp as i64 - Utc::now().timestamp()).max(0) as u64;
+    cache
+        .set(
+            &keys::revoked_token(id),
+            &true,
+            Some(remaining + REVOKED_TOKEN_GRACE_SECS),
+        )
+        .await?;
+    Ok(true)
+}
+
+/// Revoke every token issued to `user_id` up to now.
+pub async fn revoke_all(cache: &CacheStore, user_id: Uuid) -> CacheResult<()> {
+    cache
+        .set(
+            &keys::revoked_before(&user_id.to_string()),
+            &Utc::now().timestamp(),
+            None,
+        )
+        .await
+}
@@ -0,0 +1,113 @@
+use actix_web::{Error, FromRequest, HttpRequest, dev::Payload};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+use crate::auth::error::AuthError;
+use crate::auth::middleware::AuthenticatedUser;
+use crate::models::users::{self, Roles};
+
+/// Type-level marker for a single [`Roles`] variant.
+pub trait RoleMarker {
+    const ROLE: Roles;
+}
+
+pub struct Client;
+pub struct Freelancer;
+pub struct Admin;
+
+impl RoleMarker for Client {
+    const ROLE: Roles = Roles::Client;
+}
+
+impl RoleMarker for Freelancer {
+    const ROLE: Roles = Roles::Freelancer;
+}
+
+impl RoleMarker for Admin {
+    const ROLE: Roles = Roles::Admin;
+}
+
+/// A set of accepted roles: a single marker (`Admin`) or a tuple of markers
+/// (`(Freelancer, Admin)`).
+pub trait RoleSet {
+    fn roles() -> Vec<Roles>;
+
+    fn allows(role: &Roles) -> bool {
+        Self::roles().contains(role)
+    }
+}
+
+macro_rules! impl_role_set {
+    ($($marker:ident),+) => {
+        impl<$($marker: RoleMarker),+> RoleSet for ($($marker,)+) {
+            fn roles() -> Vec<Roles> {
+                vec![$($marker::ROLE),+]
+            }
+        }
+    };
+}
+
+impl_role_set!(A);
+impl_role_set!(A, B);
+impl_role_set!(A, B, C);
+
+impl RoleSet for Client {
+    fn roles() -> Vec<Roles> {
+        vec![Self::ROLE]
+    }
+}
+
+impl RoleSet for Freelancer {
+    fn roles() -> Vec<Roles> {
+        vec![Self::ROLE]
+    }
+}
+
+impl RoleSet for Admin {
+    fn roles() -> Vec<Roles> {
+        vec![Self::ROLE]
+    }
+}
+
+/// An authenticated user whose role is one of `R`, e.g.
+/// `RequireAnyRole<(Freelancer, Admin)>`. Other users get `403 insufficient_role`.
+pub struct RequireAnyRole<R: RoleSet>(pub users::Model, PhantomData<R>);
+
+/// An authenticated user with exactly the role `R`, e.g. `RequireRole<Admin>`.
+pub type RequireRole<R> = RequireAnyRole<R>;
+
+impl<R: RoleSet> RequireAnyRole<R> {
+    pub fn into_inner(self) -> users::Model {
+        self.0
+    }
+}
+
+/// Check `role` against `R`, returning the error the extractors respond with.
+pub fn check_role<R: RoleSet>(role: &Roles) -> Result<(), AuthError> {
+    if R::allows(role) {
+        return Ok(());
+    }
+
+    let names = R::roles()
+        .iter()
+        .map(|r| format!("{r:?}").to_lowercase())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(AuthError::InsufficientRole(names))
+}
+
+impl<R: RoleSet + 'static> FromRequest for RequireAnyRole<R> {
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let authenticated = AuthenticatedUser::from_request(req, payload);
+
+        Box::pin(async move {
+            let AuthenticatedUser(user) = authenticated.await?;
+            check_role::<R>(&user.role)?;
+            Ok(RequireAnyRole(user, PhantomData))
+        })
+    }
+}
@@ -0,0 +1,118 @@
+use moka::future::Cache;
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::cache::{CacheStore, keys};
+use crate::config::AppConfig;
+use crate::models::users;
+
+/// Short-lived cache of authenticated users, keyed by the token subject, so
+/// protected requests don't need a `users` lookup each time.
+///
+/// Lookups go to the in-process moka cache first and then, when configured, to
+/// Redis (shared by all instances). Handlers that change a user must call
+/// [`UserCache::invalidate`]; other instances still serve their local copy until
+/// the TTL expires, which is why it is kept short.
+pub struct UserCache {
+    local: Cache<Uuid, users::Model>,
+    redis: Option<Arc<CacheStore>>,
+    ttl: Duration,
+    local_hits: AtomicU64,
+    redis_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Counters reported by [`UserCache::stats`].
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct UserCacheStats {
+    pub local_hits: u64,
+    pub redis_hits: u64,
+    pub misses: u64,
+    pub entries: u64,
+}
+
+impl UserCache {
+    pub fn new(ttl: Duration, capacity: u64, redis: Option<Arc<CacheStore>>) -> Self {
+        Self {
+            local: Cache::builder()
+                .time_to_live(ttl)
+                .max_capacity(capacity)
+                .build(),
+            redis,
+            ttl,
+            local_hits: AtomicU64::new(0),
+            redis_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Build from the `user_cache` settings, sharing entries through `redis`
+    /// when the `user_cache_redis` feature is enabled.
+    pub fn from_config(config: &AppConfig, redis: Arc<CacheStore>) -> Self {
+        Self::new(
+            Duration::from_secs(config.user_cache.ttl_secs),
+            config.user_cache.capacity,
+            config.features.user_cache_redis.then_some(redis),
+        )
+    }
+
+    pub async fn get(&self, id: Uuid) -> Option<users::Model> {
+        if let Some(user) = self.local.get(&id).await {
+            self.local_hits.fetch_add(1, Ordering::Relaxed);
+            return Some(user);
+        }
+
+        if let Some(redis) = &self.redis {
+            match redis
+                .get::<users::Model>(&keys::auth_user(&id.to_string()))
+                .await
+            {
+                Ok(Some(user)) => {
+                    self.redis_hits.fetch_add(1, Ordering::Relaxed);
+                    self.local.insert(id, user.clone()).await;
+                    return Some(user);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Cache error: {}", e),
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    pub async fn insert(&self, user: &users::Model) {
+        self.local.insert(user.id, user.clone()).await;
+
+        if let Some(redis) = &self.redis {
+            let key = keys::auth_user(&user.id.to_string());
+            if let Err(e) = redis.set(&key, user, Some(self.ttl.as_secs())).await {
+                tracing::warn!("Cache error: {}", e);
+            }
+        }
+    }
+
+    /// Drop a user from every layer after their row changed or was deleted.
+    pub async fn invalidate(&self, id: Uuid) {
+        self.local.invalidate(&id).await;
+
+        if let Some(redis) = &self.redis {
+            let _ = redis.delete(&keys::auth_user(&id.to_string())).await;
+        }
+    }
+
+    pub fn stats(&self) -> UserCacheStats {
+        UserCacheStats {
+            local_hits: self.local_hits.load(Ordering::Relaxed),
+            redis_hits: self.redis_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.local.entry_count(),
+        }
+    }
+}
+
+/// Wrapper type for Actix-web app data
+pub type UserCacheData = Arc<UserCache>;
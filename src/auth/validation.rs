@@ -0,0 +1,91 @@
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
+use uuid::Uuid;
+
+use crate::auth::error::AuthError;
+use crate::auth::jwt::Claims;
+
+/// Rules every accepted token must satisfy, independent of how it is signed.
+#[derive(Debug, Clone)]
+pub struct ValidationPolicy {
+    /// Expected `iss` claim. `None` disables the issuer check.
+    pub issuer: Option<String>,
+    /// Accepted `aud` values. Empty disables the audience check.
+    pub audiences: Vec<String>,
+    /// Clock skew tolerated for `exp`/`nbf`, in seconds.
+    pub leeway_secs: u64,
+    /// Reject tokens that carry no email (top-level or in `user_metadata`).
+    pub require_email: bool,
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        Self {
+            issuer: None,
+            audiences: vec!["authenticated".to_string()],
+            leeway_secs: 60,
+            require_email: true,
+        }
+    }
+}
+
+impl ValidationPolicy {
+    /// Policy for tokens issued by the Supabase Auth server at `supabase_url`.
+    pub fn for_supabase(supabase_url: &str) -> Self {
+        Self {
+            issuer: Some(supabase_issuer(supabase_url)),
+            ..Self::default()
+        }
+    }
+
+    /// `jsonwebtoken` validation settings for a token signed with `algorithm`.
+    pub fn validation(&self, algorithm: Algorithm) -> Validation {
+        let mut validation = Validation::new(algorithm);
+        validation.leeway = self.leeway_secs;
+
+        let mut required = vec!["exp", "sub"];
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+            required.push("iss");
+        }
+        if self.audiences.is_empty() {
+            validation.validate_aud = false;
+        } else {
+            validation.set_audience(&self.audiences);
+            required.push("aud");
+        }
+        validation.set_required_spec_claims(&required);
+
+        validation
+    }
+
+    /// Verify `token` with `key` and apply every rule of this policy.
+    pub fn decode(
+        &self,
+        token: &str,
+        key: &DecodingKey,
+        algorithm: Algorithm,
+    ) -> Result<Claims, AuthError> {
+        let claims = decode::<Claims>(token, key, &self.validation(algorithm))?.claims;
+        self.check_claims(&claims)?;
+        Ok(claims)
+    }
+
+    /// Checks on the decoded claims that `jsonwebtoken` does not cover.
+    pub fn check_claims(&self, claims: &Claims) -> Result<(), AuthError> {
+        if claims.sub.is_empty() {
+            return Err(AuthError::MissingSubject);
+        }
+        Uuid::parse_str(&claims.sub).map_err(|e| AuthError::InvalidSubject(e.to_string()))?;
+
+        if self.require_email && claims.user_email().is_none() {
+            return Err(AuthError::MissingEmail);
+        }
+
+        Ok(())
+    }
+}
+
+/// Issuer of tokens minted by the Supabase Auth server at `supabase_url`.
+pub fn supabase_issuer(supabase_url: &str) -> String {
+    format!("{}/auth/v1", supabase_url.trim_end_matches('/'))
+}
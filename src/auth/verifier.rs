@@ -0,0 +1,137 @@
+use jsonwebtoken::{Algorithm, DecodingKey};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::auth::error::AuthError;
+use crate::auth::jwks::{JwksCache, JwksKeyData, parse_jwks, token_kid};
+use crate::auth::jwt::Claims;
+use crate::auth::validation::ValidationPolicy;
+use crate::config::{AuthConfig, VerifierKind};
+
+/// Verifies a bearer token and returns its decoded claims.
+///
+/// The concrete verifier is chosen once at startup (see [`from_config`]) and shared
+/// as app data, so the `AuthenticatedUser` extractor and the WebSocket handshake
+/// authenticate through the same code path regardless of how tokens are signed.
+/// Every implementation applies its [`ValidationPolicy`] before returning claims.
+#[async_trait::async_trait]
+pub trait TokenVerifier: Send + Sync {
+    async fn verify(&self, token: &str) -> Result<Claims, AuthError>;
+
+    /// Number of signing keys currently trusted, or `None` for verifiers that
+    /// don't use a key set (shared secret). Reported by the readiness check.
+    fn trusted_keys(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Wrapper type for Actix-web app data
+pub type VerifierData = Arc<dyn TokenVerifier>;
+
+/// Verifies HS256 tokens signed with the project's shared JWT secret
+/// (legacy Supabase projects, self-hosted instances and tests).
+pub struct HmacVerifier {
+    key: DecodingKey,
+    policy: ValidationPolicy,
+}
+
+impl HmacVerifier {
+    pub fn new(secret: &str, policy: ValidationPolicy) -> Self {
+        Self {
+            key: DecodingKey::from_secret(secret.as_bytes()),
+            policy,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenVerifier for HmacVerifier {
+    async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+        self.policy.decode(token, &self.key, Algorithm::HS256)
+    }
+}
+
+/// Verifies tokens against a JWKS document loaded once from disk, for offline
+/// environments that cannot reach the Supabase JWKS endpoint.
+pub struct StaticJwksVerifier {
+    keys: HashMap<String, JwksKeyData>,
+    policy: ValidationPolicy,
+}
+
+impl StaticJwksVerifier {
+    /// Parse a JWKS document (`{"keys": [...]}`).
+    pub fn from_json(json: &str, policy: ValidationPolicy) -> Result<Self, String> {
+        let jwks: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| format!("Failed to parse JWKS JSON: {e}"))?;
+        let keys = parse_jwks(&jwks)?;
+        if keys.is_empty() {
+            return Err("JWKS contains no usable keys".to_string());
+        }
+
+        Ok(Self { keys, policy })
+    }
+
+    /// Load a JWKS document from a file.
+    pub fn from_file(path: &str, policy: ValidationPolicy) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read JWKS file {path}: {e}"))?;
+        Self::from_json(&json, policy)
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenVerifier for StaticJwksVerifier {
+    async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+        let kid = token_kid(token)?;
+        let key_data = self.keys.get(&kid).ok_or(AuthError::UnknownKey(kid))?;
+        key_data.decode(token, &self.policy)
+    }
+
+    fn trusted_keys(&self) -> Option<usize> {
+        Some(self.keys.len())
+    }
+}
+
+/// Build the verifier selected by `auth.verifier` (`AUTH_VERIFIER`).
+///
+/// - `jwks` (default): fetch keys from the Supabase URL using the anon key,
+///   refreshed in the background per the `jwks_*` settings.
+/// - `hs256`: verify with the shared secret (`SUPABASE_JWT_SECRET`).
+/// - `jwks_file`: verify against the JWKS document at `JWKS_FILE`.
+///
+/// All modes share the [`ValidationPolicy`] built from the `JWT_*` settings.
+/// [`AppConfig::load`](crate::config::AppConfig::load) has already checked that
+/// the settings each mode needs are present.
+pub fn from_config(config: &AuthConfig) -> Result<VerifierData, String> {
+    let policy = config.validation_policy();
+
+    match config.verifier {
+        VerifierKind::Jwks => {
+            let supabase_url = required(&config.supabase_url, "SUPABASE_URL")?;
+            let anon_key = required(&config.supabase_anon_key, "SUPABASE_ANON_KEY")?;
+            let cache = Arc::new(JwksCache::with_settings(
+                supabase_url,
+                anon_key,
+                policy,
+                config.jwks_settings(),
+            ));
+            // Prefetch in the background so the first requests don't wait on it.
+            cache.spawn_refresh();
+            Ok(cache)
+        }
+        VerifierKind::Hs256 => {
+            let secret = required(&config.jwt_secret, "SUPABASE_JWT_SECRET")?;
+            Ok(Arc::new(HmacVerifier::new(secret, policy)))
+        }
+        VerifierKind::JwksFile => {
+            let path = required(&config.jwks_file, "JWKS_FILE")?;
+            Ok(Arc::new(StaticJwksVerifier::from_file(path, policy)?))
+        }
+    }
+}
+
+fn required<'a>(value: &'a Option<String>, name: &str) -> Result<&'a str, String> {
+    value
+        .as_deref()
+        .ok_or_else(|| format!("{name} must be set"))
+}
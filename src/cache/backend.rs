@@ -0,0 +1,75 @@
+use futures_util::stream::BoxStream;
+use std::time::Duration;
+
+/// Errors from a [`CacheBackend`].
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    /// The backend is not connected (e.g. Redis was down at startup and is
+    /// still reconnecting). Callers treat this like any other cache miss.
+    #[error("cache backend unavailable")]
+    Unavailable,
+    #[error("cache backend error: {0}")]
+    Backend(String),
+    #[error("cache serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+impl From<redis::RedisError> for CacheError {
+    fn from(e: redis::RedisError) -> Self {
+        Self::Backend(e.to_string())
+    }
+}
+
+pub type CacheResult<T> = Result<T, CacheError>;
+
+/// Key-value storage behind [`CacheStore`](super::CacheStore).
+///
+/// Values are opaque strings (the store handles JSON); TTLs are in seconds.
+/// Implemented by [`RedisBackend`](super::redis_backend::RedisBackend), shared
+/// by every instance, and [`MemoryBackend`](super::memory::MemoryBackend) for
+/// local development and tests.
+#[async_trait::async_trait]
+pub trait CacheBackend: Send + Sync {
+    /// Short name reported in logs and health checks.
+    fn name(&self) -> &'static str;
+
+    async fn ping(&self) -> CacheResult<()>;
+
+    async fn get(&self, key: &str) -> CacheResult<Option<String>>;
+
+    async fn set(&self, key: &str, value: String, ttl_seconds: Option<u64>) -> CacheResult<()>;
+
+    /// Get a value and delete it atomically, for single-use entries.
+    async fn take(&self, key: &str) -> CacheResult<Option<String>>;
+
+    async fn delete(&self, key: &str) -> CacheResult<()>;
+
+    async fn exists(&self, key: &str) -> CacheResult<bool>;
+
+    /// Set a value and register `key` under each tag (see [`keys::tag`](super::keys::tag)).
+    async fn set_tagged(
+        &self,
+        key: &str,
+        value: String,
+        ttl_seconds: u64,
+        tags: &[String],
+    ) -> CacheResult<()>;
+
+    /// Delete every key registered under any of `tags`; returns how many were removed.
+    async fn invalidate_tags(&self, tags: &[String]) -> CacheResult<u64>;
+
+    /// Take one token from the bucket at `key`, which holds up to `capacity`
+    /// tokens and regains one every `interval`. Returns how long until a token
+    /// is available: zero if one was taken.
+    async fn take_token(
+        &self,
+        key: &str,
+        capacity: u32,
+        interval: Duration,
+    ) -> CacheResult<Duration>;
+
+    async fn publish(&self, channel: &str, payload: String) -> CacheResult<()>;
+
+    /// Messages published on `channel` until the subscription drops.
+    async fn subscribe(&self, channel: &str) -> CacheResult<BoxStream<'static, String>>;
+}
@@ -0,0 +1,86 @@
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Redis pub/sub channel carrying [`Invalidation`]s between instances.
+pub const INVALIDATION_CHANNEL: &str = "cache:invalidate";
+
+/// In-process L1 in front of Redis for values read through
+/// [`CacheStore::cached`](super::CacheStore::cached).
+///
+/// Values are kept as the typed objects the loader produced, so a hit costs
+/// neither a round trip nor a JSON parse. Entries remember their tags so tag
+/// invalidations can be applied locally. The TTL is meant to be short: an
+/// instance that misses an invalidation message serves its copy until then.
+pub struct LocalCache {
+    entries: Cache<String, LocalEntry>,
+}
+
+#[derive(Clone)]
+struct LocalEntry {
+    value: Arc<dyn Any + Send + Sync>,
+    tags: Arc<[String]>,
+}
+
+/// A change published on [`INVALIDATION_CHANNEL`] so every instance drops its copy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Invalidation {
+    Key(String),
+    Tags(Vec<String>),
+}
+
+impl LocalCache {
+    pub fn new(ttl: Duration, capacity: u64) -> Self {
+        Self {
+            entries: Cache::builder()
+                .time_to_live(ttl)
+                .max_capacity(capacity)
+                .support_invalidation_closures()
+                .build(),
+        }
+    }
+
+    /// Cached value for `key`, if present and stored with type `T`.
+    pub async fn get<T: Clone + 'static>(&self, key: &str) -> Option<T> {
+        let entry = self.entries.get(key).await?;
+        entry.value.downcast_ref::<T>().cloned()
+    }
+
+    pub async fn insert<T: Send + Sync + 'static>(&self, key: &str, value: T, tags: &[String]) {
+        let entry = LocalEntry {
+            value: Arc::new(value),
+            tags: tags.into(),
+        };
+        self.entries.insert(key.to_string(), entry).await;
+    }
+
+    /// Drop the entries named by `invalidation`.
+    pub async fn apply(&self, invalidation: &Invalidation) {
+        match invalidation {
+            Invalidation::Key(key) => self.entries.invalidate(key).await,
+            Invalidation::Tags(tags) => {
+                let tags = tags.clone();
+                let dropped = self.entries.invalidate_entries_if(move |_, entry| {
+                    entry.tags.iter().any(|t| tags.contains(t))
+                });
+                if let Err(e) = dropped {
+                    // Only fails without invalidation closure support; clear everything instead.
+                    tracing::warn!("Local cache tag invalidation failed: {e}");
+                    self.clear();
+                }
+            }
+        }
+    }
+
+    /// Drop everything, e.g. after invalidation messages may have been missed.
+    pub fn clear(&self) {
+        self.entries.invalidate_all();
+    }
+
+    pub fn entry_count(&self) -> u64 {
+        self.entries.entry_count()
+    }
+}
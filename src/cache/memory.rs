@@ -0,0 +1,206 @@
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use super::backend::{CacheBackend, CacheResult};
+
+/// Entries written between sweeps of expired keys.
+const SWEEP_EVERY: usize = 1024;
+
+/// In-process cache for local development, tests and single-instance
+/// deployments without Redis. Nothing is shared between processes.
+pub struct MemoryBackend {
+    state: Mutex<State>,
+    channels: Mutex<HashMap<String, broadcast::Sender<String>>>,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<String, Entry>,
+    tags: HashMap<String, HashSet<String>>,
+    /// Token buckets by key: when the bucket will be full again.
+    buckets: HashMap<String, Instant>,
+    writes: usize,
+}
+
+struct Entry {
+    value: String,
+    expires_at: Option<Instant>,
+}
+
+impl Entry {
+    fn is_live(&self, now: Instant) -> bool {
+        self.expires_at.is_none_or(|at| at > now)
+    }
+}
+
+impl State {
+    fn get(&mut self, key: &str) -> Option<&Entry> {
+        let now = Instant::now();
+        if self.entries.get(key).is_some_and(|e| !e.is_live(now)) {
+            self.entries.remove(key);
+        }
+        self.entries.get(key)
+    }
+
+    fn insert(&mut self, key: &str, value: String, ttl_seconds: Option<u64>) {
+        let now = Instant::now();
+        self.count_write(now);
+        let expires_at = ttl_seconds.map(|ttl| now + Duration::from_secs(ttl));
+        self.entries
+            .insert(key.to_string(), Entry { value, expires_at });
+    }
+
+    /// Drop expired entries, tags and buckets every [`SWEEP_EVERY`] writes.
+    fn count_write(&mut self, now: Instant) {
+        self.writes += 1;
+        if self.writes % SWEEP_EVERY != 0 {
+            return;
+        }
+        self.entries.retain(|_, e| e.is_live(now));
+        self.buckets.retain(|_, full_at| *full_at > now);
+        let entries = &self.entries;
+        self.tags.retain(|_, keys| {
+            keys.retain(|k| entries.contains_key(k));
+            !keys.is_empty()
+        });
+    }
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(State::default()),
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn channel(&self, name: &str) -> broadcast::Sender<String> {
+        self.channels
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_insert_with(|| broadcast::channel(1024).0)
+            .clone()
+    }
+}
+
+impl Default for MemoryBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheBackend for MemoryBackend {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn ping(&self) -> CacheResult<()> {
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> CacheResult<Option<String>> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state.get(key).map(|e| e.value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: String, ttl_seconds: Option<u64>) -> CacheResult<()> {
+        self.state.lock().unwrap().insert(key, value, ttl_seconds);
+        Ok(())
+    }
+
+    async fn take(&self, key: &str) -> CacheResult<Option<String>> {
+        let mut state = self.state.lock().unwrap();
+        if state.get(key).is_none() {
+            return Ok(None);
+        }
+        Ok(state.entries.remove(key).map(|e| e.value))
+    }
+
+    async fn delete(&self, key: &str) -> CacheResult<()> {
+        self.state.lock().unwrap().entries.remove(key);
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> CacheResult<bool> {
+        Ok(self.state.lock().unwrap().get(key).is_some())
+    }
+
+    async fn set_tagged(
+        &self,
+        key: &str,
+        value: String,
+        ttl_seconds: u64,
+        tags: &[String],
+    ) -> CacheResult<()> {
+        let mut state = self.state.lock().unwrap();
+        state.insert(key, value, Some(ttl_seconds));
+        for tag in tags {
+            state
+                .tags
+                .entry(tag.clone())
+                .or_default()
+                .insert(key.to_string());
+        }
+        Ok(())
+    }
+
+    async fn invalidate_tags(&self, tags: &[String]) -> CacheResult<u64> {
+        let mut state = self.state.lock().unwrap();
+        let mut deleted = 0;
+        for tag in tags {
+            for key in state.tags.remove(tag).unwrap_or_default() {
+                if state.entries.remove(&key).is_some() {
+                    deleted += 1;
+                }
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn take_token(
+        &self,
+        key: &str,
+        capacity: u32,
+        interval: Duration,
+    ) -> CacheResult<Duration> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        // Same GCRA as the Redis backend: each token pushes `full_at` one
+        // interval further, and a bucket `capacity` intervals out is empty.
+        let full_at = state.buckets.get(key).copied().unwrap_or(now).max(now) + interval;
+        let allowed_at = full_at.checked_sub(interval * capacity).unwrap_or(now);
+        if allowed_at > now {
+            return Ok(allowed_at - now);
+        }
+        state.count_write(now);
+        state.buckets.insert(key.to_string(), full_at);
+        Ok(Duration::ZERO)
+    }
+
+    async fn publish(&self, channel: &str, payload: String) -> CacheResult<()> {
+        // No subscribers is not an error, same as Redis.
+        let _ = self.channel(channel).send(payload);
+        Ok(())
+    }
+
+    async fn subscribe(&self, channel: &str) -> CacheResult<BoxStream<'static, String>> {
+        let receiver = self.channel(channel).subscribe();
+        let messages = futures_util::stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(msg) => return Some((msg, receiver)),
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+        Ok(messages.boxed())
+    }
+}
@@ -1,39 +1,240 @@
-use redis::{Client, RedisError, aio::ConnectionManager};
+pub mod backend;
+pub mod local;
+pub mod memory;
+pub mod redis_backend;
+pub mod single_flight;
+
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
 use serde::{Serialize, de::DeserializeOwned};
+use std::future::Future;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
+pub use backend::{CacheBackend, CacheError, CacheResult};
+use local::{INVALIDATION_CHANNEL, Invalidation, LocalCache};
+use memory::MemoryBackend;
+use single_flight::SingleFlight;
+
+use crate::metrics::metrics;
+
+/// Typed cache used by handlers: JSON (de)serialization, cache-aside reads
+/// and the optional in-process L1, on top of any [`CacheBackend`].
 #[derive(Clone)]
-pub struct RedisCache {
-    connection: ConnectionManager,
+pub struct CacheStore {
+    backend: Arc<dyn CacheBackend>,
+    flights: Arc<SingleFlight>,
+    local: Option<Arc<LocalCache>>,
+    counters: Arc<CacheCounters>,
+}
+
+#[derive(Default)]
+struct CacheCounters {
+    local_hits: AtomicU64,
+    backend_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Read-through counters reported by [`CacheStore::stats`].
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct CacheStats {
+    pub backend: &'static str,
+    pub local_enabled: bool,
+    pub local_hits: u64,
+    pub backend_hits: u64,
+    pub misses: u64,
+    /// Share of reads answered by the in-process L1.
+    pub local_hit_ratio: f64,
+    /// Share of reads answered by the backend after missing L1.
+    pub backend_hit_ratio: f64,
+    pub local_entries: u64,
 }
 
-impl RedisCache {
-    pub async fn new(redis_url: &str) -> Result<Self, RedisError> {
-        let client = Client::open(redis_url)?;
-        let connection = ConnectionManager::new(client).await?;
-        Ok(Self { connection })
+impl CacheStore {
+    pub fn new(backend: Arc<dyn CacheBackend>) -> Self {
+        Self {
+            backend,
+            flights: Arc::new(SingleFlight::new()),
+            local: None,
+            counters: Arc::new(CacheCounters::default()),
+        }
     }
 
-    /// Get a value from cache
-    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> redis::RedisResult<Option<T>> {
-        let mut conn = self.connection.clone();
-        let value: Option<String> = redis::cmd("GET")
-            .arg(key)
-            .query_async(&mut conn)
-            .await?;
-
-        match value {
-            Some(v) => {
-                let deserialized = serde_json::from_str(&v).map_err(|e| {
-                    redis::RedisError::from((
-                        redis::ErrorKind::TypeError,
-                        "Deserialization error",
-                        e.to_string(),
-                    ))
-                })?;
-                Ok(Some(deserialized))
+    /// A store backed by a fresh [`MemoryBackend`].
+    pub fn memory() -> Self {
+        Self::new(Arc::new(MemoryBackend::new()))
+    }
+
+    /// Put an in-process [`LocalCache`] in front of the backend for [`cached`](Self::cached) reads.
+    ///
+    /// Call [`spawn_invalidation_listener`](Self::spawn_invalidation_listener)
+    /// afterwards so deletes on other instances reach this one.
+    pub fn with_local(mut self, local: LocalCache) -> Self {
+        self.local = Some(Arc::new(local));
+        self
+    }
+
+    /// Name of the backend, e.g. `redis` or `memory`.
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Whether the backend is reachable.
+    pub async fn ping(&self) -> CacheResult<()> {
+        self.backend.ping().await
+    }
+
+    /// Publish `payload` on `channel` to every instance subscribed to it.
+    pub async fn publish(&self, channel: &str, payload: String) -> CacheResult<()> {
+        self.backend.publish(channel, payload).await
+    }
+
+    /// Messages published on `channel` until the subscription drops.
+    pub async fn subscribe(&self, channel: &str) -> CacheResult<BoxStream<'static, String>> {
+        self.backend.subscribe(channel).await
+    }
+
+    /// Apply invalidations published by other instances to the local cache,
+    /// resubscribing (and clearing it, since messages may have been missed)
+    /// whenever the subscription drops.
+    pub fn spawn_invalidation_listener(&self) {
+        let Some(local) = self.local.clone() else {
+            return;
+        };
+        let backend = Arc::clone(&self.backend);
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = listen_for_invalidations(backend.as_ref(), &local).await {
+                    tracing::warn!("Cache invalidation subscription failed: {e}");
+                }
+                local.clear();
+                tokio::time::sleep(Duration::from_secs(1)).await;
             }
+        });
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let local_hits = self.counters.local_hits.load(Ordering::Relaxed);
+        let backend_hits = self.counters.backend_hits.load(Ordering::Relaxed);
+        let misses = self.counters.misses.load(Ordering::Relaxed);
+        let reads = (local_hits + backend_hits + misses).max(1) as f64;
+        CacheStats {
+            backend: self.backend.name(),
+            local_enabled: self.local.is_some(),
+            local_hits,
+            backend_hits,
+            misses,
+            local_hit_ratio: local_hits as f64 / reads,
+            backend_hit_ratio: backend_hits as f64 / reads,
+            local_entries: self.local.as_ref().map_or(0, |l| l.entry_count()),
+        }
+    }
+
+    /// Cache-aside read: return the cached value for `key`, or run `loader`
+    /// and cache its result for `ttl`.
+    ///
+    /// Concurrent misses on the same key are collapsed so only one caller runs
+    /// `loader`; the others wait and then read what it stored. Loader errors
+    /// are returned as-is and never cached. If the backend is unavailable the
+    /// loader runs directly so requests still succeed without the cache.
+    pub async fn cached<T, E, F, Fut>(&self, key: &str, ttl: Duration, loader: F) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.cached_tagged(key, &[], ttl, loader).await
+    }
+
+    /// Like [`cached`](Self::cached), but also registers the stored entry
+    /// under each of `tags` so [`invalidate_tags`](Self::invalidate_tags) can
+    /// drop it without knowing its exact key.
+    pub async fn cached_tagged<T, E, F, Fut>(
+        &self,
+        key: &str,
+        tags: &[String],
+        ttl: Duration,
+        loader: F,
+    ) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        match self.lookup::<T>(key, tags).await {
+            Ok(Some(hit)) => return Ok(hit),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!("Cache error for {key}: {e}");
+                self.counters.misses.fetch_add(1, Ordering::Relaxed);
+                metrics().cache_read("error");
+                return loader().await;
+            }
+        }
+
+        let _flight = self.flights.acquire(key).await;
+        // Another caller may have filled the entry while we waited for the flight.
+        if let Ok(Some(hit)) = self.lookup::<T>(key, tags).await {
+            return Ok(hit);
+        }
+
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+        metrics().cache_read("miss");
+        let value = loader().await?;
+        if let Err(e) = self.set_tagged(key, &value, ttl.as_secs(), tags).await {
+            tracing::warn!("Failed to cache {key}: {e}");
+        }
+        if let Some(local) = &self.local {
+            local.insert(key, value.clone(), tags).await;
+        }
+        Ok(value)
+    }
+
+    /// Read `key` from the local cache, then the backend (filling the local cache on a hit).
+    async fn lookup<T>(&self, key: &str, tags: &[String]) -> CacheResult<Option<T>>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        if let Some(local) = &self.local
+            && let Some(hit) = local.get::<T>(key).await
+        {
+            self.counters.local_hits.fetch_add(1, Ordering::Relaxed);
+            metrics().cache_read("local_hit");
+            return Ok(Some(hit));
+        }
+
+        let hit = self.get::<T>(key).await?;
+        if let Some(value) = &hit {
+            self.counters.backend_hits.fetch_add(1, Ordering::Relaxed);
+            metrics().cache_read("backend_hit");
+            if let Some(local) = &self.local {
+                local.insert(key, value.clone(), tags).await;
+            }
+        }
+        Ok(hit)
+    }
+
+    /// Drop `invalidation` from this instance's local cache and tell the others.
+    async fn broadcast(&self, invalidation: Invalidation) {
+        let Some(local) = &self.local else {
+            return;
+        };
+        local.apply(&invalidation).await;
+
+        let published = match serde_json::to_string(&invalidation) {
+            Ok(payload) => self.backend.publish(INVALIDATION_CHANNEL, payload).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = published {
+            tracing::warn!("Failed to publish cache invalidation: {e}");
+        }
+    }
+
+    /// Get a value from cache
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> CacheResult<Option<T>> {
+        match self.backend.get(key).await? {
+            Some(v) => Ok(Some(serde_json::from_str(&v)?)),
             None => Ok(None),
         }
     }
@@ -44,82 +245,88 @@ impl RedisCache {
         key: &str,
         value: &T,
         ttl_seconds: Option<u64>,
-    ) -> redis::RedisResult<()> {
-        let serialized = serde_json::to_string(value).map_err(|e| {
-            redis::RedisError::from((
-                redis::ErrorKind::TypeError,
-                "Serialization error",
-                e.to_string(),
-            ))
-        })?;
-
-        let mut cmd = redis::cmd("SET");
-        cmd.arg(key).arg(serialized);
-
-        if let Some(ttl) = ttl_seconds {
-            cmd.arg("EX").arg(ttl);
-        }
-
-        let mut conn = self.connection.clone();
-        cmd.query_async(&mut conn).await
+    ) -> CacheResult<()> {
+        let serialized = serde_json::to_string(value)?;
+        self.backend.set(key, serialized, ttl_seconds).await
     }
 
-    /// Delete a key from cache
-    pub async fn delete(&self, key: &str) -> redis::RedisResult<()> {
-        let mut conn = self.connection.clone();
-        redis::cmd("DEL")
-            .arg(key)
-            .query_async(&mut conn)
+    /// Set a value with a TTL (in seconds) and register its key under each tag
+    pub async fn set_tagged<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl_seconds: u64,
+        tags: &[String],
+    ) -> CacheResult<()> {
+        if tags.is_empty() {
+            return self.set(key, value, Some(ttl_seconds)).await;
+        }
+
+        let serialized = serde_json::to_string(value)?;
+        self.backend
+            .set_tagged(key, serialized, ttl_seconds, tags)
             .await
     }
 
-    /// Delete multiple keys matching a pattern using SCAN (production-safe)
-    pub async fn delete_pattern(&self, pattern: &str) -> redis::RedisResult<()> {
-        let mut conn = self.connection.clone();
-
-        let mut cursor = 0u64;
-
-        loop {
-            let (next_cursor, batch): (u64, Vec<String>) = redis::cmd("SCAN")
-                .arg(cursor)
-                .arg("MATCH")
-                .arg(pattern)
-                .arg("COUNT")
-                .arg(100)
-                .query_async(&mut conn)
-                .await?;
-
-            if !batch.is_empty() {
-                let _: () = redis::cmd("DEL").arg(batch).query_async(&mut conn).await?;
-            }
+    /// Delete every entry registered under any of `tags`; returns how many were removed
+    pub async fn invalidate_tags(&self, tags: &[String]) -> CacheResult<u64> {
+        if tags.is_empty() {
+            return Ok(0);
+        }
 
-            cursor = next_cursor;
+        let deleted = self.backend.invalidate_tags(tags).await;
+        self.broadcast(Invalidation::Tags(tags.to_vec())).await;
+        deleted
+    }
 
-            if cursor == 0 {
-                break;
-            }
+    /// Get a value and delete it atomically, for single-use entries
+    pub async fn take<T: DeserializeOwned>(&self, key: &str) -> CacheResult<Option<T>> {
+        match self.backend.take(key).await? {
+            Some(v) => Ok(Some(serde_json::from_str(&v)?)),
+            None => Ok(None),
         }
+    }
 
-        Ok(())
+    /// Delete a key from cache
+    pub async fn delete(&self, key: &str) -> CacheResult<()> {
+        let deleted = self.backend.delete(key).await;
+        self.broadcast(Invalidation::Key(key.to_string())).await;
+        deleted
     }
 
     /// Check if key exists
-    pub async fn exists(&self, key: &str) -> redis::RedisResult<bool> {
-        let mut conn = self.connection.clone();
-        redis::cmd("EXISTS")
-            .arg(key)
-            .query_async(&mut conn)
-            .await
+    pub async fn exists(&self, key: &str) -> CacheResult<bool> {
+        self.backend.exists(key).await
     }
 
-    /// Get remaining TTL for a key in seconds
-    pub async fn ttl(&self, key: &str) -> redis::RedisResult<i64> {
-        let mut conn = self.connection.clone();
-        redis::cmd("TTL")
-            .arg(key)
-            .query_async(&mut conn)
-            .await
+    /// Take a token from a rate-limit bucket; see [`CacheBackend::take_token`]
+    pub async fn take_token(
+        &self,
+        key: &str,
+        capacity: u32,
+        interval: Duration,
+    ) -> CacheResult<Duration> {
+        self.backend.take_token(key, capacity, interval).await
+    }
+}
+
+/// Subscribe to [`INVALIDATION_CHANNEL`] and apply messages to `local` until
+/// the subscription drops.
+async fn listen_for_invalidations(
+    backend: &dyn CacheBackend,
+    local: &LocalCache,
+) -> CacheResult<()> {
+    let mut messages = backend.subscribe(INVALIDATION_CHANNEL).await?;
+    // Anything published before the subscription started is lost.
+    local.clear();
+
+    while let Some(payload) = messages.next().await {
+        match serde_json::from_str::<Invalidation>(&payload) {
+            Ok(invalidation) => local.apply(&invalidation).await,
+            Err(e) => tracing::warn!("Bad cache invalidation message: {e}"),
+        }
     }
+    Ok(())
 }
 
 /// Cache key generators
@@ -139,6 +346,11 @@ pub mod keys {
         format!("user:{id}")
     }
 
+    /// Generate key for the authenticated-user cache (full `users` row)
+    pub fn auth_user(id: &str) -> String {
+        format!("auth:user:{id}")
+    }
+
     /// Generate key for user gigs
     pub fn user_gigs(user_id: &str) -> String {
         format!("user:{user_id}:gigs")
@@ -159,19 +371,94 @@ pub mod keys {
         format!("conversations:{user_id}")
     }
 
+    /// Generate key marking a single token (`jti` or session id) as revoked
+    pub fn revoked_token(id: &str) -> String {
+        format!("auth:revoked:{id}")
+    }
+
+    /// Generate key holding the cutoff before which a user's tokens are revoked
+    pub fn revoked_before(user_id: &str) -> String {
+        format!("auth:revoked_before:{user_id}")
+    }
+
+    /// Generate key for a single-use WebSocket ticket
+    pub fn ws_ticket(ticket: &str) -> String {
+        format!("ws:ticket:{ticket}")
+    }
+
+    /// Generate key for a rate-limit bucket of one client in one group
+    pub fn rate_limit(group: &str, client: &str) -> String {
+        format!("ratelimit:{group}:{client}")
+    }
+
     /// Generate key for messages in a conversation
     pub fn messages(conversation_id: &str) -> String {
         format!("messages:{conversation_id}")
     }
+
+    /// Generate key for the set of entries registered under a cache tag
+    pub fn tag(name: &str) -> String {
+        format!("tag:{name}")
+    }
+}
+
+/// Cache tag names, used with [`CacheStore::cached_tagged`] and [`CacheStore::invalidate_tags`]
+pub mod tags {
+    use sea_orm::{ActiveEnum, Iterable};
+    use uuid::Uuid;
+
+    use crate::models::gigs::{Categories, Model as Gig};
+
+    /// Tag for every paginated gig listing
+    pub fn all_gigs() -> String {
+        "gigs:all".to_string()
+    }
+
+    /// Tag for listings of one gig category (by its stored value, e.g. `design`)
+    pub fn gig_category(category: &str) -> String {
+        format!("gigs:category:{category}")
+    }
+
+    /// Tag for listings scoped to one user
+    pub fn user(user_id: &str) -> String {
+        format!("user:{user_id}")
+    }
+
+    /// Tags for every listing that can contain `gig`
+    pub fn for_gig(gig: &Gig) -> Vec<String> {
+        vec![
+            all_gigs(),
+            gig_category(&gig.category.to_value()),
+            user(&gig.user_id.to_string()),
+        ]
+    }
+
+    /// Tags for an updated gig: the listings it left (e.g. its old category) and joined
+    pub fn for_gig_update(before: &Gig, after: &Gig) -> Vec<String> {
+        let mut tags = for_gig(before);
+        tags.extend(for_gig(after));
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Tags for every listing that can contain any of `user_id`'s gigs
+    pub fn for_user_gigs(user_id: Uuid) -> Vec<String> {
+        let mut tags = vec![all_gigs(), user(&user_id.to_string())];
+        tags.extend(Categories::iter().map(|c| gig_category(&c.to_value())));
+        tags
+    }
 }
 
 /// Cache configuration
+#[derive(Debug, Clone)]
 pub struct CacheConfig {
     pub gig_list_ttl: Duration,
     pub gig_ttl: Duration,
     pub user_ttl: Duration,
     pub conversation_ttl: Duration,
     pub message_ttl: Duration,
+    pub portfolio_ttl: Duration,
 }
 
 impl Default for CacheConfig {
@@ -182,29 +469,10 @@ impl Default for CacheConfig {
             user_ttl: Duration::from_secs(900),         // 15 minutes
             conversation_ttl: Duration::from_secs(300), // 5 minutes
             message_ttl: Duration::from_secs(60),       // 1 minute
+            portfolio_ttl: Duration::from_secs(600),    // 10 minutes
         }
     }
 }
 
-impl CacheConfig {
-    pub fn from_env() -> Self {
-        Self {
-            gig_list_ttl: parse_duration_secs("CACHE_TTL_GIGS", 300),
-            gig_ttl: parse_duration_secs("CACHE_TTL_GIG_DETAIL", 600),
-            user_ttl: parse_duration_secs("CACHE_TTL_USERS", 900),
-            conversation_ttl: parse_duration_secs("CACHE_TTL_CONVERSATIONS", 300),
-            message_ttl: parse_duration_secs("CACHE_TTL_MESSAGES", 60),
-        }
-    }
-}
-
-fn parse_duration_secs(env_var: &str, default: u64) -> Duration {
-    std::env::var(env_var)
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .map(Duration::from_secs)
-        .unwrap_or_else(|| Duration::from_secs(default))
-}
-
 /// Wrapper type for Actix-web app data
-pub type CacheData = Arc<RedisCache>;
+pub type CacheData = Arc<CacheStore>;
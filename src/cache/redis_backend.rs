@@ -0,0 +1,239 @@
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+use redis::{Client, Script, aio::ConnectionManager};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+use super::backend::{CacheBackend, CacheError, CacheResult};
+use super::keys;
+
+/// Store `KEYS[1]` for `ARGV[2]` seconds and add it to each tag set in `KEYS[2..]`.
+/// Tag sets live at least as long as their longest-lived member.
+static SET_TAGGED: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        redis.call('SET', KEYS[1], ARGV[1], 'EX', ARGV[2])
+        local ttl = tonumber(ARGV[2])
+        for i = 2, #KEYS do
+            redis.call('SADD', KEYS[i], KEYS[1])
+            if redis.call('TTL', KEYS[i]) < ttl then
+                redis.call('EXPIRE', KEYS[i], ttl)
+            end
+        end
+        ",
+    )
+});
+
+/// Delete every entry registered under the tag sets in `KEYS`, then the sets.
+static INVALIDATE_TAGS: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        local deleted = 0
+        for _, tag in ipairs(KEYS) do
+            local members = redis.call('SMEMBERS', tag)
+            for i = 1, #members, 500 do
+                local last = math.min(i + 499, #members)
+                deleted = deleted + redis.call('DEL', unpack(members, i, last))
+            end
+            redis.call('DEL', tag)
+        end
+        return deleted
+        ",
+    )
+});
+
+/// Token bucket (GCRA) at `KEYS[1]` holding up to `ARGV[1]` tokens, one regained
+/// every `ARGV[2]` ms. The key stores when the bucket is full again, in ms on the
+/// Redis clock so every instance agrees. Returns the ms to wait, 0 if allowed.
+static TAKE_TOKEN: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        local time = redis.call('TIME')
+        local now = time[1] * 1000 + math.floor(time[2] / 1000)
+        local capacity = tonumber(ARGV[1])
+        local interval = tonumber(ARGV[2])
+        local full_at = math.max(tonumber(redis.call('GET', KEYS[1]) or now), now) + interval
+        local allowed_at = full_at - capacity * interval
+        if allowed_at > now then
+            return allowed_at - now
+        end
+        redis.call('SET', KEYS[1], full_at, 'PX', full_at - now)
+        return 0
+        ",
+    )
+});
+
+/// Redis-backed cache shared by every instance.
+///
+/// Until the first connection succeeds every operation fails fast with
+/// [`CacheError::Unavailable`], so the app can start (and serve from Postgres)
+/// while Redis is down; see [`spawn_reconnect`](Self::spawn_reconnect). Once
+/// connected, the `ConnectionManager` reconnects by itself after drops.
+pub struct RedisBackend {
+    client: Client,
+    connection: RwLock<Option<ConnectionManager>>,
+}
+
+impl RedisBackend {
+    /// Parse `redis_url` without connecting.
+    pub fn new(redis_url: &str) -> CacheResult<Self> {
+        Ok(Self {
+            client: Client::open(redis_url)?,
+            connection: RwLock::new(None),
+        })
+    }
+
+    /// Try to connect once.
+    pub async fn connect(&self) -> CacheResult<()> {
+        let connection = ConnectionManager::new(self.client.clone()).await?;
+        *self.connection.write().unwrap() = Some(connection);
+        Ok(())
+    }
+
+    /// Keep retrying [`connect`](Self::connect) in the background until it succeeds.
+    pub fn spawn_reconnect(self: &Arc<Self>, retry: Duration) {
+        let backend = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(retry).await;
+                match backend.connect().await {
+                    Ok(()) => {
+                        tracing::info!("Connected to Redis");
+                        return;
+                    }
+                    Err(e) => tracing::warn!("Redis still unavailable: {e}"),
+                }
+            }
+        });
+    }
+
+    fn connection(&self) -> CacheResult<ConnectionManager> {
+        self.connection
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or(CacheError::Unavailable)
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheBackend for RedisBackend {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    #[tracing::instrument(name = "redis PING", skip_all, fields(db.system = "redis"))]
+    async fn ping(&self) -> CacheResult<()> {
+        let mut conn = self.connection()?;
+        redis::cmd("PING")
+            .query_async::<_, String>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "redis GET", skip_all, fields(db.system = "redis"))]
+    async fn get(&self, key: &str) -> CacheResult<Option<String>> {
+        let mut conn = self.connection()?;
+        Ok(redis::cmd("GET").arg(key).query_async(&mut conn).await?)
+    }
+
+    #[tracing::instrument(name = "redis SET", skip_all, fields(db.system = "redis"))]
+    async fn set(&self, key: &str, value: String, ttl_seconds: Option<u64>) -> CacheResult<()> {
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(key).arg(value);
+
+        if let Some(ttl) = ttl_seconds {
+            cmd.arg("EX").arg(ttl);
+        }
+
+        let mut conn = self.connection()?;
+        Ok(cmd.query_async(&mut conn).await?)
+    }
+
+    #[tracing::instrument(name = "redis GETDEL", skip_all, fields(db.system = "redis"))]
+    async fn take(&self, key: &str) -> CacheResult<Option<String>> {
+        let mut conn = self.connection()?;
+        Ok(redis::cmd("GETDEL").arg(key).query_async(&mut conn).await?)
+    }
+
+    #[tracing::instrument(name = "redis DEL", skip_all, fields(db.system = "redis"))]
+    async fn delete(&self, key: &str) -> CacheResult<()> {
+        let mut conn = self.connection()?;
+        Ok(redis::cmd("DEL").arg(key).query_async(&mut conn).await?)
+    }
+
+    #[tracing::instrument(name = "redis EXISTS", skip_all, fields(db.system = "redis"))]
+    async fn exists(&self, key: &str) -> CacheResult<bool> {
+        let mut conn = self.connection()?;
+        Ok(redis::cmd("EXISTS").arg(key).query_async(&mut conn).await?)
+    }
+
+    #[tracing::instrument(name = "redis SET_TAGGED", skip_all, fields(db.system = "redis"))]
+    async fn set_tagged(
+        &self,
+        key: &str,
+        value: String,
+        ttl_seconds: u64,
+        tags: &[String],
+    ) -> CacheResult<()> {
+        let mut invocation = SET_TAGGED.prepare_invoke();
+        invocation.key(key).arg(value).arg(ttl_seconds);
+        for tag in tags {
+            invocation.key(keys::tag(tag));
+        }
+
+        let mut conn = self.connection()?;
+        Ok(invocation.invoke_async(&mut conn).await?)
+    }
+
+    #[tracing::instrument(name = "redis INVALIDATE_TAGS", skip_all, fields(db.system = "redis"))]
+    async fn invalidate_tags(&self, tags: &[String]) -> CacheResult<u64> {
+        let mut invocation = INVALIDATE_TAGS.prepare_invoke();
+        for tag in tags {
+            invocation.key(keys::tag(tag));
+        }
+
+        let mut conn = self.connection()?;
+        Ok(invocation.invoke_async(&mut conn).await?)
+    }
+
+    #[tracing::instrument(name = "redis TAKE_TOKEN", skip_all, fields(db.system = "redis"))]
+    async fn take_token(
+        &self,
+        key: &str,
+        capacity: u32,
+        interval: Duration,
+    ) -> CacheResult<Duration> {
+        let mut invocation = TAKE_TOKEN.prepare_invoke();
+        invocation
+            .key(key)
+            .arg(capacity)
+            .arg(interval.as_millis() as u64);
+
+        let mut conn = self.connection()?;
+        let wait_ms: u64 = invocation.invoke_async(&mut conn).await?;
+        Ok(Duration::from_millis(wait_ms))
+    }
+
+    #[tracing::instrument(name = "redis PUBLISH", skip_all, fields(db.system = "redis"))]
+    async fn publish(&self, channel: &str, payload: String) -> CacheResult<()> {
+        let mut conn = self.connection()?;
+        redis::cmd("PUBLISH")
+            .arg(channel)
+            .arg(payload)
+            .query_async::<_, i64>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn subscribe(&self, channel: &str) -> CacheResult<BoxStream<'static, String>> {
+        let mut pubsub = self.client.get_async_connection().await?.into_pubsub();
+        pubsub.subscribe(channel).await?;
+        let messages = pubsub.into_on_message().filter_map(|msg| async move {
+            msg.get_payload::<String>()
+                .map_err(|e| tracing::warn!("Bad pub/sub payload: {e}"))
+                .ok()
+        });
+        Ok(messages.boxed())
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Per-key locks so only one caller at a time rebuilds a missing cache entry.
+///
+/// The first caller to miss on a key holds its flight while it loads and
+/// stores the value; concurrent callers queue on the same key and re-check
+/// the cache once it is released, so a burst of misses reaches Postgres once.
+#[derive(Default)]
+pub struct SingleFlight {
+    flights: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+}
+
+/// Held while loading a key; dropping it lets the next waiter in.
+pub struct Flight<'a> {
+    group: &'a SingleFlight,
+    key: String,
+    lock: Arc<AsyncMutex<()>>,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl SingleFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until no other caller holds `key`, then hold it.
+    pub async fn acquire(&self, key: &str) -> Flight<'_> {
+        let lock = self
+            .flights
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+        let guard = lock.clone().lock_owned().await;
+        Flight {
+            group: self,
+            key: key.to_string(),
+            lock,
+            guard: Some(guard),
+        }
+    }
+
+    /// Number of keys currently being loaded or waited on.
+    pub fn in_flight(&self) -> usize {
+        self.flights.lock().unwrap().len()
+    }
+}
+
+impl Drop for Flight<'_> {
+    fn drop(&mut self) {
+        self.guard.take();
+        let mut flights = self.group.flights.lock().unwrap();
+        // Only the map and this flight still reference the lock: nobody is waiting.
+        if Arc::strong_count(&self.lock) == 2 {
+            flights.remove(&self.key);
+        }
+    }
+}
@@ -17,6 +17,18 @@ pub enum ClientMessage {
     StopTyping,
 }
 
+impl ClientMessage {
+    /// The `type` tag of this message, e.g. `send_message`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::SendMessage { .. } => "send_message",
+            Self::MarkRead { .. } => "mark_read",
+            Self::Typing => "typing",
+            Self::StopTyping => "stop_typing",
+        }
+    }
+}
+
 // ── Server -> Client messages ──
 
 /// Messages the server sends to the client over WebSocket.
@@ -38,6 +50,13 @@ pub enum ServerMessage {
     UserStopTyping { user_id: Uuid },
     /// Presence update: a user came online or went offline in this contract chat.
     Presence { user_id: Uuid, online: bool },
-    /// An error occurred.
-    Error { message: String },
+    /// A frame was rejected. `code` is the same as in REST error bodies, e.g.
+    /// `forbidden` or `internal_error`.
+    Error { message: String, code: &'static str },
+    /// The server is closing this connection (e.g. the account was suspended).
+    /// Always the last message of a session.
+    Disconnect { reason: String },
+    /// The server is shutting down; the connection closes shortly with code
+    /// 1012. Reconnect after `reconnect_after_ms` (to another instance).
+    ServerRestarting { reconnect_after_ms: u64 },
 }
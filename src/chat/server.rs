@@ -1,8 +1,27 @@
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tokio::sync::{RwLock, mpsc};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::{RwLock, mpsc, watch};
+use tokio::time::Instant;
 use uuid::Uuid;
 
+use crate::cache::{CacheResult, CacheStore};
 use crate::chat::protocol::ServerMessage;
+use crate::metrics::metrics;
+
+/// Pub/sub channel carrying [`ChatServer::disconnect_user`] calls between instances.
+pub const DISCONNECT_CHANNEL: &str = "chat:disconnect";
+
+/// A `disconnect_user` call published by the instance `origin`.
+#[derive(Debug, Serialize, Deserialize)]
+struct DisconnectRequest {
+    origin: Uuid,
+    user_id: Uuid,
+    reason: String,
+}
 
 /// A handle to send messages to a connected WebSocket client.
 #[derive(Debug, Clone)]
@@ -19,6 +38,63 @@ pub struct ClientHandle {
 pub struct ChatServer {
     /// contract_id -> list of connected client handles
     rooms: RwLock<HashMap<Uuid, Vec<ClientHandle>>>,
+    /// Set once shutdown starts; new connections and messages are refused.
+    draining: AtomicBool,
+    /// Running session loops, so shutdown can wait for them to close.
+    sessions: ActivityCounter,
+    /// Message writes (`insert_message` + broadcast) in progress.
+    writes: ActivityCounter,
+    /// Identifies this instance's own messages on [`DISCONNECT_CHANNEL`].
+    instance_id: Uuid,
+    /// Shares disconnects with the other instances, when set.
+    pubsub: Option<Arc<CacheStore>>,
+}
+
+/// Counts running activities and lets shutdown wait until none are left.
+struct ActivityCounter(watch::Sender<usize>);
+
+impl ActivityCounter {
+    fn new() -> Self {
+        Self(watch::Sender::new(0))
+    }
+
+    fn enter(&self) -> ActivityGuard<'_> {
+        self.0.send_modify(|n| *n += 1);
+        ActivityGuard(&self.0)
+    }
+
+    fn count(&self) -> usize {
+        *self.0.borrow()
+    }
+
+    /// Wait until the count drops to zero or `deadline` passes; returns
+    /// whether it reached zero.
+    async fn wait_idle(&self, deadline: Instant) -> bool {
+        let mut rx = self.0.subscribe();
+        tokio::time::timeout_at(deadline, rx.wait_for(|n| *n == 0))
+            .await
+            .is_ok()
+    }
+}
+
+/// Marks one running activity; dropping it ends the activity.
+pub struct ActivityGuard<'a>(&'a watch::Sender<usize>);
+
+impl Drop for ActivityGuard<'_> {
+    fn drop(&mut self) {
+        self.0.send_modify(|n| *n -= 1);
+    }
+}
+
+/// What [`ChatServer::shutdown`] got done before its deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrainReport {
+    /// Connections told that the server is restarting.
+    pub notified: usize,
+    /// Message writes still running when the deadline passed.
+    pub abandoned_writes: usize,
+    /// Sessions still open when the deadline passed.
+    pub open_sessions: usize,
 }
 
 impl Default for ChatServer {
@@ -31,6 +107,118 @@ impl ChatServer {
     pub fn new() -> Self {
         Self {
             rooms: RwLock::new(HashMap::new()),
+            draining: AtomicBool::new(false),
+            sessions: ActivityCounter::new(),
+            writes: ActivityCounter::new(),
+            instance_id: Uuid::new_v4(),
+            pubsub: None,
+        }
+    }
+
+    /// Forward [`disconnect_user`](Self::disconnect_user) to every instance
+    /// sharing `cache`.
+    ///
+    /// Call [`spawn_disconnect_listener`](Self::spawn_disconnect_listener)
+    /// afterwards so disconnects from other instances reach this one.
+    pub fn with_pubsub(mut self, cache: Arc<CacheStore>) -> Self {
+        self.pubsub = Some(cache);
+        self
+    }
+
+    /// Apply disconnects published by other instances, resubscribing whenever
+    /// the subscription drops. Disconnects published meanwhile are lost; the
+    /// affected users are still rejected when they next authenticate.
+    pub fn spawn_disconnect_listener(self: &Arc<Self>) {
+        let Some(cache) = self.pubsub.clone() else {
+            return;
+        };
+        let server = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = server.listen_for_disconnects(&cache).await {
+                    tracing::warn!("Chat disconnect subscription failed: {e}");
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        });
+    }
+
+    async fn listen_for_disconnects(&self, cache: &CacheStore) -> CacheResult<()> {
+        let mut messages = cache.subscribe(DISCONNECT_CHANNEL).await?;
+        while let Some(payload) = messages.next().await {
+            match serde_json::from_str::<DisconnectRequest>(&payload) {
+                Ok(request) if request.origin != self.instance_id => {
+                    self.disconnect_local(request.user_id, &request.reason)
+                        .await;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Bad chat disconnect message: {e}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether shutdown has started; new WebSocket upgrades are refused.
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Track a running session loop until the guard is dropped.
+    pub fn session_started(&self) -> ActivityGuard<'_> {
+        self.sessions.enter()
+    }
+
+    /// Track a message write until the guard is dropped, or `None` once
+    /// shutdown has started and no new writes are accepted.
+    pub fn begin_write(&self) -> Option<ActivityGuard<'_>> {
+        let guard = self.writes.enter();
+        // Checked after entering so `shutdown` either sees this write or it
+        // sees the flag; a write can't slip in after the wait has finished.
+        (!self.is_draining()).then_some(guard)
+    }
+
+    /// Drain every chat session for shutdown:
+    ///
+    /// 1. stop accepting new connections and messages,
+    /// 2. send `ServerRestarting` with `reconnect_after` to every client,
+    /// 3. wait for in-flight message writes,
+    /// 4. drop every connection so its session closes the socket with 1012,
+    ///    and wait for the sessions to finish.
+    ///
+    /// Steps 3 and 4 share `timeout`; whatever is left when it passes is
+    /// reported and abandoned.
+    pub async fn shutdown(&self, reconnect_after: Duration, timeout: Duration) -> DrainReport {
+        let deadline = Instant::now() + timeout;
+        self.draining.store(true, Ordering::SeqCst);
+
+        let message = ServerMessage::ServerRestarting {
+            reconnect_after_ms: reconnect_after.as_millis() as u64,
+        };
+        let notified = {
+            let rooms = self.rooms.read().await;
+            rooms
+                .values()
+                .flatten()
+                .filter(|client| client.sender.send(message.clone()).is_ok())
+                .count()
+        };
+
+        let writes_done = self.writes.wait_idle(deadline).await;
+        let abandoned_writes = if writes_done { 0 } else { self.writes.count() };
+
+        // Dropping the senders ends each session's receive loop.
+        self.rooms.write().await.clear();
+        let sessions_done = self.sessions.wait_idle(deadline).await;
+        let open_sessions = if sessions_done {
+            0
+        } else {
+            self.sessions.count()
+        };
+
+        DrainReport {
+            notified,
+            abandoned_writes,
+            open_sessions,
         }
     }
 
@@ -55,6 +243,11 @@ impl ChatServer {
         };
 
         let mut rooms = self.rooms.write().await;
+        // Once shutdown has cleared the rooms, drop the sender right away so
+        // the new session closes instead of outliving the drain.
+        if self.is_draining() {
+            return rx;
+        }
         let room = rooms.entry(contract_id).or_insert_with(Vec::new);
 
         // Send presence to existing members before adding the new one.
@@ -109,6 +302,7 @@ impl ChatServer {
         message: ServerMessage,
         exclude_user: Option<Uuid>,
     ) {
+        metrics().chat_messages_broadcast.inc();
         let rooms = self.rooms.read().await;
         if let Some(room) = rooms.get(&contract_id) {
             for client in room {
@@ -117,7 +311,9 @@ impl ChatServer {
                 }
                 // If the send fails, the receiver has been dropped (disconnected).
                 // That's okay — the leave() method will clean it up.
-                let _ = client.sender.send(message.clone());
+                if client.sender.send(message.clone()).is_err() {
+                    metrics().chat_send_failures.inc();
+                }
             }
         }
     }
@@ -127,13 +323,58 @@ impl ChatServer {
         let rooms = self.rooms.read().await;
         if let Some(room) = rooms.get(&contract_id) {
             for client in room {
-                if client.user_id == user_id {
-                    let _ = client.sender.send(message.clone());
+                if client.user_id == user_id && client.sender.send(message.clone()).is_err() {
+                    metrics().chat_send_failures.inc();
                 }
             }
         }
     }
 
+    /// Send `Disconnect` to every connection of a user across all contract
+    /// chats; their sessions close after delivering it.
+    ///
+    /// With [`with_pubsub`](Self::with_pubsub) the call is also published to
+    /// the other instances; otherwise only this instance's connections close.
+    /// Returns the number of connections notified on this instance.
+    pub async fn disconnect_user(&self, user_id: Uuid, reason: &str) -> usize {
+        if let Some(cache) = &self.pubsub {
+            let request = DisconnectRequest {
+                origin: self.instance_id,
+                user_id,
+                reason: reason.to_string(),
+            };
+            let published = match serde_json::to_string(&request) {
+                Ok(payload) => cache.publish(DISCONNECT_CHANNEL, payload).await,
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = published {
+                tracing::warn!("Failed to publish chat disconnect for {user_id}: {e}");
+            }
+        }
+        self.disconnect_local(user_id, reason).await
+    }
+
+    async fn disconnect_local(&self, user_id: Uuid, reason: &str) -> usize {
+        let message = ServerMessage::Disconnect {
+            reason: reason.to_string(),
+        };
+
+        let rooms = self.rooms.read().await;
+        let mut notified = 0;
+        for client in rooms.values().flatten() {
+            if client.user_id == user_id && client.sender.send(message.clone()).is_ok() {
+                notified += 1;
+            }
+        }
+        notified
+    }
+
+    /// Number of open rooms and of connected clients across them.
+    pub async fn connection_counts(&self) -> (usize, usize) {
+        let rooms = self.rooms.read().await;
+        (rooms.len(), rooms.values().map(Vec::len).sum())
+    }
+
     /// Check if a specific user is currently online in a contract chat.
     pub async fn is_user_online(&self, contract_id: Uuid, user_id: Uuid) -> bool {
         let rooms = self.rooms.read().await;
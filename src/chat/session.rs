@@ -1,118 +1,214 @@
-use actix_web::{HttpRequest, HttpResponse, web};
-use actix_ws::Message;
+use actix_web::http::header;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
+use actix_ws::{CloseCode, CloseReason, Message};
 use futures_util::StreamExt;
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 use tokio::sync::mpsc;
+use tracing::Instrument;
 use uuid::Uuid;
 
-use crate::auth::jwks::JwksCache;
-use crate::auth::jwt;
+use crate::auth::authorization::{load_contract, verify_contract_party};
+use crate::auth::error::AuthError;
+use crate::auth::middleware::{ensure_active, verify_token};
+use crate::auth::policy::{Action, Actor, Policy, Resource};
+use crate::cache::CacheStore;
 use crate::chat::protocol::{ClientMessage, ServerMessage};
 use crate::chat::server::ChatServer;
-use crate::db::contracts as contract_db;
-use crate::db::gigs as gig_db;
+use crate::chat::ticket::{self, BEARER_PROTOCOL, WsAuthConfig};
 use crate::db::messages as message_db;
-use crate::models::contracts::Status;
+use crate::db::users as user_db;
+use crate::error::ApiError;
+use crate::middleware::request_id::{self, RequestId};
 use crate::models::messages::CreateMessage;
+use crate::models::users::Roles;
+use crate::rate_limit::{RateLimiter, RateLimiterData};
 
 /// Query params for the WebSocket handshake endpoint.
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct WsQuery {
-    pub token: String,
+    /// Single-use ticket from `POST /api/chat/ws-ticket`.
+    pub ticket: Option<String>,
+    /// Raw JWT; only accepted when `WS_ALLOW_QUERY_TOKEN` is enabled.
+    pub token: Option<String>,
 }
 
-/// GET /api/chat/ws/{contract_id}?token=<jwt>
+/// GET /api/chat/ws/{contract_id}?ticket=<ticket>
 ///
 /// Upgrades the HTTP connection to a WebSocket.
-/// Authenticates via query param token (browsers can't send Authorization headers
-/// during the WebSocket handshake).
+/// Browsers can't send Authorization headers during the WebSocket handshake, so
+/// the caller authenticates with one of (in order of precedence):
+/// - a single-use ticket from `POST /api/chat/ws-ticket` in `?ticket=`,
+/// - `Sec-WebSocket-Protocol: bearer, <jwt>`,
+/// - the raw JWT in `?token=`, if enabled by config.
+///
 /// Validates that:
-/// 1. The JWT is valid.
-/// 2. The contract exists and is Accepted.
-/// 3. The user is a party to the contract (client or gig owner/freelancer).
+/// 1. The credential is valid (JWTs under the configured validation policy).
+/// 2. The account is not suspended or banned.
+/// 3. The contract exists and is Accepted, and the user is a party to it
+///    (client or gig owner/freelancer).
+#[utoipa::path(
+    get,
+    path = "/api/chat/ws/{contract_id}",
+    tag = "chat",
+    params(("contract_id" = Uuid, Path, description = "Contract id"), WsQuery),
+    responses(
+        (status = 101, description = "Switched to the chat WebSocket protocol"),
+        (status = 401, description = "Missing or invalid credentials", body = crate::error::ErrorBody),
+        (status = 403, description = "Not a party to an accepted contract", body = crate::error::ErrorBody),
+        (status = 404, description = "No such contract", body = crate::error::ErrorBody),
+        (status = 503, description = "The server is draining", body = crate::error::ErrorBody),
+    )
+)]
 pub async fn ws_connect(
     req: HttpRequest,
     stream: web::Payload,
     path: web::Path<Uuid>,
     query: web::Query<WsQuery>,
     db: web::Data<DatabaseConnection>,
-    jwks_cache: web::Data<Arc<JwksCache>>,
+    cache: web::Data<Arc<CacheStore>>,
     chat_server: web::Data<Arc<ChatServer>>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let contract_id = path.into_inner();
-    let token = &query.token;
-
-    // 1. Validate the JWT.
-    let claims = jwt::validate_token(token, jwks_cache.get_ref())
-        .await
-        .map_err(|e| actix_web::error::ErrorUnauthorized(format!("Invalid token: {e}")))?;
+    if chat_server.is_draining() {
+        return Err(
+            ApiError::Unavailable("Server is restarting, try again shortly".to_string()).into(),
+        );
+    }
 
-    let user_id = claims
-        .user_id()
-        .map_err(actix_web::error::ErrorUnauthorized)?;
+    // 1. Resolve the user from the ticket or JWT.
+    let (user_id, via_protocol) =
+        authenticate_handshake(&req, &query, cache.get_ref(), contract_id).await?;
+    request_id::record_user(user_id);
 
-    // 2. Fetch the contract and verify it's Accepted.
-    let contract = contract_db::get_contract_by_id(db.get_ref(), contract_id)
+    // 2. Reject suspended or banned accounts. Users without a row yet have never
+    //    used the REST API, so there is nothing to enforce for them.
+    let user = user_db::get_user_by_id(db.get_ref(), user_id)
         .await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(format!("Database error: {e}")))?
-        .ok_or_else(|| {
-            actix_web::error::ErrorNotFound(format!("Contract {contract_id} not found"))
-        })?;
-
-    if contract.status != Status::Accepted {
-        return Err(actix_web::error::ErrorForbidden(
-            "Chat is only available for accepted contracts",
-        ));
+        .map_err(ApiError::from)?;
+    if let Some(user) = &user {
+        ensure_active(user)?;
     }
 
-    // 3. Verify the user is a party to the contract.
-    let is_client = contract.user_id == user_id;
-    let is_freelancer = match gig_db::get_gig_by_id(db.get_ref(), contract.gig_id).await {
-        Ok(Some(gig)) => gig.user_id == user_id,
-        _ => false,
+    // 3. Verify the contract is Accepted and the user is a party to it, with the
+    //    same policy as the REST chat endpoints.
+    let actor = match &user {
+        Some(user) => Actor::from(user),
+        None => Actor {
+            id: user_id,
+            role: Roles::Client,
+        },
     };
+    verify_contract_party(db.get_ref(), contract_id, &actor).await?;
 
-    if !is_client && !is_freelancer {
-        return Err(actix_web::error::ErrorForbidden(
-            "You are not a party to this contract",
-        ));
+    // 4. Upgrade to WebSocket. Clients that authenticated via subprotocol fail
+    //    the handshake unless the server selects one of the offered protocols.
+    let (mut response, session, msg_stream) = actix_ws::handle(&req, stream)?;
+    if via_protocol {
+        response.headers_mut().insert(
+            header::SEC_WEBSOCKET_PROTOCOL,
+            header::HeaderValue::from_static(BEARER_PROTOCOL),
+        );
     }
 
-    // 4. Upgrade to WebSocket.
-    let (response, session, msg_stream) = actix_ws::handle(&req, stream)?;
-
     // 5. Join the chat room and get a receiver for outgoing messages.
     let rx = chat_server.join(contract_id, user_id).await;
 
     // 6. Spawn the WebSocket session task.
     let db_clone = db.get_ref().clone();
     let chat_server_clone = chat_server.get_ref().clone();
+    let limiter = req
+        .app_data::<web::Data<RateLimiterData>>()
+        .map(|l| l.get_ref().clone());
+
+    // The session outlives the handshake request, so it gets its own span that
+    // carries the handshake's request id for correlation.
+    let upgrade_request_id = req.extensions().get::<RequestId>().map(|id| id.0.clone());
+    let span = tracing::info_span!(
+        "ws_session",
+        %contract_id,
+        %user_id,
+        request_id = upgrade_request_id.as_deref(),
+    );
 
-    actix_web::rt::spawn(handle_ws_session(
-        session,
-        msg_stream,
-        rx,
-        contract_id,
-        user_id,
-        db_clone,
-        chat_server_clone,
-    ));
+    actix_web::rt::spawn(
+        handle_ws_session(
+            session,
+            msg_stream,
+            rx,
+            contract_id,
+            actor,
+            db_clone,
+            chat_server_clone,
+            limiter,
+        )
+        .instrument(span),
+    );
 
     Ok(response)
 }
 
+/// Authenticate a handshake. Returns the user and whether the `bearer`
+/// subprotocol was used (and so must be echoed back).
+async fn authenticate_handshake(
+    req: &HttpRequest,
+    query: &WsQuery,
+    cache: &CacheStore,
+    contract_id: Uuid,
+) -> Result<(Uuid, bool), AuthError> {
+    if let Some(id) = &query.ticket {
+        let ticket = ticket::redeem(cache, id)
+            .await
+            .map_err(|e| AuthError::Internal(format!("Cache error: {e}")))?
+            .ok_or(AuthError::InvalidTicket)?;
+        if !ticket.allows(contract_id) {
+            return Err(AuthError::InvalidTicket);
+        }
+        return Ok((ticket.user_id, false));
+    }
+
+    let protocols = req
+        .headers()
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|v| v.to_str().ok());
+    if let Some(token) = protocols.and_then(ticket::bearer_from_protocols) {
+        let (user_id, _claims) = verify_token(req, token).await?;
+        return Ok((user_id, true));
+    }
+
+    if let Some(token) = &query.token {
+        let allowed = req
+            .app_data::<web::Data<WsAuthConfig>>()
+            .is_some_and(|config| config.allow_query_token);
+        if !allowed {
+            return Err(AuthError::QueryTokenDisabled);
+        }
+        let (user_id, _claims) = verify_token(req, token).await?;
+        return Ok((user_id, false));
+    }
+
+    Err(AuthError::MissingToken)
+}
+
 /// Drives the WebSocket session: reads incoming messages from the client,
 /// sends outgoing messages from the chat server, and handles cleanup on disconnect.
+#[allow(clippy::too_many_arguments)]
 async fn handle_ws_session(
     mut session: actix_ws::Session,
     mut msg_stream: actix_ws::MessageStream,
     mut rx: mpsc::UnboundedReceiver<ServerMessage>,
     contract_id: Uuid,
-    user_id: Uuid,
+    actor: Actor,
     db: DatabaseConnection,
     chat_server: Arc<ChatServer>,
+    limiter: Option<RateLimiterData>,
 ) {
+    let user_id = actor.id;
+    let _session = chat_server.session_started();
+    let mut close_reason = None;
+    tracing::info!("chat session opened");
+
     loop {
         tokio::select! {
             // Incoming message from the WebSocket client.
@@ -123,9 +219,10 @@ async fn handle_ws_session(
                             &text,
                             &mut session,
                             contract_id,
-                            user_id,
+                            &actor,
                             &db,
                             &chat_server,
+                            limiter.as_deref(),
                         )
                         .await;
                     }
@@ -144,7 +241,15 @@ async fn handle_ws_session(
                 }
             }
             // Outgoing message from the chat server to this client.
-            Some(server_msg) = rx.recv() => {
+            server_msg = rx.recv() => {
+                // The chat server dropped this connection while shutting down.
+                let Some(server_msg) = server_msg else {
+                    close_reason = Some(CloseReason {
+                        code: CloseCode::Restart,
+                        description: Some("server restarting".to_string()),
+                    });
+                    break;
+                };
                 let json = match serde_json::to_string(&server_msg) {
                     Ok(j) => j,
                     Err(_) => continue,
@@ -152,6 +257,13 @@ async fn handle_ws_session(
                 if session.text(json).await.is_err() {
                     break;
                 }
+                if let ServerMessage::Disconnect { reason } = server_msg {
+                    close_reason = Some(CloseReason {
+                        code: CloseCode::Policy,
+                        description: Some(reason),
+                    });
+                    break;
+                }
             }
             // Both channels closed — exit.
             else => break,
@@ -160,41 +272,62 @@ async fn handle_ws_session(
 
     // Clean up: leave the chat room.
     chat_server.leave(contract_id, user_id).await;
-    let _ = session.close(None).await;
+    tracing::info!(
+        reason = close_reason.as_ref().and_then(|r| r.description.as_deref()),
+        "chat session closed"
+    );
+    let _ = session.close(close_reason).await;
 }
 
 /// Parse and handle an incoming client message.
+///
+/// Sends and read receipts are authorized with the same [`Policy`] rules as
+/// the REST chat endpoints, against the contract as it is now: the handshake
+/// check alone would leave the socket writable after the contract changes.
 async fn handle_client_message(
     text: &str,
     session: &mut actix_ws::Session,
     contract_id: Uuid,
-    user_id: Uuid,
+    actor: &Actor,
     db: &DatabaseConnection,
     chat_server: &ChatServer,
+    limiter: Option<&RateLimiter>,
 ) {
+    let user_id = actor.id;
     let client_msg: ClientMessage = match serde_json::from_str(text) {
         Ok(m) => m,
         Err(e) => {
-            let err = ServerMessage::Error {
-                message: format!("Invalid message format: {e}"),
-            };
-            let _ = session
-                .text(serde_json::to_string(&err).unwrap_or_default())
-                .await;
-            return;
+            let err = ApiError::validation(format!("Invalid message format: {e}"));
+            return send_error(session, err).await;
         }
     };
 
+    // Throttled frames are dropped; the connection stays open.
+    if let Some(limiter) = limiter
+        && let Err(retry_after) = limiter.check_chat(&client_msg, user_id).await
+    {
+        return send_error(session, ApiError::RateLimited { retry_after }).await;
+    }
+
     match client_msg {
         ClientMessage::SendMessage { content } => {
             if content.trim().is_empty() {
-                let err = ServerMessage::Error {
-                    message: "Message content cannot be empty".to_string(),
-                };
-                let _ = session
-                    .text(serde_json::to_string(&err).unwrap_or_default())
-                    .await;
-                return;
+                let err = ApiError::validation("Message content cannot be empty");
+                return send_error(session, err).await;
+            }
+
+            // Refuse new writes while shutting down; in-flight ones are awaited.
+            let Some(_write) = chat_server.begin_write() else {
+                let err = ApiError::Unavailable("Server is restarting; message not sent".into());
+                return send_error(session, err).await;
+            };
+
+            let parties = match load_contract(db, contract_id).await {
+                Ok((_, parties)) => parties,
+                Err(err) => return send_error(session, err).await,
+            };
+            if let Err(denied) = Policy::can(actor, Action::Create, &Resource::Chat(parties)) {
+                return send_error(session, denied.into()).await;
             }
 
             // Persist the message to the database.
@@ -218,12 +351,8 @@ async fn handle_client_message(
                     chat_server.broadcast(contract_id, msg, None).await;
                 }
                 Err(e) => {
-                    let err = ServerMessage::Error {
-                        message: format!("Failed to save message: {e}"),
-                    };
-                    let _ = session
-                        .text(serde_json::to_string(&err).unwrap_or_default())
-                        .await;
+                    let err = ApiError::Internal(format!("Failed to save chat message: {e}"));
+                    send_error(session, err).await;
                 }
             }
         }
@@ -232,43 +361,29 @@ async fn handle_client_message(
             let message = match message_db::get_message_by_id(db, message_id).await {
                 Ok(Some(msg)) => msg,
                 Ok(None) => {
-                    let err = ServerMessage::Error {
-                        message: format!("Message {message_id} not found"),
-                    };
-                    let _ = session
-                        .text(serde_json::to_string(&err).unwrap_or_default())
-                        .await;
-                    return;
-                }
-                Err(e) => {
-                    let err = ServerMessage::Error {
-                        message: format!("Database error: {e}"),
-                    };
-                    let _ = session
-                        .text(serde_json::to_string(&err).unwrap_or_default())
-                        .await;
-                    return;
+                    let err = ApiError::not_found(format!("Message {message_id} not found"));
+                    return send_error(session, err).await;
                 }
+                Err(e) => return send_error(session, e.into()).await,
             };
 
+            // A message from another contract does not exist as far as this
+            // room is concerned.
             if message.contract_id != contract_id {
-                let err = ServerMessage::Error {
-                    message: "You cannot mark messages from another contract as read".to_string(),
-                };
-                let _ = session
-                    .text(serde_json::to_string(&err).unwrap_or_default())
-                    .await;
-                return;
+                let err = ApiError::not_found(format!("Message {message_id} not found"));
+                return send_error(session, err).await;
             }
 
-            if message.sender_id == user_id {
-                let err = ServerMessage::Error {
-                    message: "You cannot mark your own message as read".to_string(),
-                };
-                let _ = session
-                    .text(serde_json::to_string(&err).unwrap_or_default())
-                    .await;
-                return;
+            let parties = match load_contract(db, contract_id).await {
+                Ok((_, parties)) => parties,
+                Err(err) => return send_error(session, err).await,
+            };
+            let resource = Resource::Message {
+                sender_id: message.sender_id,
+                contract: parties,
+            };
+            if let Err(denied) = Policy::can(actor, Action::Update, &resource) {
+                return send_error(session, denied.into()).await;
             }
 
             match message_db::mark_message_as_read(db, message_id).await {
@@ -278,12 +393,8 @@ async fn handle_client_message(
                     chat_server.broadcast(contract_id, msg, None).await;
                 }
                 Err(e) => {
-                    let err = ServerMessage::Error {
-                        message: format!("Failed to mark message as read: {e}"),
-                    };
-                    let _ = session
-                        .text(serde_json::to_string(&err).unwrap_or_default())
-                        .await;
+                    let err = ApiError::Internal(format!("Failed to mark message as read: {e}"));
+                    send_error(session, err).await;
                 }
             }
         }
@@ -300,3 +411,22 @@ async fn handle_client_message(
         }
     }
 }
+
+/// Report a rejected frame with the same message and code a REST response
+/// would carry: server-side failures are logged and sent as `internal_error`.
+async fn send_error(session: &mut actix_ws::Session, err: ApiError) {
+    let message = match &err {
+        ApiError::Database(_) | ApiError::Internal(_) => {
+            tracing::error!("{err}");
+            "Internal server error".to_string()
+        }
+        _ => err.to_string(),
+    };
+    let frame = ServerMessage::Error {
+        message,
+        code: err.code(),
+    };
+    let _ = session
+        .text(serde_json::to_string(&frame).unwrap_or_default())
+        .await;
+}
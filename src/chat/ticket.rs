@@ -0,0 +1,88 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::cache::{CacheResult, CacheStore, keys};
+
+/// Subprotocol name clients offer alongside their JWT in `Sec-WebSocket-Protocol`.
+pub const BEARER_PROTOCOL: &str = "bearer";
+
+/// How the WebSocket handshake may be authenticated.
+#[derive(Debug, Clone)]
+pub struct WsAuthConfig {
+    /// Lifetime of a ticket issued by `POST /api/chat/ws-ticket`.
+    pub ticket_ttl_secs: u64,
+    /// Accept the raw JWT in `?token=` (it ends up in proxy and access logs).
+    pub allow_query_token: bool,
+}
+
+impl Default for WsAuthConfig {
+    fn default() -> Self {
+        Self {
+            ticket_ttl_secs: 30,
+            allow_query_token: false,
+        }
+    }
+}
+
+/// Body of `POST /api/chat/ws-ticket`.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateWsTicket {
+    /// Restrict the ticket to one contract's chat.
+    pub contract_id: Option<Uuid>,
+}
+
+/// Response of `POST /api/chat/ws-ticket`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct IssuedWsTicket {
+    /// Pass as `?ticket=` when opening the chat WebSocket.
+    pub ticket: String,
+    /// Seconds until the ticket expires.
+    pub expires_in: u64,
+}
+
+/// What a ticket grants: one handshake for `user_id`, optionally only for `contract_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WsTicket {
+    pub user_id: Uuid,
+    pub contract_id: Option<Uuid>,
+}
+
+impl WsTicket {
+    /// Whether the ticket may open the chat socket of `contract_id`.
+    pub fn allows(&self, contract_id: Uuid) -> bool {
+        self.contract_id.is_none_or(|id| id == contract_id)
+    }
+}
+
+/// Generate an opaque ticket id (128 bits of entropy).
+pub fn generate_ticket_id() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Store a new ticket in Redis and return its id.
+pub async fn issue(cache: &CacheStore, ticket: &WsTicket, ttl_secs: u64) -> CacheResult<String> {
+    let id = generate_ticket_id();
+    cache
+        .set(&keys::ws_ticket(&id), ticket, Some(ttl_secs))
+        .await?;
+    Ok(id)
+}
+
+/// Consume a ticket. Returns `None` if it never existed, expired or was already used.
+pub async fn redeem(cache: &CacheStore, id: &str) -> CacheResult<Option<WsTicket>> {
+    cache.take(&keys::ws_ticket(id)).await
+}
+
+/// Extract the JWT from a `Sec-WebSocket-Protocol` header of the form `bearer, <jwt>`.
+///
+/// Browsers cannot set `Authorization` on a WebSocket handshake, but they can
+/// offer subprotocols; the server then selects `bearer` in its response.
+pub fn bearer_from_protocols(header: &str) -> Option<&str> {
+    let mut protocols = header.split(',').map(str::trim);
+    protocols.find(|p| *p == BEARER_PROTOCOL)?;
+    protocols.next().filter(|token| !token.is_empty())
+}
@@ -0,0 +1,867 @@
+use serde::Deserialize;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::auth::jwks::JwksSettings;
+use crate::auth::validation::{ValidationPolicy, supabase_issuer};
+use crate::cache::CacheConfig;
+use crate::chat::ticket::WsAuthConfig;
+use crate::rate_limit::RateLimit;
+
+/// Env var naming an optional TOML file with the settings below.
+pub const CONFIG_FILE_ENV: &str = "APP_CONFIG_FILE";
+
+/// Everything the server needs to start, loaded once in `main` and shared as app data.
+///
+/// Values come from the TOML file named by `APP_CONFIG_FILE` (if set), then
+/// from env vars, which take precedence. [`AppConfig::load`] reports every
+/// invalid or missing setting at once instead of failing on the first.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    pub server: ServerConfig,
+    pub cors: CorsConfig,
+    pub database: DatabaseConfig,
+    pub redis: RedisConfig,
+    pub cache: CacheTtlConfig,
+    pub user_cache: UserCacheConfig,
+    pub auth: AuthConfig,
+    pub ws: WsConfig,
+    pub log: LogConfig,
+    pub otel: OtelConfig,
+    pub rate_limit: RateLimitConfig,
+    pub features: FeatureFlags,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    /// Worker threads per server.
+    pub workers: usize,
+    /// How long in-flight HTTP requests get to finish after chat sessions
+    /// have drained on SIGTERM/SIGINT.
+    pub shutdown_timeout_secs: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            workers: 12,
+            shutdown_timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API from a browser. `["*"]` allows any
+    /// origin; an empty list allows none.
+    pub allowed_origins: Vec<String>,
+    pub max_age_secs: usize,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            max_age_secs: 3600,
+        }
+    }
+}
+
+impl CorsConfig {
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|o| o == "*")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub url: String,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub connect_timeout_secs: u64,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            max_connections: 10,
+            min_connections: 1,
+            connect_timeout_secs: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedisConfig {
+    pub url: String,
+    /// Refuse to start while Redis is unreachable. When `false` the server
+    /// starts without a cache and keeps reconnecting in the background.
+    pub required: bool,
+    pub reconnect_secs: u64,
+}
+
+impl Default for RedisConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            required: true,
+            reconnect_secs: 5,
+        }
+    }
+}
+
+/// Where cached data lives.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheBackendKind {
+    /// Redis at `redis.url`, shared by every instance.
+    #[default]
+    Redis,
+    /// In-process only, for local development and single-instance setups.
+    Memory,
+}
+
+impl CacheBackendKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Redis => "redis",
+            Self::Memory => "memory",
+        }
+    }
+}
+
+impl FromStr for CacheBackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "redis" => Ok(Self::Redis),
+            "memory" => Ok(Self::Memory),
+            other => Err(format!(
+                "unknown cache backend '{other}', expected one of: redis, memory"
+            )),
+        }
+    }
+}
+
+/// Response cache backend and lifetimes of cached API responses, in seconds.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheTtlConfig {
+    pub backend: CacheBackendKind,
+    pub gig_list_secs: u64,
+    pub gig_secs: u64,
+    pub user_secs: u64,
+    pub conversation_secs: u64,
+    pub message_secs: u64,
+    pub portfolio_secs: u64,
+    /// TTL of the in-process L1 (`features.cache_local`), kept short because
+    /// an instance that misses an invalidation serves its copy until then.
+    pub local_ttl_secs: u64,
+    /// Maximum entries in the in-process L1.
+    pub local_capacity: u64,
+}
+
+impl Default for CacheTtlConfig {
+    fn default() -> Self {
+        let ttls = CacheConfig::default();
+        Self {
+            backend: CacheBackendKind::default(),
+            gig_list_secs: ttls.gig_list_ttl.as_secs(),
+            gig_secs: ttls.gig_ttl.as_secs(),
+            user_secs: ttls.user_ttl.as_secs(),
+            conversation_secs: ttls.conversation_ttl.as_secs(),
+            message_secs: ttls.message_ttl.as_secs(),
+            portfolio_secs: ttls.portfolio_ttl.as_secs(),
+            local_ttl_secs: 5,
+            local_capacity: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UserCacheConfig {
+    pub ttl_secs: u64,
+    pub capacity: u64,
+}
+
+impl Default for UserCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 30,
+            capacity: 10_000,
+        }
+    }
+}
+
+/// How bearer tokens are verified.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifierKind {
+    /// Keys published by Supabase, fetched and refreshed at runtime.
+    #[default]
+    Jwks,
+    /// The project's shared HS256 secret.
+    Hs256,
+    /// A JWKS document on disk.
+    JwksFile,
+}
+
+impl VerifierKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Jwks => "jwks",
+            Self::Hs256 => "hs256",
+            Self::JwksFile => "jwks_file",
+        }
+    }
+}
+
+impl FromStr for VerifierKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "jwks" => Ok(Self::Jwks),
+            "hs256" => Ok(Self::Hs256),
+            "jwks_file" => Ok(Self::JwksFile),
+            other => Err(format!(
+                "unknown verifier '{other}', expected one of: jwks, hs256, jwks_file"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    pub verifier: VerifierKind,
+    pub supabase_url: Option<String>,
+    pub supabase_anon_key: Option<String>,
+    pub jwt_secret: Option<String>,
+    pub jwks_file: Option<String>,
+    /// Expected `iss`; defaults to `$supabase_url/auth/v1`, empty disables the check.
+    pub issuer: Option<String>,
+    /// Accepted `aud` values; empty disables the check.
+    pub audiences: Vec<String>,
+    pub leeway_secs: u64,
+    pub require_email: bool,
+    pub jwks_refresh_secs: u64,
+    pub jwks_min_refetch_secs: u64,
+    pub jwks_negative_ttl_secs: u64,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        let policy = ValidationPolicy::default();
+        let jwks = JwksSettings::default();
+        Self {
+            verifier: VerifierKind::default(),
+            supabase_url: None,
+            supabase_anon_key: None,
+            jwt_secret: None,
+            jwks_file: None,
+            issuer: None,
+            audiences: policy.audiences,
+            leeway_secs: policy.leeway_secs,
+            require_email: policy.require_email,
+            jwks_refresh_secs: jwks.refresh_interval.as_secs(),
+            jwks_min_refetch_secs: jwks.min_refetch_interval.as_secs(),
+            jwks_negative_ttl_secs: jwks.negative_ttl.as_secs(),
+        }
+    }
+}
+
+impl AuthConfig {
+    /// The validation policy shared by every verifier.
+    pub fn validation_policy(&self) -> ValidationPolicy {
+        let issuer = self
+            .issuer
+            .clone()
+            .or_else(|| self.supabase_url.as_deref().map(supabase_issuer));
+        ValidationPolicy {
+            issuer: issuer.filter(|iss| !iss.is_empty()),
+            audiences: self.audiences.clone(),
+            leeway_secs: self.leeway_secs,
+            require_email: self.require_email,
+        }
+    }
+
+    pub fn jwks_settings(&self) -> JwksSettings {
+        JwksSettings {
+            refresh_interval: Duration::from_secs(self.jwks_refresh_secs),
+            min_refetch_interval: Duration::from_secs(self.jwks_min_refetch_secs),
+            negative_ttl: Duration::from_secs(self.jwks_negative_ttl_secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WsConfig {
+    pub ticket_ttl_secs: u64,
+    /// Reconnect delay suggested to clients in `server_restarting`.
+    pub reconnect_after_ms: u64,
+    /// How long shutdown waits for message writes and sessions to finish.
+    pub drain_timeout_secs: u64,
+}
+
+impl Default for WsConfig {
+    fn default() -> Self {
+        Self {
+            ticket_ttl_secs: WsAuthConfig::default().ticket_ttl_secs,
+            reconnect_after_ms: 1000,
+            drain_timeout_secs: 10,
+        }
+    }
+}
+
+/// How log lines are written to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Human-readable lines, for local development.
+    #[default]
+    Text,
+    /// One JSON object per line with the current span fields, for log shippers.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "unknown log format '{other}', expected one of: text, json"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    pub format: LogFormat,
+    /// `tracing` filter directives; `RUST_LOG` takes precedence when set.
+    pub filter: String,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::Text,
+            filter: "info".to_string(),
+        }
+    }
+}
+
+/// OpenTelemetry trace export over OTLP/HTTP.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OtelConfig {
+    pub enabled: bool,
+    /// Collector base URL; spans are posted to `<endpoint>/v1/traces`.
+    pub endpoint: String,
+    pub service_name: String,
+    /// Share of new traces to record; requests with a sampled parent always are.
+    pub sample_ratio: f64,
+}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318".to_string(),
+            service_name: "gradwork-backend".to_string(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+/// Per-client request budgets, as `<requests>/<s|min|h>`. Clients are the
+/// user behind a valid bearer token, or else the client IP.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    /// Take the client IP from `X-Forwarded-For`/`Forwarded`; only safe behind
+    /// a proxy that overwrites them.
+    pub trust_proxy: bool,
+    /// `/api/auth/*` and WebSocket tickets.
+    pub auth: RateLimit,
+    /// Creating, updating and deleting contracts.
+    pub contract_writes: RateLimit,
+    /// Gig listings and details, which need no account.
+    pub gig_reads: RateLimit,
+    /// Every other write.
+    pub writes: RateLimit,
+    /// Every other request.
+    pub default: RateLimit,
+    pub chat_send_message: RateLimit,
+    pub chat_mark_read: RateLimit,
+    /// `typing` and `stop_typing` frames together.
+    pub chat_typing: RateLimit,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            trust_proxy: false,
+            auth: RateLimit::per_minute(30),
+            contract_writes: RateLimit::per_minute(10),
+            gig_reads: RateLimit::per_minute(120),
+            writes: RateLimit::per_minute(60),
+            default: RateLimit::per_minute(300),
+            chat_send_message: RateLimit::per_minute(30),
+            chat_mark_read: RateLimit::per_minute(120),
+            chat_typing: RateLimit::per_minute(60),
+        }
+    }
+}
+
+/// Optional behaviour that is off by default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeatureFlags {
+    /// Accept the raw JWT in `?token=` on the chat WebSocket.
+    pub ws_query_token: bool,
+    /// Share cached users between instances through Redis.
+    pub user_cache_redis: bool,
+    /// Serve hot cached responses from an in-process L1 in front of Redis.
+    pub cache_local: bool,
+}
+
+/// Every problem found while loading the configuration.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid configuration:\n  - {}", .0.join("\n  - "))]
+pub struct ConfigError(pub Vec<String>);
+
+impl AppConfig {
+    /// Load from `APP_CONFIG_FILE` (if set) and the process environment.
+    pub fn load() -> Result<Self, ConfigError> {
+        let file = match std::env::var(CONFIG_FILE_ENV) {
+            Ok(path) => Some(std::fs::read_to_string(&path).map_err(|e| {
+                ConfigError(vec![format!("{CONFIG_FILE_ENV}: cannot read {path}: {e}")])
+            })?),
+            Err(_) => None,
+        };
+        Self::from_sources(file.as_deref(), |name| std::env::var(name).ok())
+    }
+
+    /// Build from optional TOML text and an env lookup, then validate.
+    pub fn from_sources(
+        toml: Option<&str>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let mut errors = Vec::new();
+        // A broken file is reported with the env errors, checked against defaults.
+        let mut config: Self = match toml {
+            Some(text) => toml::from_str(text).unwrap_or_else(|e| {
+                errors.push(format!("config file: {e}"));
+                Self::default()
+            }),
+            None => Self::default(),
+        };
+
+        config.apply_env(&EnvReader { env: &env }, &mut errors);
+        config.validate(&mut errors);
+
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError(errors))
+        }
+    }
+
+    fn apply_env(&mut self, env: &EnvReader<'_>, errors: &mut Vec<String>) {
+        env.string("HOST", &mut self.server.host);
+        env.parse("PORT", &mut self.server.port, errors);
+        env.parse("WORKERS", &mut self.server.workers, errors);
+        env.parse(
+            "SHUTDOWN_TIMEOUT_SECS",
+            &mut self.server.shutdown_timeout_secs,
+            errors,
+        );
+
+        env.list("CORS_ALLOWED_ORIGINS", &mut self.cors.allowed_origins);
+        env.parse("CORS_MAX_AGE_SECS", &mut self.cors.max_age_secs, errors);
+
+        let db = &mut self.database;
+        env.string("DATABASE_URL", &mut db.url);
+        env.parse("DATABASE_MAX_CONNECTIONS", &mut db.max_connections, errors);
+        env.parse("DATABASE_MIN_CONNECTIONS", &mut db.min_connections, errors);
+        env.parse(
+            "DATABASE_CONNECT_TIMEOUT_SECS",
+            &mut db.connect_timeout_secs,
+            errors,
+        );
+
+        env.string("REDIS_URL", &mut self.redis.url);
+        env.parse("REDIS_REQUIRED", &mut self.redis.required, errors);
+        env.parse(
+            "REDIS_RECONNECT_SECS",
+            &mut self.redis.reconnect_secs,
+            errors,
+        );
+        env.parse("CACHE_BACKEND", &mut self.cache.backend, errors);
+
+        env.parse("CACHE_TTL_GIGS", &mut self.cache.gig_list_secs, errors);
+        env.parse("CACHE_TTL_GIG_DETAIL", &mut self.cache.gig_secs, errors);
+        env.parse("CACHE_TTL_USERS", &mut self.cache.user_secs, errors);
+        env.parse(
+            "CACHE_TTL_CONVERSATIONS",
+            &mut self.cache.conversation_secs,
+            errors,
+        );
+        env.parse("CACHE_TTL_MESSAGES", &mut self.cache.message_secs, errors);
+        env.parse(
+            "CACHE_TTL_PORTFOLIOS",
+            &mut self.cache.portfolio_secs,
+            errors,
+        );
+        env.parse(
+            "CACHE_LOCAL_TTL_SECS",
+            &mut self.cache.local_ttl_secs,
+            errors,
+        );
+        env.parse(
+            "CACHE_LOCAL_CAPACITY",
+            &mut self.cache.local_capacity,
+            errors,
+        );
+
+        env.parse(
+            "AUTH_USER_CACHE_TTL_SECS",
+            &mut self.user_cache.ttl_secs,
+            errors,
+        );
+        env.parse(
+            "AUTH_USER_CACHE_CAPACITY",
+            &mut self.user_cache.capacity,
+            errors,
+        );
+
+        let auth = &mut self.auth;
+        env.parse("AUTH_VERIFIER", &mut auth.verifier, errors);
+        env.string_opt("SUPABASE_URL", &mut auth.supabase_url);
+        env.string_opt("SUPABASE_ANON_KEY", &mut auth.supabase_anon_key);
+        env.string_opt("SUPABASE_JWT_SECRET", &mut auth.jwt_secret);
+        env.string_opt("JWKS_FILE", &mut auth.jwks_file);
+        env.string_opt("JWT_ISSUER", &mut auth.issuer);
+        env.list("JWT_AUDIENCES", &mut auth.audiences);
+        env.parse("JWT_LEEWAY_SECS", &mut auth.leeway_secs, errors);
+        env.parse("JWT_REQUIRE_EMAIL", &mut auth.require_email, errors);
+        env.parse("JWKS_REFRESH_SECS", &mut auth.jwks_refresh_secs, errors);
+        env.parse(
+            "JWKS_MIN_REFETCH_SECS",
+            &mut auth.jwks_min_refetch_secs,
+            errors,
+        );
+        env.parse(
+            "JWKS_NEGATIVE_TTL_SECS",
+            &mut auth.jwks_negative_ttl_secs,
+            errors,
+        );
+
+        env.parse("WS_TICKET_TTL_SECS", &mut self.ws.ticket_ttl_secs, errors);
+        env.parse(
+            "WS_RECONNECT_AFTER_MS",
+            &mut self.ws.reconnect_after_ms,
+            errors,
+        );
+        env.parse(
+            "WS_DRAIN_TIMEOUT_SECS",
+            &mut self.ws.drain_timeout_secs,
+            errors,
+        );
+
+        env.parse("LOG_FORMAT", &mut self.log.format, errors);
+        env.string("RUST_LOG", &mut self.log.filter);
+
+        env.parse("OTEL_ENABLED", &mut self.otel.enabled, errors);
+        env.string("OTEL_EXPORTER_OTLP_ENDPOINT", &mut self.otel.endpoint);
+        env.string("OTEL_SERVICE_NAME", &mut self.otel.service_name);
+        env.parse("OTEL_SAMPLE_RATIO", &mut self.otel.sample_ratio, errors);
+
+        let limits = &mut self.rate_limit;
+        env.parse("RATE_LIMIT_ENABLED", &mut limits.enabled, errors);
+        env.parse("RATE_LIMIT_TRUST_PROXY", &mut limits.trust_proxy, errors);
+        env.parse("RATE_LIMIT_AUTH", &mut limits.auth, errors);
+        env.parse(
+            "RATE_LIMIT_CONTRACT_WRITES",
+            &mut limits.contract_writes,
+            errors,
+        );
+        env.parse("RATE_LIMIT_GIG_READS", &mut limits.gig_reads, errors);
+        env.parse("RATE_LIMIT_WRITES", &mut limits.writes, errors);
+        env.parse("RATE_LIMIT_DEFAULT", &mut limits.default, errors);
+        env.parse(
+            "RATE_LIMIT_CHAT_SEND_MESSAGE",
+            &mut limits.chat_send_message,
+            errors,
+        );
+        env.parse(
+            "RATE_LIMIT_CHAT_MARK_READ",
+            &mut limits.chat_mark_read,
+            errors,
+        );
+        env.parse("RATE_LIMIT_CHAT_TYPING", &mut limits.chat_typing, errors);
+
+        env.parse(
+            "WS_ALLOW_QUERY_TOKEN",
+            &mut self.features.ws_query_token,
+            errors,
+        );
+        env.parse(
+            "AUTH_USER_CACHE_REDIS",
+            &mut self.features.user_cache_redis,
+            errors,
+        );
+        env.parse("CACHE_LOCAL", &mut self.features.cache_local, errors);
+    }
+
+    fn validate(&self, errors: &mut Vec<String>) {
+        let mut check = |ok: bool, message: String| {
+            if !ok {
+                errors.push(message);
+            }
+        };
+
+        let server = &self.server;
+        check(server.port != 0, "server.port (PORT) must not be 0".into());
+        check(
+            server.workers > 0,
+            "server.workers (WORKERS) must be at least 1".into(),
+        );
+
+        let cors = &self.cors;
+        check(
+            !cors.allows_any_origin() || cors.allowed_origins.len() == 1,
+            "cors.allowed_origins (CORS_ALLOWED_ORIGINS) cannot mix \"*\" with other origins"
+                .into(),
+        );
+        for origin in cors.allowed_origins.iter().filter(|o| *o != "*") {
+            let scheme = origin.starts_with("http://") || origin.starts_with("https://");
+            check(
+                scheme && !origin.ends_with('/'),
+                format!(
+                    "cors.allowed_origins (CORS_ALLOWED_ORIGINS): '{origin}' is not an origin \
+                     like https://app.example.com"
+                ),
+            );
+        }
+
+        let db = &self.database;
+        check(
+            !db.url.is_empty(),
+            "database.url (DATABASE_URL) must be set".into(),
+        );
+        check(
+            db.max_connections >= 1,
+            "database.max_connections (DATABASE_MAX_CONNECTIONS) must be at least 1".into(),
+        );
+        check(
+            db.min_connections <= db.max_connections,
+            "database.min_connections (DATABASE_MIN_CONNECTIONS) must not exceed max_connections"
+                .into(),
+        );
+        check(
+            db.connect_timeout_secs >= 1,
+            "database.connect_timeout_secs (DATABASE_CONNECT_TIMEOUT_SECS) must be at least 1"
+                .into(),
+        );
+        check(
+            self.cache.backend != CacheBackendKind::Redis || !self.redis.url.is_empty(),
+            "redis.url (REDIS_URL) must be set for cache backend redis".into(),
+        );
+        if self.cache.backend == CacheBackendKind::Redis
+            && !self.redis.url.is_empty()
+            && let Err(e) = redis::Client::open(self.redis.url.as_str())
+        {
+            check(false, format!("redis.url (REDIS_URL): {e}"));
+        }
+
+        let auth = &self.auth;
+        let positive = [
+            (
+                "redis.reconnect_secs (REDIS_RECONNECT_SECS)",
+                self.redis.reconnect_secs,
+            ),
+            (
+                "cache.gig_list_secs (CACHE_TTL_GIGS)",
+                self.cache.gig_list_secs,
+            ),
+            ("cache.gig_secs (CACHE_TTL_GIG_DETAIL)", self.cache.gig_secs),
+            ("cache.user_secs (CACHE_TTL_USERS)", self.cache.user_secs),
+            (
+                "cache.conversation_secs (CACHE_TTL_CONVERSATIONS)",
+                self.cache.conversation_secs,
+            ),
+            (
+                "cache.message_secs (CACHE_TTL_MESSAGES)",
+                self.cache.message_secs,
+            ),
+            (
+                "cache.portfolio_secs (CACHE_TTL_PORTFOLIOS)",
+                self.cache.portfolio_secs,
+            ),
+            (
+                "cache.local_ttl_secs (CACHE_LOCAL_TTL_SECS)",
+                self.cache.local_ttl_secs,
+            ),
+            (
+                "cache.local_capacity (CACHE_LOCAL_CAPACITY)",
+                self.cache.local_capacity,
+            ),
+            (
+                "user_cache.ttl_secs (AUTH_USER_CACHE_TTL_SECS)",
+                self.user_cache.ttl_secs,
+            ),
+            (
+                "user_cache.capacity (AUTH_USER_CACHE_CAPACITY)",
+                self.user_cache.capacity,
+            ),
+            (
+                "ws.ticket_ttl_secs (WS_TICKET_TTL_SECS)",
+                self.ws.ticket_ttl_secs,
+            ),
+            (
+                "ws.drain_timeout_secs (WS_DRAIN_TIMEOUT_SECS)",
+                self.ws.drain_timeout_secs,
+            ),
+            (
+                "auth.jwks_refresh_secs (JWKS_REFRESH_SECS)",
+                auth.jwks_refresh_secs,
+            ),
+            (
+                "auth.jwks_min_refetch_secs (JWKS_MIN_REFETCH_SECS)",
+                auth.jwks_min_refetch_secs,
+            ),
+        ];
+        for (name, value) in positive {
+            check(value > 0, format!("{name} must be greater than 0"));
+        }
+
+        if let Err(e) = tracing_subscriber::EnvFilter::try_new(&self.log.filter) {
+            check(false, format!("log.filter (RUST_LOG): {e}"));
+        }
+
+        let otel = &self.otel;
+        check(
+            (0.0..=1.0).contains(&otel.sample_ratio),
+            "otel.sample_ratio (OTEL_SAMPLE_RATIO) must be between 0 and 1".into(),
+        );
+        if otel.enabled {
+            check(
+                otel.endpoint.starts_with("http://") || otel.endpoint.starts_with("https://"),
+                format!(
+                    "otel.endpoint (OTEL_EXPORTER_OTLP_ENDPOINT): '{}' is not an http(s) URL",
+                    otel.endpoint
+                ),
+            );
+            check(
+                !otel.service_name.is_empty(),
+                "otel.service_name (OTEL_SERVICE_NAME) must not be empty".into(),
+            );
+        }
+
+        let required: &[(&Option<String>, &str)] = match auth.verifier {
+            VerifierKind::Jwks => &[
+                (&auth.supabase_url, "auth.supabase_url (SUPABASE_URL)"),
+                (
+                    &auth.supabase_anon_key,
+                    "auth.supabase_anon_key (SUPABASE_ANON_KEY)",
+                ),
+            ],
+            VerifierKind::Hs256 => &[(&auth.jwt_secret, "auth.jwt_secret (SUPABASE_JWT_SECRET)")],
+            VerifierKind::JwksFile => &[(&auth.jwks_file, "auth.jwks_file (JWKS_FILE)")],
+        };
+        for (value, name) in required {
+            check(
+                value.as_deref().is_some_and(|v| !v.is_empty()),
+                format!("{name} must be set for verifier {}", auth.verifier.as_str()),
+            );
+        }
+    }
+
+    pub fn cache_config(&self) -> CacheConfig {
+        CacheConfig {
+            gig_list_ttl: Duration::from_secs(self.cache.gig_list_secs),
+            gig_ttl: Duration::from_secs(self.cache.gig_secs),
+            user_ttl: Duration::from_secs(self.cache.user_secs),
+            conversation_ttl: Duration::from_secs(self.cache.conversation_secs),
+            message_ttl: Duration::from_secs(self.cache.message_secs),
+            portfolio_ttl: Duration::from_secs(self.cache.portfolio_secs),
+        }
+    }
+
+    pub fn ws_auth(&self) -> WsAuthConfig {
+        WsAuthConfig {
+            ticket_ttl_secs: self.ws.ticket_ttl_secs,
+            allow_query_token: self.features.ws_query_token,
+        }
+    }
+}
+
+/// Reads env overrides, recording values that fail to parse.
+struct EnvReader<'a> {
+    env: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl EnvReader<'_> {
+    fn get(&self, name: &str) -> Option<String> {
+        (self.env)(name)
+    }
+
+    fn string(&self, name: &str, target: &mut String) {
+        if let Some(value) = self.get(name) {
+            *target = value;
+        }
+    }
+
+    fn string_opt(&self, name: &str, target: &mut Option<String>) {
+        if let Some(value) = self.get(name) {
+            *target = Some(value);
+        }
+    }
+
+    /// Comma-separated list; an empty value clears the list.
+    fn list(&self, name: &str, target: &mut Vec<String>) {
+        if let Some(value) = self.get(name) {
+            *target = value
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+                .collect();
+        }
+    }
+
+    fn parse<T>(&self, name: &str, target: &mut T, errors: &mut Vec<String>)
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        if let Some(value) = self.get(name) {
+            match value.trim().parse() {
+                Ok(parsed) => *target = parsed,
+                Err(e) => errors.push(format!("{name}: invalid value '{value}': {e}")),
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use sea_orm::prelude::Expr;
+use sea_orm::*;
+use uuid::Uuid;
+
+use crate::models::api_keys::{self, ApiScope};
+
+/// Insert a new API key. `key_hash` and `prefix` come from `auth::api_keys::generate`.
+pub async fn insert_api_key(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    name: String,
+    prefix: String,
+    key_hash: String,
+    scopes: &[ApiScope],
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<api_keys::Model, DbErr> {
+    let scopes = scopes
+        .iter()
+        .map(ApiScope::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let new_key = api_keys::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        name: Set(name),
+        prefix: Set(prefix),
+        key_hash: Set(key_hash),
+        scopes: Set(scopes),
+        expires_at: Set(expires_at),
+        last_used_at: Set(None),
+        revoked_at: Set(None),
+        created_at: Set(chrono::Utc::now()),
+    };
+
+    new_key.insert(db).await
+}
+
+/// Look up a key by the hash of its secret.
+pub async fn get_api_key_by_hash(
+    db: &DatabaseConnection,
+    key_hash: &str,
+) -> Result<Option<api_keys::Model>, DbErr> {
+    api_keys::Entity::find()
+        .filter(api_keys::Column::KeyHash.eq(key_hash))
+        .one(db)
+        .await
+}
+
+/// Fetch a single key by ID.
+pub async fn get_api_key_by_id(
+    db: &DatabaseConnection,
+    id: Uuid,
+) -> Result<Option<api_keys::Model>, DbErr> {
+    api_keys::Entity::find_by_id(id).one(db).await
+}
+
+/// Fetch all keys of a user, newest first (including revoked ones).
+pub async fn get_api_keys_by_user(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<api_keys::Model>, DbErr> {
+    api_keys::Entity::find()
+        .filter(api_keys::Column::UserId.eq(user_id))
+        .order_by_desc(api_keys::Column::CreatedAt)
+        .all(db)
+        .await
+}
+
+/// Mark a key as revoked. Revoked keys are kept for auditing.
+pub async fn revoke_api_key(db: &DatabaseConnection, id: Uuid) -> Result<api_keys::Model, DbErr> {
+    let key = api_keys::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound("API key not found".to_string()))?;
+
+    if key.revoked_at.is_some() {
+        return Ok(key);
+    }
+
+    let mut active: api_keys::ActiveModel = key.into();
+    active.revoked_at = Set(Some(chrono::Utc::now()));
+
+    active.update(db).await
+}
+
+/// Record that a key was just used.
+pub async fn touch_api_key(db: &DatabaseConnection, id: Uuid) -> Result<(), DbErr> {
+    api_keys::Entity::update_many()
+        .col_expr(
+            api_keys::Column::LastUsedAt,
+            Expr::value(chrono::Utc::now()),
+        )
+        .filter(api_keys::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
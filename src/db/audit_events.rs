@@ -0,0 +1,71 @@
+use sea_orm::*;
+use uuid::Uuid;
+
+use crate::models::audit_events::{self, AuditQuery, NewAuditEvent};
+
+/// Append an audit event. Events are never updated or deleted.
+pub async fn insert_audit_event(
+    db: &DatabaseConnection,
+    input: NewAuditEvent,
+) -> Result<audit_events::Model, DbErr> {
+    let event = audit_events::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        actor_id: Set(input.actor_id),
+        action: Set(input.action),
+        target_type: Set(input.target_type),
+        target_id: Set(input.target_id),
+        before: Set(input.before),
+        after: Set(input.after),
+        ip: Set(input.ip),
+        request_id: Set(input.request_id),
+        created_at: Set(chrono::Utc::now()),
+    };
+
+    event.insert(db).await
+}
+
+/// Fetch audit events matching `query`, newest first, with cursor pagination.
+pub async fn query_audit_events(
+    db: &DatabaseConnection,
+    query: &AuditQuery,
+) -> Result<Vec<audit_events::Model>, DbErr> {
+    let mut select = audit_events::Entity::find();
+
+    if let Some(actor_id) = query.actor_id {
+        select = select.filter(audit_events::Column::ActorId.eq(actor_id));
+    }
+    if let Some(target_type) = &query.target_type {
+        select = select.filter(audit_events::Column::TargetType.eq(target_type.as_str()));
+    }
+    if let Some(target_id) = query.target_id {
+        select = select.filter(audit_events::Column::TargetId.eq(target_id));
+    }
+    if let Some(action) = &query.action {
+        select = select.filter(audit_events::Column::Action.eq(action.as_str()));
+    }
+    if let Some(from) = query.from {
+        select = select.filter(audit_events::Column::CreatedAt.gte(from));
+    }
+    if let Some(to) = query.to {
+        select = select.filter(audit_events::Column::CreatedAt.lt(to));
+    }
+
+    if let (Some(cursor_created_at), Some(cursor_id)) = (query.cursor_created_at, query.cursor_id) {
+        select = select.filter(
+            Condition::any()
+                .add(audit_events::Column::CreatedAt.lt(cursor_created_at))
+                .add(
+                    Condition::all()
+                        .add(audit_events::Column::CreatedAt.eq(cursor_created_at))
+                        .add(audit_events::Column::Id.lt(cursor_id)),
+                ),
+        );
+    }
+
+    select
+        .order_by_desc(audit_events::Column::CreatedAt)
+        .order_by_desc(audit_events::Column::Id)
+        .limit(query.limit())
+        .all(db)
+        .await
+}
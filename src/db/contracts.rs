@@ -24,6 +24,20 @@ pub async fn get_all_contracts(db: &DatabaseConnection) -> Result<Vec<contracts:
     contracts::Entity::find().all(db).await
 }
 
+/// Fetch contracts with pagination, newest first.
+pub async fn get_contracts_paginated(
+    db: &DatabaseConnection,
+    page: u64,
+    limit: u64,
+) -> Result<Vec<contracts::Model>, DbErr> {
+    contracts::Entity::find()
+        .order_by_desc(contracts::Column::CreatedAt)
+        .offset((page - 1) * limit)
+        .limit(limit)
+        .all(db)
+        .await
+}
+
 /// Fetch a single contract by ID.
 pub async fn get_contract_by_id(
     db: &DatabaseConnection,
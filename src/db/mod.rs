@@ -1,16 +1,32 @@
+pub mod api_keys;
+pub mod audit_events;
 pub mod contracts;
 pub mod gigs;
 pub mod messages;
 pub mod portfolio;
 pub mod users;
 
-use sea_orm::{Database, DatabaseConnection};
-use std::env;
+use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr, SqlErr};
+use std::time::Duration;
 
-/// Create a SeaORM database connection pool from the `DATABASE_URL` env var.
-pub async fn create_pool() -> DatabaseConnection {
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    Database::connect(&database_url)
+use crate::config::DatabaseConfig;
+use crate::metrics::metrics;
+
+/// Create a SeaORM database connection pool from the `database` settings.
+pub async fn create_pool(config: &DatabaseConfig) -> DatabaseConnection {
+    let mut options = ConnectOptions::new(&config.url);
+    options
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs));
+    let mut db = Database::connect(options)
         .await
-        .expect("Failed to connect to database")
+        .expect("Failed to connect to database");
+    db.set_metric_callback(|info| metrics().observe_query(info));
+    db
+}
+
+/// Whether `err` is a unique-constraint violation (e.g. a duplicate email or contract).
+pub fn is_unique_violation(err: &DbErr) -> bool {
+    matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_)))
 }
@@ -26,6 +26,20 @@ pub async fn get_all_portfolios(db: &DatabaseConnection) -> Result<Vec<portfolio
     portfolio::Entity::find().all(db).await
 }
 
+/// Fetch portfolio items with pagination, newest first.
+pub async fn get_portfolios_paginated(
+    db: &DatabaseConnection,
+    page: u64,
+    limit: u64,
+) -> Result<Vec<portfolio::Model>, DbErr> {
+    portfolio::Entity::find()
+        .order_by_desc(portfolio::Column::CreatedAt)
+        .offset((page - 1) * limit)
+        .limit(limit)
+        .all(db)
+        .await
+}
+
 /// Fetch a single portfolio item by ID.
 pub async fn get_portfolio_by_id(
     db: &DatabaseConnection,
@@ -1,45 +1,120 @@
 use sea_orm::*;
 use uuid::Uuid;
 
-use crate::models::users::{self, CompleteProfile, CreateUserFromAuth, UpdateUser};
+use crate::db::is_unique_violation;
+use crate::models::users::{self, AccountStatus, CompleteProfile, CreateUserFromAuth, UpdateUser};
 
-fn is_unique_violation(err: &DbErr) -> bool {
-    err.to_string()
-        .to_lowercase()
-        .contains("duplicate key value violates unique constraint")
-}
+/// Minimum time between two claim syncs that change the same user.
+const PROFILE_SYNC_INTERVAL: chrono::Duration = chrono::Duration::minutes(15);
 
-/// Create a new user from Supabase Auth JWT claims (called by auth middleware).
+/// Find or create a user from Supabase Auth JWT claims (called by auth middleware).
+///
+/// Existing users are synced with the claims (see [`sync_from_auth`]). The flag
+/// is `true` when the stored profile changed, so callers can drop cached copies.
 pub async fn find_or_create_from_auth(
     db: &DatabaseConnection,
     input: CreateUserFromAuth,
-) -> Result<users::Model, DbErr> {
+) -> Result<(users::Model, bool), DbErr> {
     // Try to find the user first (by Supabase auth UUID).
     if let Some(existing) = users::Entity::find_by_id(input.id).one(db).await? {
-        return Ok(existing);
+        return sync_from_auth(db, existing, &input).await;
     }
 
-    // User doesn't exist yet — create from JWT claims.
+    // User doesn't exist yet — create from JWT claims. Tokens without an email
+    // get a stable placeholder so the unique column holds.
+    let email = input
+        .email
+        .unwrap_or_else(|| format!("{}@users.invalid", input.id));
+    let now = chrono::Utc::now();
     let new_user = users::ActiveModel {
         id: Set(input.id),
-        email: Set(input.email),
+        email: Set(email),
         username: Set(None),
         display_name: Set(input.display_name),
         avatar_url: Set(input.avatar_url),
         auth_provider: Set(input.auth_provider),
         role: Set(input.role),
-        created_at: Set(chrono::Utc::now()),
+        created_at: Set(now),
         updated_at: Set(None),
+        status: Set(AccountStatus::Active),
+        suspended_until: Set(None),
+        status_reason: Set(None),
+        last_synced_at: Set(Some(now)),
+        email_locked: Set(false),
+        display_name_locked: Set(false),
+        avatar_url_locked: Set(false),
     };
 
     match new_user.insert(db).await {
-        Ok(created) => Ok(created),
+        Ok(created) => Ok((created, true)),
         Err(e) if is_unique_violation(&e) => {
             // Handle concurrent first-login races where another request inserted first.
-            users::Entity::find_by_id(input.id)
+            let existing = users::Entity::find_by_id(input.id)
                 .one(db)
                 .await?
-                .ok_or(e)
+                .ok_or(e)?;
+            Ok((existing, false))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Update provider, email, display name and avatar from the claims when they
+/// differ from the stored profile.
+///
+/// Fields the user edited themselves (`*_locked`) are left alone, and a user is
+/// synced at most once per [`PROFILE_SYNC_INTERVAL`] so flapping claims don't
+/// cause a write per request.
+pub async fn sync_from_auth(
+    db: &DatabaseConnection,
+    user: users::Model,
+    input: &CreateUserFromAuth,
+) -> Result<(users::Model, bool), DbErr> {
+    let recently_synced = user
+        .last_synced_at
+        .is_some_and(|at| chrono::Utc::now() - at < PROFILE_SYNC_INTERVAL);
+    if recently_synced {
+        return Ok((user, false));
+    }
+
+    let mut active: users::ActiveModel = user.clone().into();
+    let mut changed = false;
+
+    if user.auth_provider != input.auth_provider {
+        active.auth_provider = Set(input.auth_provider.clone());
+        changed = true;
+    }
+    if let Some(email) = &input.email
+        && !user.email_locked
+        && &user.email != email
+    {
+        active.email = Set(email.clone());
+        changed = true;
+    }
+    if input.display_name.is_some()
+        && !user.display_name_locked
+        && user.display_name != input.display_name
+    {
+        active.display_name = Set(input.display_name.clone());
+        changed = true;
+    }
+    if input.avatar_url.is_some() && !user.avatar_url_locked && user.avatar_url != input.avatar_url
+    {
+        active.avatar_url = Set(input.avatar_url.clone());
+        changed = true;
+    }
+
+    if !changed {
+        return Ok((user, false));
+    }
+
+    active.last_synced_at = Set(Some(chrono::Utc::now()));
+    match active.update(db).await {
+        Ok(updated) => Ok((updated, true)),
+        Err(e) if is_unique_violation(&e) => {
+            // The new email belongs to another account; keep the stored profile.
+            tracing::warn!("Skipping profile sync for user {}: {e}", user.id);
+            Ok((user, false))
         }
         Err(e) => Err(e),
     }
@@ -107,9 +182,11 @@ pub async fn complete_profile(
     }
     if let Some(display_name) = input.display_name {
         active.display_name = Set(Some(display_name));
+        active.display_name_locked = Set(true);
     }
     if let Some(avatar_url) = input.avatar_url {
         active.avatar_url = Set(Some(avatar_url));
+        active.avatar_url_locked = Set(true);
     }
     active.updated_at = Set(Some(chrono::Utc::now()));
 
@@ -131,15 +208,18 @@ pub async fn update_user(
 
     if let Some(email) = input.email {
         active.email = Set(email);
+        active.email_locked = Set(true);
     }
     if let Some(username) = input.username {
         active.username = Set(Some(username));
     }
     if let Some(display_name) = input.display_name {
         active.display_name = Set(Some(display_name));
+        active.display_name_locked = Set(true);
     }
     if let Some(avatar_url) = input.avatar_url {
         active.avatar_url = Set(Some(avatar_url));
+        active.avatar_url_locked = Set(true);
     }
     if let Some(role) = input.role {
         active.role = Set(role);
@@ -149,6 +229,46 @@ pub async fn update_user(
     active.update(db).await
 }
 
+/// Change a user's role (admin only).
+pub async fn set_user_role(
+    db: &DatabaseConnection,
+    id: Uuid,
+    role: users::Roles,
+) -> Result<users::Model, DbErr> {
+    let user = users::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound("User not found".to_string()))?;
+
+    let mut active: users::ActiveModel = user.into();
+    active.role = Set(role);
+    active.updated_at = Set(Some(chrono::Utc::now()));
+
+    active.update(db).await
+}
+
+/// Set a user's moderation status. `suspended_until` only applies to suspensions.
+pub async fn set_user_status(
+    db: &DatabaseConnection,
+    id: Uuid,
+    status: AccountStatus,
+    suspended_until: Option<chrono::DateTime<chrono::Utc>>,
+    reason: Option<String>,
+) -> Result<users::Model, DbErr> {
+    let user = users::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound("User not found".to_string()))?;
+
+    let mut active: users::ActiveModel = user.into();
+    active.status = Set(status);
+    active.suspended_until = Set(suspended_until);
+    active.status_reason = Set(reason);
+    active.updated_at = Set(Some(chrono::Utc::now()));
+
+    active.update(db).await
+}
+
 /// Delete a user by ID.
 pub async fn delete_user(db: &DatabaseConnection, id: Uuid) -> Result<DeleteResult, DbErr> {
     users::Entity::delete_by_id(id).exec(db).await
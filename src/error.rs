@@ -0,0 +1,164 @@
+use actix_web::http::StatusCode;
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::{HttpRequest, HttpResponse, ResponseError, error, web};
+use sea_orm::DbErr;
+use serde::Serialize;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use crate::auth::error::AuthError;
+use crate::auth::policy::Denied;
+use crate::db::is_unique_violation;
+use crate::middleware::request_id;
+use crate::rate_limit::retry_after_secs;
+
+/// Errors returned by handlers.
+///
+/// Every error is rendered as `{ "error", "code", "request_id" }`. Server-side
+/// failures are logged with their detail and reported to the client only as
+/// `internal_error`, so database and driver messages never leak.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    Validation(String),
+    /// The server cannot take the request right now (e.g. it is shutting down).
+    #[error("{0}")]
+    Unavailable(String),
+    /// A rate limit was exceeded; sent with `Retry-After`.
+    #[error("Too many requests, retry in {}s", retry_after_secs(*.retry_after))]
+    RateLimited { retry_after: Duration },
+    /// Authentication failures keep their own, more specific codes.
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error("Database error: {0}")]
+    Database(DbErr),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    /// Stable machine-readable error code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "not_found",
+            Self::Forbidden(_) => "forbidden",
+            Self::Conflict(_) => "conflict",
+            Self::Validation(_) => "validation_failed",
+            Self::Unavailable(_) => "service_unavailable",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Auth(e) => e.code(),
+            Self::Database(_) | Self::Internal(_) => "internal_error",
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::Forbidden(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::Conflict(message.into())
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::Validation(message.into())
+    }
+}
+
+impl From<DbErr> for ApiError {
+    fn from(e: DbErr) -> Self {
+        match e {
+            DbErr::RecordNotFound(message) => Self::NotFound(message),
+            e if is_unique_violation(&e) => Self::Conflict("Resource already exists".to_string()),
+            e => Self::Database(e),
+        }
+    }
+}
+
+impl From<Denied> for ApiError {
+    fn from(denied: Denied) -> Self {
+        Self::Forbidden(denied.reason.to_string())
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Auth(e) => e.status_code(),
+            Self::Database(_) | Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            Self::Auth(e) => e.error_response(),
+            Self::Database(_) | Self::Internal(_) => {
+                tracing::error!("{self}");
+                error_envelope(self.status_code(), "Internal server error", self.code())
+            }
+            Self::RateLimited { retry_after } => {
+                let mut res = error_envelope(self.status_code(), &self.to_string(), self.code());
+                res.headers_mut().insert(
+                    header::RETRY_AFTER,
+                    HeaderValue::from(retry_after_secs(*retry_after)),
+                );
+                res
+            }
+            _ => error_envelope(self.status_code(), &self.to_string(), self.code()),
+        }
+    }
+}
+
+/// The JSON envelope of every error response.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+    /// Stable machine-readable code, e.g. `not_found` or `rate_limited`.
+    pub code: String,
+    /// Matches the `X-Request-Id` response header.
+    pub request_id: Option<String>,
+}
+
+/// Build the JSON error envelope shared by [`ApiError`] and [`AuthError`].
+pub fn error_envelope(status: StatusCode, message: &str, code: &str) -> HttpResponse {
+    HttpResponse::build(status).json(ErrorBody {
+        error: message.to_string(),
+        code: code.to_string(),
+        request_id: request_id::current(),
+    })
+}
+
+/// Body, query and path extractor configs that report malformed input as
+/// `validation_failed` instead of actix's plain-text errors.
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|e, _req: &HttpRequest| {
+        error::Error::from(ApiError::Validation(e.to_string()))
+    })
+}
+
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(|e, _req: &HttpRequest| {
+        error::Error::from(ApiError::Validation(e.to_string()))
+    })
+}
+
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default().error_handler(|e, _req: &HttpRequest| {
+        error::Error::from(ApiError::Validation(e.to_string()))
+    })
+}
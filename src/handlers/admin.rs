@@ -0,0 +1,895 @@
+use actix_web::{HttpResponse, Responder, web};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::audit::{Audit, AuditEvent, Target};
+use crate::auth::revocation;
+use crate::auth::roles::{Admin, RequireRole};
+use crate::auth::user_cache::{UserCache, UserCacheData};
+use crate::cache::{CacheStore, keys, tags};
+use crate::chat::server::ChatServer;
+use crate::db::api_keys as api_key_db;
+use crate::db::audit_events as audit_db;
+use crate::db::contracts as contract_db;
+use crate::db::gigs as gig_db;
+use crate::db::portfolio as portfolio_db;
+use crate::db::users as user_db;
+use crate::error::{ApiError, ErrorBody};
+use crate::handlers::api_keys::mint_api_key;
+use crate::models::api_keys::{ApiKeyResponse, CreateApiKey, CreatedApiKey};
+use crate::models::audit_events::AuditQuery;
+use crate::models::contracts::UpdateContractStatus;
+use crate::models::gigs::{GigListQuery, UpdateGig};
+use crate::models::portfolio::UpdatePortfolio;
+use crate::models::users::{
+    AccountStatus, BanUser, RevokedSessions, SuspendUser, UpdateUser, UpdateUserRole, UserResponse,
+};
+use crate::models::{Confirmation, PaginationQuery};
+
+// ── Users ──
+
+/// GET /api/admin/users — list all users with pagination.
+#[utoipa::path(
+    get,
+    path = "/api/admin/users",
+    tag = "admin",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "A page of users", body = Vec<UserResponse>),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_users(
+    _admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<PaginationQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let users = user_db::get_users_paginated(db.get_ref(), query.page(), query.limit()).await?;
+    let response: Vec<UserResponse> = users.into_iter().map(UserResponse::from).collect();
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// PUT /api/admin/users/{id} — update any user's profile fields.
+#[utoipa::path(
+    put,
+    path = "/api/admin/users/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    request_body = UpdateUser,
+    responses(
+        (status = 200, description = "The updated user", body = UserResponse),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn update_user(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    user_cache: web::Data<UserCacheData>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateUser>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+    let input = body.into_inner();
+
+    // Role changes go through the dedicated endpoint so its safeguards and
+    // `user.role_change` audit event apply.
+    if input.role.is_some() {
+        return Err(ApiError::validation(
+            "Change roles with PUT /api/admin/users/{id}/role",
+        ));
+    }
+
+    let before = load_user(db.get_ref(), id).await?;
+    let updated = user_db::update_user(db.get_ref(), id, input).await?;
+    let _ = cache.delete(&keys::user(&id.to_string())).await;
+    user_cache.invalidate(id).await;
+
+    let response = UserResponse::from(updated);
+    let event = AuditEvent::new(admin.0.id, "user.update", Target::User(id))
+        .before(&UserResponse::from(before))
+        .after(&response);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// PUT /api/admin/users/{id}/role — change a user's role.
+#[utoipa::path(
+    put,
+    path = "/api/admin/users/{id}/role",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    request_body = UpdateUserRole,
+    responses(
+        (status = 200, description = "The updated user", body = UserResponse),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn set_user_role(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    user_cache: web::Data<UserCacheData>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateUserRole>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    // Prevent admins from locking themselves out.
+    if admin.0.id == id {
+        return Err(ApiError::validation("You cannot change your own role"));
+    }
+
+    let before = load_user(db.get_ref(), id).await?;
+    let updated = user_db::set_user_role(db.get_ref(), id, body.into_inner().role).await?;
+    let _ = cache.delete(&keys::user(&id.to_string())).await;
+    user_cache.invalidate(id).await;
+
+    let response = UserResponse::from(updated);
+    let event = AuditEvent::new(admin.0.id, "user.role_change", Target::User(id))
+        .before(&UserResponse::from(before))
+        .after(&response);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// DELETE /api/admin/users/{id} — delete any user.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "User deleted", body = Confirmation),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn delete_user(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    user_cache: web::Data<UserCacheData>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    if admin.0.id == id {
+        return Err(ApiError::validation(
+            "You cannot delete your own account from the admin API",
+        ));
+    }
+
+    let user = load_user(db.get_ref(), id).await?;
+    let result = user_db::delete_user(db.get_ref(), id).await?;
+    if result.rows_affected == 0 {
+        return Err(ApiError::not_found(format!("User {id} not found")));
+    }
+
+    let _ = cache.delete(&keys::user(&id.to_string())).await;
+    let _ = cache.delete(&keys::portfolio(&id.to_string())).await;
+    let _ = cache.invalidate_tags(&tags::for_user_gigs(id)).await;
+    user_cache.invalidate(id).await;
+
+    let event = AuditEvent::new(admin.0.id, "user.delete", Target::User(id))
+        .before(&UserResponse::from(user));
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(Confirmation::new(format!("User {id} deleted"))))
+}
+
+/// POST /api/admin/users/{id}/suspend — lock a user out until `until` (or indefinitely).
+#[allow(clippy::too_many_arguments)]
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/suspend",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    request_body = SuspendUser,
+    responses(
+        (status = 200, description = "The suspended user", body = UserResponse),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn suspend_user(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    user_cache: web::Data<UserCacheData>,
+    chat_server: web::Data<Arc<ChatServer>>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+    body: web::Json<SuspendUser>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+    let input = body.into_inner();
+
+    if admin.0.id == id {
+        return Err(ApiError::validation("You cannot suspend yourself"));
+    }
+    if input.until.is_some_and(|until| until <= chrono::Utc::now()) {
+        return Err(ApiError::validation("Suspension end must be in the future"));
+    }
+
+    let before = load_user(db.get_ref(), id).await?;
+    let updated = user_db::set_user_status(
+        db.get_ref(),
+        id,
+        AccountStatus::Suspended,
+        input.until,
+        input.reason,
+    )
+    .await?;
+    let response = apply_status_change(
+        updated,
+        &cache,
+        &user_cache,
+        &chat_server,
+        "Account suspended",
+    )
+    .await;
+
+    let event = AuditEvent::new(admin.0.id, "user.suspend", Target::User(id))
+        .before(&UserResponse::from(before))
+        .after(&response);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /api/admin/users/{id}/ban — permanently lock a user out.
+#[allow(clippy::too_many_arguments)]
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/ban",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    request_body = BanUser,
+    responses(
+        (status = 200, description = "The banned user", body = UserResponse),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn ban_user(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    user_cache: web::Data<UserCacheData>,
+    chat_server: web::Data<Arc<ChatServer>>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+    body: web::Json<BanUser>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    if admin.0.id == id {
+        return Err(ApiError::validation("You cannot ban yourself"));
+    }
+
+    let before = load_user(db.get_ref(), id).await?;
+    let updated = user_db::set_user_status(
+        db.get_ref(),
+        id,
+        AccountStatus::Banned,
+        None,
+        body.into_inner().reason,
+    )
+    .await?;
+    let response =
+        apply_status_change(updated, &cache, &user_cache, &chat_server, "Account banned").await;
+
+    let event = AuditEvent::new(admin.0.id, "user.ban", Target::User(id))
+        .before(&UserResponse::from(before))
+        .after(&response);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /api/admin/users/{id}/unsuspend — restore a suspended or banned account.
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/unsuspend",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "The restored user", body = UserResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn unsuspend_user(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    user_cache: web::Data<UserCacheData>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let before = load_user(db.get_ref(), id).await?;
+    let updated =
+        user_db::set_user_status(db.get_ref(), id, AccountStatus::Active, None, None).await?;
+    let _ = cache.delete(&keys::user(&id.to_string())).await;
+    user_cache.invalidate(id).await;
+
+    let response = UserResponse::from(updated);
+    let event = AuditEvent::new(admin.0.id, "user.unsuspend", Target::User(id))
+        .before(&UserResponse::from(before))
+        .after(&response);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /api/admin/users/{id}/revoke-sessions — invalidate every token issued to
+/// the user so far and close their open chat sockets.
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/revoke-sessions",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "Tokens revoked and chat sockets closed", body = RevokedSessions),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn revoke_user_sessions(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    chat_server: web::Data<Arc<ChatServer>>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    if user_db::get_user_by_id(db.get_ref(), id).await?.is_none() {
+        return Err(ApiError::not_found(format!("User {id} not found")));
+    }
+
+    revocation::revoke_all(cache.get_ref(), id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to revoke sessions: {e}")))?;
+    let closed = chat_server
+        .disconnect_user(id, "Your sessions were revoked by an administrator")
+        .await;
+
+    let event = AuditEvent::new(admin.0.id, "user.sessions_revoke", Target::User(id));
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(RevokedSessions {
+        closed_connections: closed,
+    }))
+}
+
+/// Fetch a user, answering `404` if they do not exist.
+async fn load_user(
+    db: &DatabaseConnection,
+    id: Uuid,
+) -> Result<crate::models::users::Model, ApiError> {
+    user_db::get_user_by_id(db, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("User {id} not found")))
+}
+
+/// Drop cached copies of a suspended/banned user and close their live chat sessions.
+async fn apply_status_change(
+    updated: crate::models::users::Model,
+    cache: &CacheStore,
+    user_cache: &UserCache,
+    chat_server: &ChatServer,
+    disconnect_reason: &str,
+) -> UserResponse {
+    let id = updated.id;
+    let _ = cache.delete(&keys::user(&id.to_string())).await;
+    user_cache.invalidate(id).await;
+    chat_server.disconnect_user(id, disconnect_reason).await;
+    UserResponse::from(updated)
+}
+
+/// GET /api/admin/user-cache — hit/miss counters of this instance's user cache.
+#[utoipa::path(
+    get,
+    path = "/api/admin/user-cache",
+    tag = "admin",
+    responses(
+        (status = 200, description = "User cache counters", body = crate::auth::user_cache::UserCacheStats),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn user_cache_stats(
+    _admin: RequireRole<Admin>,
+    user_cache: web::Data<UserCacheData>,
+) -> impl Responder {
+    HttpResponse::Ok().json(user_cache.stats())
+}
+
+/// GET /api/admin/cache — response cache hit counters and ratios per layer.
+#[utoipa::path(
+    get,
+    path = "/api/admin/cache",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Response cache counters", body = crate::cache::CacheStats),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn cache_stats(
+    _admin: RequireRole<Admin>,
+    cache: web::Data<Arc<CacheStore>>,
+) -> impl Responder {
+    HttpResponse::Ok().json(cache.stats())
+}
+
+// ── API keys ──
+
+/// GET /api/admin/users/{id}/api-keys — list a user's API keys.
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{id}/api-keys",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "The user's API keys", body = Vec<ApiKeyResponse>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_user_api_keys(
+    _admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let keys = api_key_db::get_api_keys_by_user(db.get_ref(), path.into_inner()).await?;
+    let response: Vec<ApiKeyResponse> = keys.into_iter().map(ApiKeyResponse::from).collect();
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /api/admin/users/{id}/api-keys — mint a key on behalf of a user
+/// (e.g. a service account used by an integration).
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/api-keys",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    request_body = CreateApiKey,
+    responses(
+        (status = 201, description = "The new key, including its only plaintext copy", body = CreatedApiKey),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn create_user_api_key(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+    body: web::Json<CreateApiKey>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    if user_db::get_user_by_id(db.get_ref(), id).await?.is_none() {
+        return Err(ApiError::not_found(format!("User {id} not found")));
+    }
+    let created = mint_api_key(db.get_ref(), id, body.into_inner()).await?;
+
+    let event = AuditEvent::new(
+        admin.0.id,
+        "api_key.create",
+        Target::ApiKey(created.api_key.id),
+    )
+    .after(&created.api_key);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+/// DELETE /api/admin/api-keys/{id} — revoke any API key.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/api-keys/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "API key id")),
+    responses(
+        (status = 200, description = "The revoked key", body = ApiKeyResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such key", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn revoke_api_key(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let revoked = ApiKeyResponse::from(api_key_db::revoke_api_key(db.get_ref(), id).await?);
+    let event = AuditEvent::new(admin.0.id, "api_key.revoke", Target::ApiKey(id)).after(&revoked);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(revoked))
+}
+
+// ── Gigs ──
+
+/// GET /api/admin/gigs — list all gigs (cursor pagination, uncached).
+#[utoipa::path(
+    get,
+    path = "/api/admin/gigs",
+    tag = "admin",
+    params(GigListQuery),
+    responses(
+        (status = 200, description = "A page of gigs, newest first", body = Vec<crate::models::gigs::Model>),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_gigs(
+    _admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<GigListQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let gigs = gig_db::get_gigs_paginated(
+        db.get_ref(),
+        query.limit(),
+        query.cursor_created_at,
+        query.cursor_id,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(gigs))
+}
+
+/// PUT /api/admin/gigs/{id} — update any gig.
+#[utoipa::path(
+    put,
+    path = "/api/admin/gigs/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Gig id")),
+    request_body = UpdateGig,
+    responses(
+        (status = 200, description = "The updated gig", body = crate::models::gigs::Model),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such gig", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn update_gig(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateGig>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let before = gig_db::get_gig_by_id(db.get_ref(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Gig {id} not found")))?;
+
+    let updated = gig_db::update_gig(db.get_ref(), id, body.into_inner()).await?;
+    let _ = cache.delete(&keys::gig(&id.to_string())).await;
+    let _ = cache
+        .invalidate_tags(&tags::for_gig_update(&before, &updated))
+        .await;
+
+    let event = AuditEvent::new(admin.0.id, "gig.update", Target::Gig(id))
+        .before(&before)
+        .after(&updated);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// DELETE /api/admin/gigs/{id} — delete any gig.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/gigs/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Gig id")),
+    responses(
+        (status = 200, description = "Gig deleted", body = Confirmation),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such gig", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn delete_gig(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let gig = gig_db::get_gig_by_id(db.get_ref(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Gig {id} not found")))?;
+
+    gig_db::delete_gig(db.get_ref(), id).await?;
+    let _ = cache.delete(&keys::gig(&id.to_string())).await;
+    let _ = cache.invalidate_tags(&tags::for_gig(&gig)).await;
+
+    let event = AuditEvent::new(admin.0.id, "gig.delete", Target::Gig(id)).before(&gig);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(Confirmation::new(format!("Gig {id} deleted"))))
+}
+
+// ── Portfolios ──
+
+/// GET /api/admin/portfolios — list all portfolio items with pagination.
+#[utoipa::path(
+    get,
+    path = "/api/admin/portfolios",
+    tag = "admin",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "A page of portfolio items", body = Vec<crate::models::portfolio::Model>),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_portfolios(
+    _admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<PaginationQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let items =
+        portfolio_db::get_portfolios_paginated(db.get_ref(), query.page(), query.limit()).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
+
+/// PUT /api/admin/portfolios/{id} — update any portfolio item.
+#[utoipa::path(
+    put,
+    path = "/api/admin/portfolios/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Portfolio item id")),
+    request_body = UpdatePortfolio,
+    responses(
+        (status = 200, description = "The updated portfolio item", body = crate::models::portfolio::Model),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such portfolio item", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn update_portfolio(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdatePortfolio>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let before = portfolio_db::get_portfolio_by_id(db.get_ref(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Portfolio item {id} not found")))?;
+    let updated = portfolio_db::update_portfolio(db.get_ref(), id, body.into_inner()).await?;
+    let _ = cache
+        .delete(&keys::portfolio(&updated.freelancer_id.to_string()))
+        .await;
+
+    let event = AuditEvent::new(admin.0.id, "portfolio.update", Target::Portfolio(id))
+        .before(&before)
+        .after(&updated);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// DELETE /api/admin/portfolios/{id} — delete any portfolio item.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/portfolios/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Portfolio item id")),
+    responses(
+        (status = 200, description = "Portfolio item deleted", body = Confirmation),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such portfolio item", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn delete_portfolio(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let item = portfolio_db::get_portfolio_by_id(db.get_ref(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Portfolio item {id} not found")))?;
+
+    portfolio_db::delete_portfolio(db.get_ref(), id).await?;
+    let _ = cache
+        .delete(&keys::portfolio(&item.freelancer_id.to_string()))
+        .await;
+
+    let event =
+        AuditEvent::new(admin.0.id, "portfolio.delete", Target::Portfolio(id)).before(&item);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(Confirmation::new(format!("Portfolio item {id} deleted"))))
+}
+
+// ── Contracts ──
+
+/// GET /api/admin/contracts — list all contracts with pagination.
+#[utoipa::path(
+    get,
+    path = "/api/admin/contracts",
+    tag = "admin",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "A page of contracts", body = Vec<crate::models::contracts::Model>),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_contracts(
+    _admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<PaginationQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let contracts =
+        contract_db::get_contracts_paginated(db.get_ref(), query.page(), query.limit()).await?;
+    Ok(HttpResponse::Ok().json(contracts))
+}
+
+/// PUT /api/admin/contracts/{id}/status — set any contract's status.
+///
+/// Unlike the freelancer endpoint this is not limited to pending contracts.
+#[utoipa::path(
+    put,
+    path = "/api/admin/contracts/{id}/status",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Contract id")),
+    request_body = UpdateContractStatus,
+    responses(
+        (status = 200, description = "The updated contract", body = crate::models::contracts::Model),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such contract", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn update_contract_status(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateContractStatus>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let before = contract_db::get_contract_by_id(db.get_ref(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Contract {id} not found")))?;
+    let updated = contract_db::update_contract_status(db.get_ref(), id, body.into_inner()).await?;
+
+    let event = AuditEvent::new(admin.0.id, "contract.status_change", Target::Contract(id))
+        .before(&before)
+        .after(&updated);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// DELETE /api/admin/contracts/{id} — delete any contract regardless of status.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/contracts/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "Contract id")),
+    responses(
+        (status = 200, description = "Contract deleted", body = Confirmation),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+        (status = 404, description = "No such contract", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn delete_contract(
+    admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let contract = contract_db::get_contract_by_id(db.get_ref(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Contract {id} not found")))?;
+    contract_db::delete_contract(db.get_ref(), id).await?;
+
+    let event =
+        AuditEvent::new(admin.0.id, "contract.delete", Target::Contract(id)).before(&contract);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(Confirmation::new(format!("Contract {id} deleted"))))
+}
+
+// ── Audit log ──
+
+/// GET /api/admin/audit-events — query the audit log, newest first.
+///
+/// Filter by `actor_id`, `target_type` + `target_id`, `action` and a
+/// `from`/`to` time range; page with `cursor_created_at` + `cursor_id` taken
+/// from the last event of the previous page.
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit-events",
+    tag = "admin",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Matching audit events, newest first", body = Vec<crate::models::audit_events::Model>),
+        (status = 400, description = "Invalid filters or an empty time range", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Caller is not an admin", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_audit_events(
+    _admin: RequireRole<Admin>,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<AuditQuery>,
+) -> Result<HttpResponse, ApiError> {
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from >= to
+    {
+        return Err(ApiError::validation("`from` must be before `to`"));
+    }
+
+    let events = audit_db::query_audit_events(db.get_ref(), &query).await?;
+    Ok(HttpResponse::Ok().json(events))
+}
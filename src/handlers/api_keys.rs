@@ -0,0 +1,145 @@
+use actix_web::{HttpResponse, web};
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+use crate::audit::{Audit, AuditEvent, Target};
+use crate::auth::api_keys;
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::policy::{Action, Actor, Policy, Resource};
+use crate::db::api_keys as api_key_db;
+use crate::error::{ApiError, ErrorBody};
+use crate::models::api_keys::{ApiKeyResponse, CreateApiKey, CreatedApiKey};
+
+/// POST /api/auth/api-keys — mint a new API key for the current user.
+///
+/// Requires a session JWT: API keys cannot mint further keys. The plaintext key
+/// is only returned in this response.
+#[utoipa::path(
+    post,
+    path = "/api/auth/api-keys",
+    tag = "api-keys",
+    request_body = CreateApiKey,
+    responses(
+        (status = 201, description = "The new key, including its only plaintext copy", body = CreatedApiKey),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn create_api_key(
+    user: AuthenticatedUser,
+    db: web::Data<DatabaseConnection>,
+    audit: Audit,
+    body: web::Json<CreateApiKey>,
+) -> Result<HttpResponse, ApiError> {
+    let created = mint_api_key(db.get_ref(), user.0.id, body.into_inner()).await?;
+    let event = AuditEvent::new(
+        user.0.id,
+        "api_key.create",
+        Target::ApiKey(created.api_key.id),
+    )
+    .after(&created.api_key);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+/// GET /api/auth/api-keys — list the current user's API keys (without secrets).
+#[utoipa::path(
+    get,
+    path = "/api/auth/api-keys",
+    tag = "api-keys",
+    responses(
+        (status = 200, description = "The caller's API keys", body = Vec<ApiKeyResponse>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_api_keys(
+    user: AuthenticatedUser,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, ApiError> {
+    let keys = api_key_db::get_api_keys_by_user(db.get_ref(), user.0.id).await?;
+    let response: Vec<ApiKeyResponse> = keys.into_iter().map(ApiKeyResponse::from).collect();
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// DELETE /api/auth/api-keys/{id} — revoke one of the current user's API keys.
+#[utoipa::path(
+    delete,
+    path = "/api/auth/api-keys/{id}",
+    tag = "api-keys",
+    params(("id" = Uuid, Path, description = "API key id")),
+    responses(
+        (status = 200, description = "The revoked key", body = ApiKeyResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 404, description = "No such key", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn revoke_api_key(
+    user: AuthenticatedUser,
+    db: web::Data<DatabaseConnection>,
+    audit: Audit,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let owned = api_key_db::get_api_key_by_id(db.get_ref(), id)
+        .await?
+        .is_some_and(|key| {
+            let resource = Resource::ApiKey {
+                owner_id: key.user_id,
+            };
+            Policy::can(&Actor::from(&user.0), Action::Delete, &resource).is_ok()
+        });
+    // Don't reveal whether another user's key exists.
+    if !owned {
+        return Err(ApiError::not_found(format!("API key {id} not found")));
+    }
+
+    let revoked = ApiKeyResponse::from(api_key_db::revoke_api_key(db.get_ref(), id).await?);
+    let event = AuditEvent::new(user.0.id, "api_key.revoke", Target::ApiKey(id)).after(&revoked);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(revoked))
+}
+
+/// Validate the request, generate a key for `user_id` and store its hash.
+pub async fn mint_api_key(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    input: CreateApiKey,
+) -> Result<CreatedApiKey, ApiError> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiError::validation("API key name cannot be empty"));
+    }
+    if input.scopes.is_empty() {
+        return Err(ApiError::validation("An API key needs at least one scope"));
+    }
+    if input
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+    {
+        return Err(ApiError::validation("Expiry must be in the future"));
+    }
+
+    let mut scopes = input.scopes;
+    scopes.sort_by_key(|s| s.as_str());
+    scopes.dedup();
+
+    let generated = api_keys::generate();
+    let created = api_key_db::insert_api_key(
+        db,
+        user_id,
+        name,
+        generated.prefix,
+        generated.hash,
+        &scopes,
+        input.expires_at,
+    )
+    .await?;
+    Ok(CreatedApiKey {
+        key: generated.key,
+        api_key: ApiKeyResponse::from(created),
+    })
+}
@@ -1,25 +1,141 @@
-use actix_web::{HttpResponse, Responder, web};
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, Responder, web};
 use sea_orm::DatabaseConnection;
+use std::sync::Arc;
 
+use crate::audit::{Audit, AuditEvent, Target};
+use crate::auth::jwt::Claims;
 use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::policy::{Action, Actor, Policy, Resource};
+use crate::auth::revocation;
+use crate::auth::user_cache::UserCacheData;
+use crate::cache::{CacheStore, keys};
+use crate::chat::server::ChatServer;
 use crate::db::users;
+use crate::error::{ApiError, ErrorBody};
 use crate::models::users::{CompleteProfile, UserResponse};
 
 /// GET /api/auth/me — return the currently authenticated user's profile.
+#[utoipa::path(
+    get,
+    path = "/api/auth/me",
+    tag = "auth",
+    responses(
+        (status = 200, description = "The caller's profile", body = UserResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn me(user: AuthenticatedUser) -> impl Responder {
     HttpResponse::Ok().json(UserResponse::from(user.0))
 }
 
 /// POST /api/auth/complete-profile — set username, role, display_name after first login.
+#[utoipa::path(
+    post,
+    path = "/api/auth/complete-profile",
+    tag = "auth",
+    request_body = CompleteProfile,
+    responses(
+        (status = 200, description = "The completed profile", body = UserResponse),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 409, description = "Username already taken", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn complete_profile(
     user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    user_cache: web::Data<UserCacheData>,
+    audit: Audit,
     body: web::Json<CompleteProfile>,
-) -> impl Responder {
-    match users::complete_profile(db.get_ref(), user.0.id, body.into_inner()).await {
-        Ok(updated) => HttpResponse::Ok().json(UserResponse::from(updated)),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to update profile: {e}"),
-        })),
+) -> Result<HttpResponse, ApiError> {
+    // Only admins may grant the admin role.
+    if let Some(role) = &body.role {
+        let resource = Resource::Role(role.clone());
+        Policy::can(&Actor::from(&user.0), Action::Update, &resource)?;
     }
+
+    let updated = users::complete_profile(db.get_ref(), user.0.id, body.into_inner()).await?;
+    let _ = cache.delete(&keys::user(&updated.id.to_string())).await;
+    user_cache.invalidate(updated.id).await;
+
+    let response = UserResponse::from(updated);
+    let event = AuditEvent::new(user.0.id, "auth.complete_profile", Target::User(user.0.id))
+        .before(&UserResponse::from(user.0))
+        .after(&response);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /api/auth/logout — revoke the token used for this request.
+///
+/// Supabase tokens are revoked by their `session_id`, so every access token of
+/// that session stops working here; clients should still sign out of Supabase.
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout",
+    tag = "auth",
+    responses(
+        (status = 204, description = "Token revoked"),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn logout(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
+) -> Result<HttpResponse, ApiError> {
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or_else(|| ApiError::Internal("Token claims not available".to_string()))?;
+
+    let revoked = revocation::revoke_token(cache.get_ref(), &claims)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to revoke token: {e}")))?;
+    if !revoked {
+        return Err(ApiError::validation(
+            "Token has no jti or session_id; use /api/auth/logout-all",
+        ));
+    }
+
+    let event = AuditEvent::new(user.0.id, "auth.logout", Target::User(user.0.id));
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// POST /api/auth/logout-all — revoke every token issued to the caller so far
+/// and close their open chat sockets.
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout-all",
+    tag = "auth",
+    responses(
+        (status = 204, description = "All tokens revoked"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn logout_all(
+    user: AuthenticatedUser,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    chat_server: web::Data<Arc<ChatServer>>,
+    audit: Audit,
+) -> Result<HttpResponse, ApiError> {
+    revocation::revoke_all(cache.get_ref(), user.0.id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to revoke sessions: {e}")))?;
+    chat_server.disconnect_user(user.0.id, "Signed out").await;
+
+    let event = AuditEvent::new(user.0.id, "auth.logout_all", Target::User(user.0.id));
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::NoContent().finish())
 }
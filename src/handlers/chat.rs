@@ -1,36 +1,100 @@
-use actix_web::{HttpResponse, Responder, web};
+use actix_web::{HttpResponse, web};
 use sea_orm::DatabaseConnection;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use uuid::Uuid;
-use tracing;
 
-use crate::auth::authorization::verify_contract_party;
+use crate::auth::authorization::{load_contract, verify_contract_party};
 use crate::auth::middleware::AuthenticatedUser;
-use crate::cache::{RedisCache, keys};
+use crate::auth::policy::{Action, Actor, Policy, Resource};
+use crate::cache::{CacheConfig, CacheStore, keys};
+use crate::chat::ticket::{self, CreateWsTicket, IssuedWsTicket, WsAuthConfig, WsTicket};
 use crate::db::contracts as contract_db;
 use crate::db::gigs as gig_db;
 use crate::db::messages as message_db;
+use crate::error::{ApiError, ErrorBody};
 use crate::models::contracts::Status;
 use crate::models::messages::{ConversationSummary, MessageQuery, MessageResponse};
 
+/// POST /api/chat/ws-ticket
+///
+/// Exchange the bearer token for a short-lived, single-use ticket to pass as
+/// `?ticket=` when opening the chat WebSocket, so the JWT stays out of URLs.
+/// With a `contract_id` the ticket only opens that contract's chat.
+#[utoipa::path(
+    post,
+    path = "/api/chat/ws-ticket",
+    tag = "chat",
+    request_body = Option<CreateWsTicket>,
+    responses(
+        (status = 201, description = "A single-use WebSocket ticket", body = IssuedWsTicket),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such contract", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn create_ws_ticket(
+    user: AuthenticatedUser,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    config: Option<web::Data<WsAuthConfig>>,
+    body: Option<web::Json<CreateWsTicket>>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user.0.id;
+    let actor = Actor::from(&user.0);
+    let contract_id = body.and_then(|b| b.into_inner().contract_id);
+    let ttl = config
+        .map(|c| c.ticket_ttl_secs)
+        .unwrap_or_else(|| WsAuthConfig::default().ticket_ttl_secs);
+
+    if let Some(contract_id) = contract_id {
+        verify_contract_party(db.get_ref(), contract_id, &actor).await?;
+    }
+
+    let grant = WsTicket {
+        user_id,
+        contract_id,
+    };
+    let ticket = ticket::issue(cache.get_ref(), &grant, ttl)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to issue ticket: {e}")))?;
+    Ok(HttpResponse::Created().json(IssuedWsTicket {
+        ticket,
+        expires_in: ttl,
+    }))
+}
+
 /// GET /api/chat/{contract_id}/messages?page=1&limit=50
 ///
 /// Fetch paginated message history for a contract.
 /// Only the two parties of the contract can access this.
+#[utoipa::path(
+    get,
+    path = "/api/chat/{contract_id}/messages",
+    tag = "chat",
+    params(("contract_id" = Uuid, Path, description = "Contract id"), MessageQuery),
+    responses(
+        (status = 200, description = "A page of messages", body = Vec<MessageResponse>),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such contract", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn get_messages(
     user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    ttls: web::Data<CacheConfig>,
     path: web::Path<Uuid>,
     query: web::Query<MessageQuery>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let contract_id = path.into_inner();
-    let user_id = user.0.id;
+    let actor = Actor::from(&user.0);
 
-    if let Err(resp) = verify_contract_party(db.get_ref(), contract_id, user_id).await {
-        return resp;
-    }
+    verify_contract_party(db.get_ref(), contract_id, &actor).await?;
 
     let limit = query.limit.unwrap_or(50).min(100);
     let cursor_created_at = query.cursor_created_at;
@@ -41,130 +105,111 @@ pub async fn get_messages(
     };
     let cache_key = format!("messages:{contract_id}:{limit}:{cursor_part}");
 
-    match cache.get::<Vec<MessageResponse>>(&cache_key).await {
-        Ok(Some(cached)) => return HttpResponse::Ok().json(cached),
-        Ok(None) => {}
-        Err(e) => tracing::warn!("Cache error: {}", e),
-    }
-
-    match message_db::get_messages_by_contract(
-        db.get_ref(),
-        contract_id,
-        limit,
-        cursor_created_at,
-        cursor_id,
-    )
-    .await
-    {
-        Ok(messages) => {
-            let response: Vec<MessageResponse> = messages.into_iter().map(|m| m.into()).collect();
-            let _ = cache.set(&cache_key, &response, Some(60)).await;
-            HttpResponse::Ok().json(response)
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Database error: {e}"),
-        })),
-    }
+    let response: Vec<MessageResponse> = cache
+        .cached(&cache_key, ttls.message_ttl, || async {
+            let messages = message_db::get_messages_by_contract(
+                db.get_ref(),
+                contract_id,
+                limit,
+                cursor_created_at,
+                cursor_id,
+            )
+            .await?;
+            Ok::<_, ApiError>(messages.into_iter().map(|m| m.into()).collect())
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(response))
 }
 
 /// PUT /api/chat/messages/{id}/read
 ///
 /// Mark a specific message as read. Only the recipient (non-sender) should call this.
+#[utoipa::path(
+    put,
+    path = "/api/chat/messages/{id}/read",
+    tag = "chat",
+    params(("id" = Uuid, Path, description = "Message id")),
+    responses(
+        (status = 200, description = "The message, marked read", body = MessageResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such message", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn mark_message_read(
     user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let message_id = path.into_inner();
     let user_id = user.0.id;
 
-    let message = match message_db::get_message_by_id(db.get_ref(), message_id).await {
-        Ok(Some(msg)) => msg,
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Message {message_id} not found"),
-            }));
-        }
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let message = message_db::get_message_by_id(db.get_ref(), message_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Message {message_id} not found")))?;
 
-    if let Err(resp) = verify_contract_party(db.get_ref(), message.contract_id, user_id).await {
-        return resp;
-    }
-
-    if message.sender_id == user_id {
-        return HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You cannot mark your own message as read",
-        }));
-    }
+    let (_, contract) = load_contract(db.get_ref(), message.contract_id).await?;
+    let resource = Resource::Message {
+        sender_id: message.sender_id,
+        contract,
+    };
+    Policy::can(&Actor::from(&user.0), Action::Update, &resource)?;
 
-    match message_db::mark_message_as_read(db.get_ref(), message_id).await {
-        Ok(msg) => {
-            let _ = cache
-                .delete(&keys::conversations(&user_id.to_string()))
-                .await;
-            let response: MessageResponse = msg.into();
-            HttpResponse::Ok().json(response)
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to mark message as read: {e}"),
-        })),
-    }
+    let msg = message_db::mark_message_as_read(db.get_ref(), message_id).await?;
+    let _ = cache
+        .delete(&keys::conversations(&user_id.to_string()))
+        .await;
+    let response: MessageResponse = msg.into();
+    Ok(HttpResponse::Ok().json(response))
 }
 
 /// GET /api/chat/conversations
 ///
 /// List all contracts with chat activity for the authenticated user.
 /// Returns a summary with the last message, unread count, and the other party's info.
+#[utoipa::path(
+    get,
+    path = "/api/chat/conversations",
+    tag = "chat",
+    responses(
+        (status = 200, description = "The caller's conversations", body = Vec<ConversationSummary>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn get_conversations(
     user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
-) -> impl Responder {
+    cache: web::Data<Arc<CacheStore>>,
+    ttls: web::Data<CacheConfig>,
+) -> Result<HttpResponse, ApiError> {
     let user_id = user.0.id;
     let cache_key = keys::conversations(&user_id.to_string());
 
-    match cache.get::<Vec<ConversationSummary>>(&cache_key).await {
-        Ok(Some(cached)) => return HttpResponse::Ok().json(cached),
-        Ok(None) => {}
-        Err(e) => tracing::warn!("Cache error: {}", e),
-    }
+    let summaries = cache
+        .cached(&cache_key, ttls.conversation_ttl, || {
+            load_conversations(db.get_ref(), user_id)
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(summaries))
+}
 
+/// Build the conversation summaries for `user_id` from the database.
+async fn load_conversations(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<ConversationSummary>, ApiError> {
     // Get all contracts where the user is the client.
-    let as_client = match contract_db::get_contracts_by_user_id(db.get_ref(), user_id).await {
-        Ok(c) => c,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let as_client = contract_db::get_contracts_by_user_id(db, user_id).await?;
 
     // Get all contracts where the user is the freelancer (gig owner).
-    let user_gigs = match gig_db::get_gigs_by_user_id(db.get_ref(), user_id).await {
-        Ok(g) => g,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let user_gigs = gig_db::get_gigs_by_user_id(db, user_id).await?;
 
     // Batch fetch all contracts for user's gigs in a single query (N+1 fix)
     let gig_ids: Vec<Uuid> = user_gigs.iter().map(|g| g.id).collect();
-    let freelancer_contracts = match contract_db::get_contracts_by_gig_ids(db.get_ref(), gig_ids).await {
-        Ok(c) => c,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let freelancer_contracts = contract_db::get_contracts_by_gig_ids(db, gig_ids).await?;
 
     // Merge and deduplicate in O(n), keeping only Accepted contracts.
     let mut seen_contract_ids: HashSet<Uuid> = HashSet::new();
@@ -176,15 +221,7 @@ pub async fn get_conversations(
     }
 
     let unique_gig_ids: HashSet<Uuid> = accepted_contracts.iter().map(|c| c.gig_id).collect();
-    let gigs = match gig_db::get_gigs_by_ids(db.get_ref(), unique_gig_ids.into_iter().collect()).await
-    {
-        Ok(g) => g,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let gigs = gig_db::get_gigs_by_ids(db, unique_gig_ids.into_iter().collect()).await?;
     let gig_owner_by_id: HashMap<Uuid, Uuid> = gigs.into_iter().map(|g| (g.id, g.user_id)).collect();
 
     let mut other_user_ids: HashSet<Uuid> = HashSet::new();
@@ -198,43 +235,16 @@ pub async fn get_conversations(
         }
     }
 
-    let users = match crate::db::users::get_users_by_ids(
-        db.get_ref(),
-        other_user_ids.into_iter().collect(),
-    )
-    .await
-    {
-        Ok(u) => u,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let users =
+        crate::db::users::get_users_by_ids(db, other_user_ids.into_iter().collect()).await?;
     let user_name_by_id: HashMap<Uuid, Option<String>> =
         users.into_iter().map(|u| (u.id, u.display_name)).collect();
 
     let contract_ids: Vec<Uuid> = accepted_contracts.iter().map(|c| c.id).collect();
     let latest_by_contract =
-        match message_db::get_latest_messages_for_contracts(db.get_ref(), contract_ids.clone())
-            .await
-        {
-            Ok(m) => m,
-            Err(e) => {
-                return HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                }));
-            }
-        };
+        message_db::get_latest_messages_for_contracts(db, contract_ids.clone()).await?;
     let unread_by_contract =
-        match message_db::count_unread_for_contracts(db.get_ref(), contract_ids, user_id).await {
-            Ok(c) => c,
-            Err(e) => {
-                return HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                }));
-            }
-        };
+        message_db::count_unread_for_contracts(db, contract_ids, user_id).await?;
 
     // Build conversation summaries.
     let mut summaries: Vec<ConversationSummary> = Vec::new();
@@ -279,6 +289,5 @@ pub async fn get_conversations(
         b_time.cmp(&a_time)
     });
 
-    let _ = cache.set(&cache_key, &summaries, Some(300)).await;
-    HttpResponse::Ok().json(summaries)
+    Ok(summaries)
 }
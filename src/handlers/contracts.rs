@@ -1,53 +1,63 @@
-use actix_web::{HttpResponse, Responder, web};
+use actix_web::{HttpResponse, web};
 use sea_orm::DatabaseConnection;
 use std::collections::HashSet;
 use uuid::Uuid;
 
+use crate::audit::{Audit, AuditEvent, Target};
+use crate::auth::authorization::load_contract;
 use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::policy::{Action, Actor, ContractParties, Policy, Resource};
+use crate::auth::principal::Principal;
 use crate::db::contracts as contract_db;
 use crate::db::gigs as gig_db;
+use crate::db::is_unique_violation;
+use crate::error::{ApiError, ErrorBody};
+use crate::models::Confirmation;
+use crate::models::api_keys::ApiScope;
 use crate::models::contracts::{CreateContract, Status, UpdateContractStatus};
 
-fn is_unique_violation(err: &sea_orm::DbErr) -> bool {
-    let msg = err.to_string().to_lowercase();
-    msg.contains("duplicate key value violates unique constraint")
-        || msg.contains("idx_contracts_gig_user_unique")
-}
-
 /// POST /api/contracts — a client sends a contract request on a freelancer's gig.
 ///
 /// The `user_id` is automatically set from the authenticated user's JWT (the client).
 /// The gig must exist, the client cannot contract on their own gig, and only one
 /// contract per client per gig is allowed.
+#[utoipa::path(
+    post,
+    path = "/api/contracts",
+    tag = "contracts",
+    request_body = CreateContractRequest,
+    responses(
+        (status = 201, description = "The new contract request", body = crate::models::contracts::Model),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such gig", body = ErrorBody),
+        (status = 409, description = "Already requested this gig", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn create_contract(
     user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
+    audit: Audit,
     body: web::Json<CreateContractRequest>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let client_id = user.0.id;
     let gig_id = body.gig_id;
 
     // 1. Verify the gig exists.
-    let gig = match gig_db::get_gig_by_id(db.get_ref(), gig_id).await {
-        Ok(Some(gig)) => gig,
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Gig {gig_id} not found"),
-            }));
-        }
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let gig = gig_db::get_gig_by_id(db.get_ref(), gig_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Gig {gig_id} not found")))?;
 
     // 2. Prevent clients from contracting on their own gig.
-    if gig.user_id == client_id {
-        return HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "You cannot create a contract on your own gig",
-        }));
-    }
+    let resource = Resource::Contract(ContractParties {
+        client_id,
+        freelancer_id: gig.user_id,
+        status: Status::Pending,
+    });
+    Policy::can(&Actor::from(&user.0), Action::Create, &resource)
+        .map_err(|denied| ApiError::validation(denied.reason))?;
 
     // 3. Create the contract. The DB unique index on (gig_id, user_id) is the source of truth
     // for duplicate prevention under concurrency.
@@ -56,15 +66,20 @@ pub async fn create_contract(
         user_id: client_id,
     };
 
-    match contract_db::insert_contract(db.get_ref(), input).await {
-        Ok(contract) => HttpResponse::Created().json(contract),
-        Err(e) if is_unique_violation(&e) => HttpResponse::Conflict().json(serde_json::json!({
-            "error": "You have already sent a contract request for this gig",
-        })),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to create contract: {e}"),
-        })),
-    }
+    let contract = match contract_db::insert_contract(db.get_ref(), input).await {
+        Ok(contract) => contract,
+        Err(e) if is_unique_violation(&e) => {
+            return Err(ApiError::conflict(
+                "You have already sent a contract request for this gig",
+            ));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let event = AuditEvent::new(client_id, "contract.create", Target::Contract(contract.id))
+        .after(&contract);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Created().json(contract))
 }
 
 /// GET /api/contracts — list contracts relevant to the authenticated user.
@@ -72,42 +87,34 @@ pub async fn create_contract(
 /// Returns contracts where the user is either:
 /// - The client (user_id on the contract), OR
 /// - The freelancer (owner of the gig referenced by the contract).
+#[utoipa::path(
+    get,
+    path = "/api/contracts",
+    tag = "contracts",
+    responses(
+        (status = 200, description = "Contracts where the caller is client or freelancer", body = Vec<crate::models::contracts::Model>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn get_contracts(
-    user: AuthenticatedUser,
+    user: Principal,
     db: web::Data<DatabaseConnection>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    user.require(ApiScope::ContractsRead)?;
+
     let user_id = user.0.id;
 
     // Get contracts where user is the client.
-    let as_client = match contract_db::get_contracts_by_user_id(db.get_ref(), user_id).await {
-        Ok(contracts) => contracts,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let as_client = contract_db::get_contracts_by_user_id(db.get_ref(), user_id).await?;
 
     // Get all gigs owned by this user, then get contracts on those gigs.
-    let user_gigs = match gig_db::get_gigs_by_user_id(db.get_ref(), user_id).await {
-        Ok(gigs) => gigs,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let user_gigs = gig_db::get_gigs_by_user_id(db.get_ref(), user_id).await?;
 
     // Batch fetch all contracts for user's gigs in a single query (N+1 fix)
     let gig_ids: Vec<Uuid> = user_gigs.iter().map(|g| g.id).collect();
-    let as_freelancer = match contract_db::get_contracts_by_gig_ids(db.get_ref(), gig_ids).await {
-        Ok(contracts) => contracts,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let as_freelancer = contract_db::get_contracts_by_gig_ids(db.get_ref(), gig_ids).await?;
 
     // Merge and deduplicate in O(n) (a user could be both client and gig owner in theory,
     // though we prevent self-contracts).
@@ -119,251 +126,232 @@ pub async fn get_contracts(
         }
     }
 
-    HttpResponse::Ok().json(all_contracts)
+    Ok(HttpResponse::Ok().json(all_contracts))
 }
 
 /// GET /api/contracts/{id} — get a single contract.
 ///
 /// Only the client (user_id on the contract) or the freelancer (gig owner) can view it.
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}",
+    tag = "contracts",
+    params(("id" = Uuid, Path, description = "Contract id")),
+    responses(
+        (status = 200, description = "The contract", body = crate::models::contracts::Model),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such contract", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn get_contract(
-    user: AuthenticatedUser,
+    user: Principal,
     db: web::Data<DatabaseConnection>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    user.require(ApiScope::ContractsRead)?;
+
     let contract_id = path.into_inner();
-    let user_id = user.0.id;
 
-    let contract = match contract_db::get_contract_by_id(db.get_ref(), contract_id).await {
-        Ok(Some(c)) => c,
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Contract {contract_id} not found"),
-            }));
-        }
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let (contract, parties) = load_contract(db.get_ref(), contract_id).await?;
 
     // Check authorization: user must be the client or the gig owner.
-    if contract.user_id != user_id {
-        match gig_db::get_gig_by_id(db.get_ref(), contract.gig_id).await {
-            Ok(Some(gig)) if gig.user_id == user_id => {} // authorized as gig owner
-            Ok(_) => {
-                return HttpResponse::Forbidden().json(serde_json::json!({
-                    "error": "You can only view contracts you are involved in",
-                }));
-            }
-            Err(e) => {
-                return HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                }));
-            }
-        }
-    }
+    let resource = Resource::Contract(parties);
+    Policy::can(&Actor::from(&user.0), Action::Read, &resource)?;
 
-    HttpResponse::Ok().json(contract)
+    Ok(HttpResponse::Ok().json(contract))
 }
 
 /// PUT /api/contracts/{id}/status — freelancer (gig owner) accepts or rejects a contract.
 ///
 /// Only the gig owner can update the status. The contract must be in Pending status.
+#[utoipa::path(
+    put,
+    path = "/api/contracts/{id}/status",
+    tag = "contracts",
+    params(("id" = Uuid, Path, description = "Contract id")),
+    request_body = UpdateContractStatus,
+    responses(
+        (status = 200, description = "The updated contract", body = crate::models::contracts::Model),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such contract", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn update_status(
     user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
+    audit: Audit,
     path: web::Path<Uuid>,
     body: web::Json<UpdateContractStatus>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let contract_id = path.into_inner();
-    let user_id = user.0.id;
 
     // 1. Fetch the contract.
-    let contract = match contract_db::get_contract_by_id(db.get_ref(), contract_id).await {
-        Ok(Some(c)) => c,
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Contract {contract_id} not found"),
-            }));
-        }
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let (contract, parties) = load_contract(db.get_ref(), contract_id).await?;
 
     // 2. Verify the authenticated user is the gig owner (freelancer).
-    match gig_db::get_gig_by_id(db.get_ref(), contract.gig_id).await {
-        Ok(Some(gig)) if gig.user_id == user_id => {} // authorized
-        Ok(Some(_)) => {
-            return HttpResponse::Forbidden().json(serde_json::json!({
-                "error": "Only the gig owner (freelancer) can accept or reject contracts",
-            }));
-        }
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": "The gig associated with this contract no longer exists",
-            }));
-        }
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    }
+    let resource = Resource::Contract(parties);
+    Policy::can(&Actor::from(&user.0), Action::Update, &resource)?;
 
     // 3. Only allow status updates on Pending contracts.
     if contract.status != Status::Pending {
-        return HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!(
-                "Contract is already {:?}. Only pending contracts can be updated.",
-                contract.status
-            ),
-        }));
+        return Err(ApiError::validation(format!(
+            "Contract is already {:?}. Only pending contracts can be updated.",
+            contract.status
+        )));
     }
 
     // 4. Update the status.
-    match contract_db::update_contract_status(db.get_ref(), contract_id, body.into_inner()).await {
-        Ok(updated) => HttpResponse::Ok().json(updated),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to update contract status: {e}"),
-        })),
-    }
+    let updated =
+        contract_db::update_contract_status(db.get_ref(), contract_id, body.into_inner()).await?;
+    let event = AuditEvent::new(
+        user.0.id,
+        "contract.status_change",
+        Target::Contract(contract_id),
+    )
+    .before(&contract)
+    .after(&updated);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(updated))
 }
 
 /// DELETE /api/contracts/{id} — client withdraws a pending contract request.
 ///
 /// Only the client who created the contract can withdraw it, and only while it is Pending.
+#[utoipa::path(
+    delete,
+    path = "/api/contracts/{id}",
+    tag = "contracts",
+    params(("id" = Uuid, Path, description = "Contract id")),
+    responses(
+        (status = 200, description = "Contract request withdrawn", body = Confirmation),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such contract", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn delete_contract(
     user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
+    audit: Audit,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let contract_id = path.into_inner();
-    let user_id = user.0.id;
 
     // 1. Fetch the contract.
-    let contract = match contract_db::get_contract_by_id(db.get_ref(), contract_id).await {
-        Ok(Some(c)) => c,
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Contract {contract_id} not found"),
-            }));
-        }
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    };
+    let (contract, parties) = load_contract(db.get_ref(), contract_id).await?;
 
     // 2. Only the client who created the contract can withdraw it.
-    if contract.user_id != user_id {
-        return HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You can only withdraw your own contract requests",
-        }));
-    }
+    let resource = Resource::Contract(parties);
+    Policy::can(&Actor::from(&user.0), Action::Delete, &resource)?;
 
     // 3. Only allow withdrawal of Pending contracts.
     if contract.status != Status::Pending {
-        return HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!(
-                "Contract is already {:?}. Only pending contracts can be withdrawn.",
-                contract.status
-            ),
-        }));
+        return Err(ApiError::validation(format!(
+            "Contract is already {:?}. Only pending contracts can be withdrawn.",
+            contract.status
+        )));
     }
 
     // 4. Delete the contract.
-    match contract_db::delete_contract(db.get_ref(), contract_id).await {
-        Ok(result) => {
-            if result.rows_affected > 0 {
-                HttpResponse::Ok().json(serde_json::json!({
-                    "message": format!("Contract {contract_id} withdrawn"),
-                }))
-            } else {
-                HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("Contract {contract_id} not found"),
-                }))
-            }
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to delete contract: {e}"),
-        })),
+    let result = contract_db::delete_contract(db.get_ref(), contract_id).await?;
+    if result.rows_affected == 0 {
+        return Err(ApiError::not_found(format!(
+            "Contract {contract_id} not found"
+        )));
     }
+
+    let event = AuditEvent::new(user.0.id, "contract.delete", Target::Contract(contract_id))
+        .before(&contract);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(Confirmation::new(format!(
+        "Contract {contract_id} withdrawn"
+    ))))
 }
 
 /// GET /api/contracts/gig/{gig_id} — get all contracts for a specific gig.
 ///
 /// Only the gig owner (freelancer) can view all contracts on their gig.
+#[utoipa::path(
+    get,
+    path = "/api/contracts/gig/{gig_id}",
+    tag = "contracts",
+    params(("gig_id" = Uuid, Path, description = "Gig id")),
+    responses(
+        (status = 200, description = "Contracts on the gig", body = Vec<crate::models::contracts::Model>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such gig", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn get_contracts_by_gig(
-    user: AuthenticatedUser,
+    user: Principal,
     db: web::Data<DatabaseConnection>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    user.require(ApiScope::ContractsRead)?;
+
     let gig_id = path.into_inner();
-    let user_id = user.0.id;
 
     // Verify the authenticated user owns the gig.
-    match gig_db::get_gig_by_id(db.get_ref(), gig_id).await {
-        Ok(Some(gig)) if gig.user_id == user_id => {} // authorized
-        Ok(Some(_)) => {
-            return HttpResponse::Forbidden().json(serde_json::json!({
-                "error": "Only the gig owner can view contracts for this gig",
-            }));
-        }
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Gig {gig_id} not found"),
-            }));
-        }
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-    }
+    let gig = gig_db::get_gig_by_id(db.get_ref(), gig_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Gig {gig_id} not found")))?;
+    let resource = Resource::GigContracts {
+        owner_id: gig.user_id,
+    };
+    Policy::can(&Actor::from(&user.0), Action::Read, &resource)?;
 
-    match contract_db::get_contracts_by_gig_id(db.get_ref(), gig_id).await {
-        Ok(contracts) => HttpResponse::Ok().json(contracts),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Database error: {e}"),
-        })),
-    }
+    let contracts = contract_db::get_contracts_by_gig_id(db.get_ref(), gig_id).await?;
+    Ok(HttpResponse::Ok().json(contracts))
 }
 
 /// GET /api/contracts/user/{user_id} — get all contracts sent by a specific user (client).
 ///
 /// Users can only view their own sent contracts.
+#[utoipa::path(
+    get,
+    path = "/api/contracts/user/{user_id}",
+    tag = "contracts",
+    params(("user_id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "Contracts sent by the user", body = Vec<crate::models::contracts::Model>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn get_contracts_by_user(
-    auth_user: AuthenticatedUser,
+    auth_user: Principal,
     db: web::Data<DatabaseConnection>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    auth_user.require(ApiScope::ContractsRead)?;
+
     let target_user_id = path.into_inner();
 
     // Users can only view their own contracts.
-    if auth_user.0.id != target_user_id {
-        return HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You can only view your own contracts",
-        }));
-    }
+    let resource = Resource::UserContracts {
+        client_id: target_user_id,
+    };
+    Policy::can(&Actor::from(&auth_user.0), Action::Read, &resource)?;
 
-    match contract_db::get_contracts_by_user_id(db.get_ref(), target_user_id).await {
-        Ok(contracts) => HttpResponse::Ok().json(contracts),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Database error: {e}"),
-        })),
-    }
+    let contracts = contract_db::get_contracts_by_user_id(db.get_ref(), target_user_id).await?;
+    Ok(HttpResponse::Ok().json(contracts))
 }
 
 // ── Request DTOs ──
 
 /// Request body for POST /api/contracts.
 /// Only `gig_id` is required — `user_id` comes from the JWT.
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, utoipa::ToSchema)]
 pub struct CreateContractRequest {
     pub gig_id: Uuid,
 }
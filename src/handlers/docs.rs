@@ -0,0 +1,9 @@
+use actix_web::HttpResponse;
+use utoipa::OpenApi;
+
+use crate::openapi::ApiDoc;
+
+/// GET /api/openapi.json — the OpenAPI 3 document for every `/api` route.
+pub async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
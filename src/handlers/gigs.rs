@@ -1,23 +1,38 @@
-use actix_web::{HttpResponse, Responder, web};
-use sea_orm::DatabaseConnection;
+use actix_web::{HttpResponse, web};
+use sea_orm::{ActiveEnum, DatabaseConnection};
 use std::sync::Arc;
 use uuid::Uuid;
-use tracing;
 
-use crate::auth::middleware::AuthenticatedUser;
-use crate::auth::authorization::verify_gig_owner;
-use crate::cache::{RedisCache, keys};
+use crate::audit::{Audit, AuditEvent, Target};
+use crate::auth::authorization::authorize_gig;
+use crate::auth::policy::{Action, Actor, Policy, Resource};
+use crate::auth::principal::Principal;
+use crate::cache::{CacheConfig, CacheStore, keys, tags};
 use crate::db::gigs as gig_db;
+use crate::error::{ApiError, ErrorBody};
+use crate::models::Confirmation;
+use crate::models::api_keys::ApiScope;
 use crate::models::gigs::{Categories, CreateGig, GigListQuery, UpdateGig};
 
 /// GET /api/gigs — list all gigs with pagination. (NO AUTHENTICATION REQUIRED)
 /// Query params: ?page=1&limit=20
+#[utoipa::path(
+    get,
+    path = "/api/gigs",
+    tag = "gigs",
+    params(GigListQuery),
+    responses(
+        (status = 200, description = "A page of gigs, newest first", body = Vec<crate::models::gigs::Model>),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+    )
+)]
 pub async fn get_gigs(
     // _user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    ttls: web::Data<CacheConfig>,
     query: web::Query<GigListQuery>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let limit = query.limit();
     let cursor_created_at = query.cursor_created_at;
     let cursor_id = query.cursor_id;
@@ -27,272 +42,283 @@ pub async fn get_gigs(
     };
     let cache_key = keys::gig_list(&format!("l{limit}:{cursor_part}"));
 
-    match cache.get::<serde_json::Value>(&cache_key).await {
-        Ok(Some(cached)) => HttpResponse::Ok().json(cached),
-        Ok(None) => {
-            match gig_db::get_gigs_paginated(db.get_ref(), limit, cursor_created_at, cursor_id)
-                .await
-            {
-                Ok(gigs) => {
-                    let _ = cache.set(&cache_key, &gigs, Some(300)).await;
-                    HttpResponse::Ok().json(gigs)
-                }
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Failed to fetch gigs: {e}"),
-                })),
-            }
-        }
-        Err(e) => {
-            tracing::warn!("Cache error: {}", e);
-            match gig_db::get_gigs_paginated(db.get_ref(), limit, cursor_created_at, cursor_id)
-                .await
-            {
-                Ok(gigs) => HttpResponse::Ok().json(gigs),
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Failed to fetch gigs: {e}"),
-                })),
-            }
-        }
-    }
+    let gigs = cache
+        .cached_tagged(
+            &cache_key,
+            &[tags::all_gigs()],
+            ttls.gig_list_ttl,
+            || async {
+                gig_db::get_gigs_paginated(db.get_ref(), limit, cursor_created_at, cursor_id)
+                    .await
+                    .map_err(ApiError::from)
+            },
+        )
+        .await?;
+    Ok(HttpResponse::Ok().json(gigs))
 }
 
 /// GET /api/gigs/{id} — get a single gig (requires authentication).
+#[utoipa::path(
+    get,
+    path = "/api/gigs/{id}",
+    tag = "gigs",
+    params(("id" = Uuid, Path, description = "Gig id")),
+    responses(
+        (status = 200, description = "The gig", body = crate::models::gigs::Model),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such gig", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn get_gig(
-    _user: AuthenticatedUser,
+    user: Principal,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    ttls: web::Data<CacheConfig>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    user.require(ApiScope::GigsRead)?;
+
     let id = path.into_inner();
     let cache_key = keys::gig(&id.to_string());
 
-    // Try to get from cache first
-    match cache.get::<serde_json::Value>(&cache_key).await {
-        Ok(Some(cached)) => {
-            HttpResponse::Ok().json(cached)
-        }
-        Ok(None) => {
-            // Cache miss - fetch from database
-            match gig_db::get_gig_by_id(db.get_ref(), id).await {
-                Ok(Some(gig)) => {
-                    // Store in cache (10 minute TTL)
-                    let _ = cache.set(&cache_key, &gig, Some(600)).await;
-                    HttpResponse::Ok().json(gig)
-                }
-                Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("Gig {id} not found"),
-                })),
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                })),
-            }
-        }
-        Err(e) => {
-            // Cache error - fallback to database
-            tracing::warn!("Cache error: {}", e);
-            match gig_db::get_gig_by_id(db.get_ref(), id).await {
-                Ok(Some(gig)) => HttpResponse::Ok().json(gig),
-                Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("Gig {id} not found"),
-                })),
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                })),
-            }
-        }
-    }
+    let gig = cache
+        .cached(&cache_key, ttls.gig_ttl, || async {
+            gig_db::get_gig_by_id(db.get_ref(), id)
+                .await?
+                .ok_or_else(|| ApiError::not_found(format!("Gig {id} not found")))
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(gig))
 }
 
 /// GET /api/gigs/category/{category} — get gigs by category
-
+#[utoipa::path(
+    get,
+    path = "/api/gigs/category/{category}",
+    tag = "gigs",
+    params(("category" = Categories, Path, description = "Gig category")),
+    responses(
+        (status = 200, description = "Gigs in the category", body = Vec<crate::models::gigs::Model>),
+        (status = 400, description = "Unknown category", body = ErrorBody),
+    )
+)]
 pub async fn get_gigs_by_category(
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    ttls: web::Data<CacheConfig>,
     path: web::Path<String>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let category_raw = path.into_inner();
-    let category = match parse_category(&category_raw) {
-        Some(c) => c,
-        None => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": format!("Invalid category: {category_raw}"),
-            }));
-        }
-    };
-    let cache_key = keys::gigs_by_category(&category_raw.to_lowercase());
+    let category = parse_category(&category_raw)
+        .ok_or_else(|| ApiError::validation(format!("Invalid category: {category_raw}")))?;
+    let category_tags = [tags::gig_category(&category.to_value())];
+    let cache_key = keys::gigs_by_category(&category.to_value());
 
-    match cache.get::<serde_json::Value>(&cache_key).await {
-        Ok(Some(cached)) => HttpResponse::Ok().json(cached),
-        Ok(None) => {
-            match gig_db::get_gigs_by_category(db.get_ref(), category.clone()).await {
-                Ok(gigs) => {
-                    let _ = cache.set(&cache_key, &gigs, Some(300)).await;
-                    HttpResponse::Ok().json(gigs)
-                }
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                })),
-            }
-        }
-        Err(e) => {
-            tracing::warn!("Cache error: {}", e);
-            match gig_db::get_gigs_by_category(db.get_ref(), category).await {
-                Ok(gigs) => HttpResponse::Ok().json(gigs),
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                })),
-            }
-        }
-    }
+    let gigs = cache
+        .cached_tagged(&cache_key, &category_tags, ttls.gig_list_ttl, || async {
+            gig_db::get_gigs_by_category(db.get_ref(), category)
+                .await
+                .map_err(ApiError::from)
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(gigs))
 }
 
 /// GET /api/gigs/user/{user_id} — get gigs by user_id (requires authentication).
+#[utoipa::path(
+    get,
+    path = "/api/gigs/user/{user_id}",
+    tag = "gigs",
+    params(("user_id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "The user's gigs", body = Vec<crate::models::gigs::Model>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn get_gigs_by_user_id(
-    _user: AuthenticatedUser,
+    user: Principal,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    ttls: web::Data<CacheConfig>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    user.require(ApiScope::GigsRead)?;
+
     let user_id = path.into_inner();
     let cache_key = keys::user_gigs(&user_id.to_string());
+    let user_tags = [tags::user(&user_id.to_string())];
 
-    match cache.get::<serde_json::Value>(&cache_key).await {
-        Ok(Some(cached)) => HttpResponse::Ok().json(cached),
-        Ok(None) => match gig_db::get_gigs_by_user_id(db.get_ref(), user_id).await {
-            Ok(gigs) => {
-                let _ = cache.set(&cache_key, &gigs, Some(300)).await;
-                HttpResponse::Ok().json(gigs)
-            }
-            Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            })),
-        },
-        Err(e) => {
-            tracing::warn!("Cache error: {}", e);
-            match gig_db::get_gigs_by_user_id(db.get_ref(), user_id).await {
-                Ok(gigs) => HttpResponse::Ok().json(gigs),
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                })),
-            }
-        }
-    }
+    let gigs = cache
+        .cached_tagged(&cache_key, &user_tags, ttls.gig_list_ttl, || async {
+            gig_db::get_gigs_by_user_id(db.get_ref(), user_id)
+                .await
+                .map_err(ApiError::from)
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(gigs))
 }
 
 /// DELETE /api/gigs/user/{user_id} — delete all gigs by user_id (requires authentication).
+#[utoipa::path(
+    delete,
+    path = "/api/gigs/user/{user_id}",
+    tag = "gigs",
+    params(("user_id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 204, description = "The user's gigs were deleted"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn delete_all_gig_by_user_id(
-    user: AuthenticatedUser,
+    user: Principal,
     db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    user.require(ApiScope::GigsWrite)?;
+
     let user_id = path.into_inner();
 
     // Users can only delete their own gigs.
-    if user.0.id != user_id {
-        return HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You can only delete your own gigs",
-        }));
-    }
+    let resource = Resource::UserGigs { owner_id: user_id };
+    Policy::can(&Actor::from(&user.0), Action::Delete, &resource)?;
 
-    match gig_db::delete_all_gig_by_user_id(db.get_ref(), user_id).await {
-        Ok(()) => HttpResponse::NoContent().finish(),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Database error: {e}"),
-        })),
-    }
+    gig_db::delete_all_gig_by_user_id(db.get_ref(), user_id).await?;
+    let _ = cache.invalidate_tags(&tags::for_user_gigs(user_id)).await;
+    let event = AuditEvent::new(user.0.id, "gig.delete_all", Target::User(user_id));
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 /// POST /api/gigs — create a new gig (requires authentication).
+#[utoipa::path(
+    post,
+    path = "/api/gigs",
+    tag = "gigs",
+    request_body = CreateGig,
+    responses(
+        (status = 201, description = "The new gig", body = crate::models::gigs::Model),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn create_gig(
-    user: AuthenticatedUser,
+    user: Principal,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
     body: web::Json<CreateGig>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    user.require(ApiScope::GigsWrite)?;
+
     let user_id = user.0.id;
-    match gig_db::insert_gig(db.get_ref(), body.into_inner(), user_id).await {
-        Ok(gig) => {
-            // Invalidate user's gigs cache and all gigs list
-            let _ = cache.delete(&keys::user_gigs(&user_id.to_string())).await;
-            let _ = cache.delete_pattern("gigs:list:*").await;
-            HttpResponse::Created().json(gig)
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to create gig: {e}"),
-        })),
-    }
+    let resource = Resource::Gig { owner_id: user_id };
+    Policy::can(&Actor::from(&user.0), Action::Create, &resource)?;
+
+    let gig = gig_db::insert_gig(db.get_ref(), body.into_inner(), user_id).await?;
+
+    // Invalidate the listings the new gig appears in
+    let _ = cache.invalidate_tags(&tags::for_gig(&gig)).await;
+    let event = AuditEvent::new(user_id, "gig.create", Target::Gig(gig.id)).after(&gig);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Created().json(gig))
 }
 
 /// PUT /api/gigs/{id} — update a gig (requires authentication).
+#[utoipa::path(
+    put,
+    path = "/api/gigs/{id}",
+    tag = "gigs",
+    params(("id" = Uuid, Path, description = "Gig id")),
+    request_body = UpdateGig,
+    responses(
+        (status = 200, description = "The updated gig", body = crate::models::gigs::Model),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such gig", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn update_gig(
-    user: AuthenticatedUser,
+    user: Principal,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
     path: web::Path<Uuid>,
     body: web::Json<UpdateGig>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    user.require(ApiScope::GigsWrite)?;
+
     let id = path.into_inner();
-    let user_id = user.0.id;
 
     // Verify the user owns the gig
-    if let Err(resp) = verify_gig_owner(db.get_ref(), id, user_id).await {
-        return resp;
-    }
+    let actor = Actor::from(&user.0);
+    let before = authorize_gig(db.get_ref(), id, &actor, Action::Update).await?;
 
-    match gig_db::update_gig(db.get_ref(), id, body.into_inner()).await {
-        Ok(updated) => {
-            // Invalidate specific gig cache and related caches
-            let _ = cache.delete(&keys::gig(&id.to_string())).await;
-            let _ = cache.delete_pattern("gigs:list:*").await;
-            HttpResponse::Ok().json(updated)
-        }
-        Err(e) => {
-            let mut status = if e.to_string().contains("not found") {
-                HttpResponse::NotFound()
-            } else {
-                HttpResponse::InternalServerError()
-            };
-            status.json(serde_json::json!({
-                "error": format!("Failed to update gig: {e}"),
-            }))
-        }
-    }
+    let updated = gig_db::update_gig(db.get_ref(), id, body.into_inner()).await?;
+
+    // Invalidate specific gig cache and the listings it appeared in before and after
+    let _ = cache.delete(&keys::gig(&id.to_string())).await;
+    let _ = cache
+        .invalidate_tags(&tags::for_gig_update(&before, &updated))
+        .await;
+    let event = AuditEvent::new(user.0.id, "gig.update", Target::Gig(id))
+        .before(&before)
+        .after(&updated);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(updated))
 }
 
 /// DELETE /api/gigs/{id} — delete a gig (requires authentication).
+#[utoipa::path(
+    delete,
+    path = "/api/gigs/{id}",
+    tag = "gigs",
+    params(("id" = Uuid, Path, description = "Gig id")),
+    responses(
+        (status = 200, description = "Gig deleted", body = Confirmation),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such gig", body = ErrorBody),
+    ),
+    security(("bearer" = []), ("api_key" = []))
+)]
 pub async fn delete_gig(
-    user: AuthenticatedUser,
+    user: Principal,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    user.require(ApiScope::GigsWrite)?;
+
     let id = path.into_inner();
-    let user_id = user.0.id;
 
     // Verify the user owns the gig.
-    if let Err(resp) = verify_gig_owner(db.get_ref(), id, user_id).await {
-        return resp;
-    }
+    let actor = Actor::from(&user.0);
+    let gig = authorize_gig(db.get_ref(), id, &actor, Action::Delete).await?;
 
-    match gig_db::delete_gig(db.get_ref(), id).await {
-        Ok(result) => {
-            if result.rows_affected > 0 {
-                // Invalidate specific gig cache and related caches
-                let _ = cache.delete(&keys::gig(&id.to_string())).await;
-                let _ = cache.delete_pattern("gigs:list:*").await;
-                HttpResponse::Ok().json(serde_json::json!({
-                    "message": format!("Gig {id} deleted"),
-                }))
-            } else {
-                HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("Gig {id} not found"),
-                }))
-            }
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to delete gig: {e}"),
-        })),
+    let result = gig_db::delete_gig(db.get_ref(), id).await?;
+    if result.rows_affected == 0 {
+        return Err(ApiError::not_found(format!("Gig {id} not found")));
     }
+
+    // Invalidate specific gig cache and related caches
+    let _ = cache.delete(&keys::gig(&id.to_string())).await;
+    let _ = cache.invalidate_tags(&tags::for_gig(&gig)).await;
+    let event = AuditEvent::new(user.0.id, "gig.delete", Target::Gig(id)).before(&gig);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(Confirmation::new(format!("Gig {id} deleted"))))
 }
 
 fn parse_category(input: &str) -> Option<Categories> {
@@ -0,0 +1,165 @@
+use actix_web::{HttpResponse, web};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::auth::verifier::VerifierData;
+use crate::cache::CacheStore;
+use crate::config::AppConfig;
+
+/// How long a single dependency check may take before it counts as down.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckState {
+    Up,
+    Down,
+}
+
+/// Result of probing one dependency.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyCheck {
+    pub name: &'static str,
+    pub state: CheckState,
+    /// Whether the instance is not ready while this dependency is down.
+    pub required: bool,
+    pub latency_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Readiness {
+    /// Every dependency is up.
+    Ready,
+    /// Only optional dependencies are down; the instance still serves traffic.
+    Degraded,
+    /// A required dependency is down.
+    NotReady,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub status: Readiness,
+    pub checks: Vec<DependencyCheck>,
+}
+
+impl HealthReport {
+    pub fn new(checks: Vec<DependencyCheck>) -> Self {
+        let down = |required: bool| {
+            checks
+                .iter()
+                .any(|c| c.required == required && c.state == CheckState::Down)
+        };
+        let status = if down(true) {
+            Readiness::NotReady
+        } else if down(false) {
+            Readiness::Degraded
+        } else {
+            Readiness::Ready
+        };
+
+        Self { status, checks }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.status != Readiness::NotReady
+    }
+}
+
+/// Time `probe`, giving up after [`CHECK_TIMEOUT`]. The probe returns an
+/// optional detail when the dependency is up, or why it is down.
+pub async fn check<F>(name: &'static str, required: bool, probe: F) -> DependencyCheck
+where
+    F: Future<Output = Result<Option<String>, String>>,
+{
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, probe)
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {}s", CHECK_TIMEOUT.as_secs())));
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let (state, detail) = match result {
+        Ok(detail) => (CheckState::Up, detail),
+        Err(e) => (CheckState::Down, Some(e)),
+    };
+    DependencyCheck {
+        name,
+        state,
+        required,
+        latency_ms,
+        detail,
+    }
+}
+
+/// Log why `check` failed and return a fixed detail: `/health/ready` is
+/// unauthenticated, so database and cache errors stay in the logs.
+fn unreachable(check: &str, error: impl std::fmt::Display) -> String {
+    tracing::error!("Readiness: {check} check failed: {error}");
+    "unreachable".to_string()
+}
+
+/// GET /health/live — the process is up and serving requests.
+pub async fn live() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({ "status": "alive" }))
+}
+
+/// GET /health/ready — probe every dependency; 503 if a required one is down.
+///
+/// The cache only counts as required when `redis.required` is set, since the
+/// app otherwise keeps serving from Postgres while Redis reconnects.
+pub async fn ready(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<Arc<CacheStore>>,
+    verifier: web::Data<VerifierData>,
+) -> HttpResponse {
+    let db = db.get_ref();
+    let (database, cache, jwks, migrations) = tokio::join!(
+        check("database", true, async {
+            db.ping()
+                .await
+                .map(|_| None)
+                .map_err(|e| unreachable("database", e))
+        }),
+        check("cache", config.redis.required, async {
+            cache
+                .ping()
+                .await
+                .map(|_| Some(cache.backend_name().to_string()))
+                .map_err(|e| format!("{}: {}", cache.backend_name(), unreachable("cache", e)))
+        }),
+        check("jwks", true, async {
+            match verifier.trusted_keys() {
+                None => Ok(Some("shared secret".to_string())),
+                Some(0) => Err("no signing keys loaded".to_string()),
+                Some(n) => Ok(Some(format!("{n} keys"))),
+            }
+        }),
+        check("migrations", true, async {
+            let pending = Migrator::get_pending_migrations(db)
+                .await
+                .map_err(|e| unreachable("migrations", e))?;
+            match pending.as_slice() {
+                [] => Ok(None),
+                pending => {
+                    let names: Vec<&str> = pending.iter().map(|m| m.name()).collect();
+                    tracing::warn!("Readiness: pending migrations: {}", names.join(", "));
+                    Err(format!("{} pending migrations", pending.len()))
+                }
+            }
+        }),
+    );
+
+    let report = HealthReport::new(vec![database, cache, jwks, migrations]);
+    if report.is_ready() {
+        HttpResponse::Ok().json(report)
+    } else {
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
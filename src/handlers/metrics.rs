@@ -0,0 +1,19 @@
+use actix_web::{HttpResponse, web};
+use std::sync::Arc;
+
+use crate::chat::server::ChatServer;
+use crate::metrics::metrics;
+
+/// GET /metrics — Prometheus text exposition of every registered metric.
+///
+/// Chat gauges are sampled from the `ChatServer` at scrape time.
+pub async fn export(chat_server: web::Data<Arc<ChatServer>>) -> HttpResponse {
+    let m = metrics();
+    let (rooms, connections) = chat_server.connection_counts().await;
+    m.chat_rooms.set(rooms as i64);
+    m.chat_connections.set(connections as i64);
+
+    HttpResponse::Ok()
+        .content_type(prometheus::TEXT_FORMAT)
+        .body(m.render())
+}
@@ -1,18 +1,53 @@
+pub mod admin;
+pub mod api_keys;
 pub mod auth;
 pub mod chat;
 pub mod contracts;
+pub mod docs;
 pub mod gigs;
+pub mod health;
+pub mod metrics;
 pub mod portfolio;
 pub mod users;
 
 use actix_web::web;
+use utoipa::OpenApi;
+use utoipa_scalar::{Scalar, Servable};
+
+use crate::openapi::ApiDoc;
+
+/// Liveness and readiness probes, mounted outside `/api` for load balancers.
+pub fn init_health_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/health")
+            .route("/live", web::get().to(health::live))
+            .route("/ready", web::get().to(health::ready)),
+    );
+}
+
+/// Prometheus scrape endpoint, mounted outside `/api` like the health probes.
+pub fn init_metrics_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(metrics::export));
+}
+
+/// The OpenAPI document and its viewer. Mounted ahead of the `/api` scope so
+/// they take precedence over it and skip rate limiting.
+pub fn init_docs_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/openapi.json", web::get().to(docs::openapi_json))
+        .service(Scalar::with_url("/api/docs", ApiDoc::openapi()));
+}
 
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
     // ── Auth routes (protected by JWT via the AuthenticatedUser extractor) ──
     cfg.service(
         web::scope("/auth")
             .route("/me", web::get().to(auth::me))
-            .route("/complete-profile", web::post().to(auth::complete_profile)),
+            .route("/complete-profile", web::post().to(auth::complete_profile))
+            .route("/logout", web::post().to(auth::logout))
+            .route("/logout-all", web::post().to(auth::logout_all))
+            .route("/api-keys", web::get().to(api_keys::list_api_keys))
+            .route("/api-keys", web::post().to(api_keys::create_api_key))
+            .route("/api-keys/{id}", web::delete().to(api_keys::revoke_api_key)),
     );
 
     // ── User routes (all protected — require valid JWT) ──
@@ -75,8 +110,54 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
             ),
     );
 
+    // ── Admin routes (require the Admin role via RequireRole<Admin>) ──
+    cfg.service(
+        web::scope("/admin")
+            .route("/users", web::get().to(admin::list_users))
+            .route("/users/{id}", web::put().to(admin::update_user))
+            .route("/users/{id}", web::delete().to(admin::delete_user))
+            .route("/users/{id}/role", web::put().to(admin::set_user_role))
+            .route("/users/{id}/suspend", web::post().to(admin::suspend_user))
+            .route("/users/{id}/ban", web::post().to(admin::ban_user))
+            .route(
+                "/users/{id}/unsuspend",
+                web::post().to(admin::unsuspend_user),
+            )
+            .route(
+                "/users/{id}/revoke-sessions",
+                web::post().to(admin::revoke_user_sessions),
+            )
+            .route(
+                "/users/{id}/api-keys",
+                web::get().to(admin::list_user_api_keys),
+            )
+            .route(
+                "/users/{id}/api-keys",
+                web::post().to(admin::create_user_api_key),
+            )
+            .route("/api-keys/{id}", web::delete().to(admin::revoke_api_key))
+            .route("/user-cache", web::get().to(admin::user_cache_stats))
+            .route("/cache", web::get().to(admin::cache_stats))
+            .route("/gigs", web::get().to(admin::list_gigs))
+            .route("/gigs/{id}", web::put().to(admin::update_gig))
+            .route("/gigs/{id}", web::delete().to(admin::delete_gig))
+            .route("/portfolios", web::get().to(admin::list_portfolios))
+            .route("/portfolios/{id}", web::put().to(admin::update_portfolio))
+            .route(
+                "/portfolios/{id}",
+                web::delete().to(admin::delete_portfolio),
+            )
+            .route("/contracts", web::get().to(admin::list_contracts))
+            .route(
+                "/contracts/{id}/status",
+                web::put().to(admin::update_contract_status),
+            )
+            .route("/contracts/{id}", web::delete().to(admin::delete_contract))
+            .route("/audit-events", web::get().to(admin::list_audit_events)),
+    );
+
     // ── Chat routes ──
-    // WebSocket endpoint (auth via query param token):
+    // WebSocket endpoint (auth via ticket, bearer subprotocol or, if enabled, query token):
     cfg.service(
         web::resource("/chat/ws/{contract_id}")
             .route(web::get().to(crate::chat::session::ws_connect)),
@@ -84,6 +165,7 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
     // REST endpoints (auth via Authorization header):
     cfg.service(
         web::scope("/chat")
+            .route("/ws-ticket", web::post().to(chat::create_ws_ticket))
             .route("/conversations", web::get().to(chat::get_conversations))
             .route("/{contract_id}/messages", web::get().to(chat::get_messages))
             .route(
@@ -1,197 +1,216 @@
-use actix_web::{HttpResponse, Responder, web};
+use actix_web::{HttpResponse, web};
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 use uuid::Uuid;
-use tracing;
 
+use crate::audit::{Audit, AuditEvent, Target};
+use crate::auth::authorization::authorize_portfolio;
 use crate::auth::middleware::AuthenticatedUser;
-use crate::cache::{RedisCache, keys};
+use crate::auth::policy::{Action, Actor, Policy, Resource};
+use crate::cache::{CacheConfig, CacheStore, keys};
 use crate::db::portfolio as portfolio_db;
+use crate::error::{ApiError, ErrorBody};
+use crate::models::Confirmation;
 use crate::models::portfolio::{CreatePortfolio, UpdatePortfolio};
 
 /// GET /api/portfolios — list all portfolio items (requires authentication).
+#[utoipa::path(
+    get,
+    path = "/api/portfolios",
+    tag = "portfolios",
+    responses(
+        (status = 200, description = "All portfolio items", body = Vec<crate::models::portfolio::Model>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn get_portfolios(
     _user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-) -> impl Responder {
-    match portfolio_db::get_all_portfolios(db.get_ref()).await {
-        Ok(items) => HttpResponse::Ok().json(items),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to fetch portfolios: {e}"),
-        })),
-    }
+) -> Result<HttpResponse, ApiError> {
+    let items = portfolio_db::get_all_portfolios(db.get_ref()).await?;
+    Ok(HttpResponse::Ok().json(items))
 }
 
 /// GET /api/portfolios/{id} — get a single portfolio item (requires authentication).
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}",
+    tag = "portfolios",
+    params(("id" = Uuid, Path, description = "Portfolio item id")),
+    responses(
+        (status = 200, description = "The portfolio item", body = crate::models::portfolio::Model),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 404, description = "No such portfolio item", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn get_portfolio(
     _user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let id = path.into_inner();
-    match portfolio_db::get_portfolio_by_id(db.get_ref(), id).await {
-        Ok(Some(item)) => HttpResponse::Ok().json(item),
-        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": format!("Portfolio item {id} not found"),
-        })),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Database error: {e}"),
-        })),
-    }
+    let item = portfolio_db::get_portfolio_by_id(db.get_ref(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Portfolio item {id} not found")))?;
+    Ok(HttpResponse::Ok().json(item))
 }
 
 /// GET /api/portfolios/freelancer/{freelancer_id} — list portfolio items for a freelancer.
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/freelancer/{freelancer_id}",
+    tag = "portfolios",
+    params(("freelancer_id" = Uuid, Path, description = "Freelancer id")),
+    responses(
+        (status = 200, description = "The freelancer's portfolio items", body = Vec<crate::models::portfolio::Model>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn get_portfolios_by_freelancer(
     _user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    ttls: web::Data<CacheConfig>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let freelancer_id = path.into_inner();
     let cache_key = keys::portfolio(&freelancer_id.to_string());
 
-    match cache.get::<serde_json::Value>(&cache_key).await {
-        Ok(Some(cached)) => HttpResponse::Ok().json(cached),
-        Ok(None) => {
-            match portfolio_db::get_portfolios_by_freelancer(db.get_ref(), freelancer_id).await {
-                Ok(items) => {
-                    let _ = cache.set(&cache_key, &items, Some(600)).await;
-                    HttpResponse::Ok().json(items)
-                }
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Failed to fetch portfolios: {e}"),
-                })),
-            }
-        }
-        Err(e) => {
-            tracing::warn!("Cache error: {}", e);
-            match portfolio_db::get_portfolios_by_freelancer(db.get_ref(), freelancer_id).await {
-                Ok(items) => HttpResponse::Ok().json(items),
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Failed to fetch portfolios: {e}"),
-                })),
-            }
-        }
-    }
+    let items = cache
+        .cached(&cache_key, ttls.portfolio_ttl, || async {
+            portfolio_db::get_portfolios_by_freelancer(db.get_ref(), freelancer_id)
+                .await
+                .map_err(ApiError::from)
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(items))
 }
 
 /// POST /api/portfolios — create a new portfolio item (requires authentication).
+#[utoipa::path(
+    post,
+    path = "/api/portfolios",
+    tag = "portfolios",
+    request_body = CreatePortfolio,
+    responses(
+        (status = 201, description = "The new portfolio item", body = crate::models::portfolio::Model),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn create_portfolio(
     auth_user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
     body: web::Json<CreatePortfolio>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let input = body.into_inner();
 
-    if auth_user.0.id != input.freelancer_id {
-        return HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You can only create portfolio items for your own account",
-        }));
-    }
+    let resource = Resource::Portfolio {
+        freelancer_id: input.freelancer_id,
+    };
+    Policy::can(&Actor::from(&auth_user.0), Action::Create, &resource)?;
 
-    match portfolio_db::insert_portfolio(db.get_ref(), input).await {
-        Ok(item) => {
-            let _ = cache
-                .delete(&keys::portfolio(&auth_user.0.id.to_string()))
-                .await;
-            HttpResponse::Created().json(item)
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to create portfolio item: {e}"),
-        })),
-    }
+    let item = portfolio_db::insert_portfolio(db.get_ref(), input).await?;
+    let _ = cache
+        .delete(&keys::portfolio(&auth_user.0.id.to_string()))
+        .await;
+    let event = AuditEvent::new(
+        auth_user.0.id,
+        "portfolio.create",
+        Target::Portfolio(item.id),
+    )
+    .after(&item);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Created().json(item))
 }
 
 /// PUT /api/portfolios/{id} — update a portfolio item (requires authentication).
+#[utoipa::path(
+    put,
+    path = "/api/portfolios/{id}",
+    tag = "portfolios",
+    params(("id" = Uuid, Path, description = "Portfolio item id")),
+    request_body = UpdatePortfolio,
+    responses(
+        (status = 200, description = "The updated portfolio item", body = crate::models::portfolio::Model),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such portfolio item", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn update_portfolio(
     auth_user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
     path: web::Path<Uuid>,
     body: web::Json<UpdatePortfolio>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let id = path.into_inner();
 
     // Verify the portfolio item belongs to the authenticated user.
-    match portfolio_db::get_portfolio_by_id(db.get_ref(), id).await {
-        Ok(Some(item)) if item.freelancer_id != auth_user.0.id => {
-            return HttpResponse::Forbidden().json(serde_json::json!({
-                "error": "You can only update your own portfolio items",
-            }));
-        }
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Portfolio item {id} not found"),
-            }));
-        }
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-        _ => {}
-    }
+    let actor = Actor::from(&auth_user.0);
+    let before = authorize_portfolio(db.get_ref(), id, &actor, Action::Update).await?;
 
-    match portfolio_db::update_portfolio(db.get_ref(), id, body.into_inner()).await {
-        Ok(updated) => {
-            let _ = cache
-                .delete(&keys::portfolio(&auth_user.0.id.to_string()))
-                .await;
-            HttpResponse::Ok().json(updated)
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to update portfolio item: {e}"),
-        })),
-    }
+    let updated = portfolio_db::update_portfolio(db.get_ref(), id, body.into_inner()).await?;
+    let _ = cache
+        .delete(&keys::portfolio(&auth_user.0.id.to_string()))
+        .await;
+    let event = AuditEvent::new(auth_user.0.id, "portfolio.update", Target::Portfolio(id))
+        .before(&before)
+        .after(&updated);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(updated))
 }
 
 /// DELETE /api/portfolios/{id} — delete a portfolio item (requires authentication).
+#[utoipa::path(
+    delete,
+    path = "/api/portfolios/{id}",
+    tag = "portfolios",
+    params(("id" = Uuid, Path, description = "Portfolio item id")),
+    responses(
+        (status = 200, description = "Portfolio item deleted", body = Confirmation),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such portfolio item", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn delete_portfolio(
     auth_user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    audit: Audit,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let id = path.into_inner();
 
     // Verify the portfolio item belongs to the authenticated user.
-    match portfolio_db::get_portfolio_by_id(db.get_ref(), id).await {
-        Ok(Some(item)) if item.freelancer_id != auth_user.0.id => {
-            return HttpResponse::Forbidden().json(serde_json::json!({
-                "error": "You can only delete your own portfolio items",
-            }));
-        }
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Portfolio item {id} not found"),
-            }));
-        }
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Database error: {e}"),
-            }));
-        }
-        _ => {}
-    }
+    let actor = Actor::from(&auth_user.0);
+    let item = authorize_portfolio(db.get_ref(), id, &actor, Action::Delete).await?;
 
-    match portfolio_db::delete_portfolio(db.get_ref(), id).await {
-        Ok(result) => {
-            if result.rows_affected > 0 {
-                let _ = cache
-                    .delete(&keys::portfolio(&auth_user.0.id.to_string()))
-                    .await;
-                HttpResponse::Ok().json(serde_json::json!({
-                    "message": format!("Portfolio item {id} deleted"),
-                }))
-            } else {
-                HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("Portfolio item {id} not found"),
-                }))
-            }
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to delete portfolio item: {e}"),
-        })),
+    let result = portfolio_db::delete_portfolio(db.get_ref(), id).await?;
+    if result.rows_affected == 0 {
+        return Err(ApiError::not_found(format!(
+            "Portfolio item {id} not found"
+        )));
     }
+
+    let _ = cache
+        .delete(&keys::portfolio(&auth_user.0.id.to_string()))
+        .await;
+    let event =
+        AuditEvent::new(auth_user.0.id, "portfolio.delete", Target::Portfolio(id)).before(&item);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(Confirmation::new(format!("Portfolio item {id} deleted"))))
 }
@@ -1,147 +1,176 @@
-use actix_web::{HttpResponse, Responder, web};
+use actix_web::{HttpResponse, web};
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 use uuid::Uuid;
-use tracing;
 
+use crate::audit::{Audit, AuditEvent, Target};
 use crate::auth::middleware::AuthenticatedUser;
-use crate::cache::{RedisCache, keys};
+use crate::auth::policy::{Action, Actor, Policy, Resource};
+use crate::auth::user_cache::UserCacheData;
+use crate::cache::{CacheConfig, CacheStore, keys, tags};
 use crate::db::users as user_db;
+use crate::error::{ApiError, ErrorBody};
 use crate::models::users::{UpdateUser, UserResponse};
-use crate::models::PaginationQuery;
+use crate::models::{Confirmation, PaginationQuery};
 
 /// GET /api/users — list all users with pagination (requires authentication).
 /// Query params: ?page=1&limit=20
+#[utoipa::path(
+    get,
+    path = "/api/users",
+    tag = "users",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "A page of users", body = Vec<UserResponse>),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn get_users(
     _user: AuthenticatedUser, // ensures caller is authenticated
     db: web::Data<DatabaseConnection>,
     query: web::Query<PaginationQuery>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let page = query.page();
     let limit = query.limit();
 
-    match user_db::get_users_paginated(db.get_ref(), page, limit).await {
-        Ok(users) => {
-            let response: Vec<UserResponse> = users.into_iter().map(UserResponse::from).collect();
-            HttpResponse::Ok().json(response)
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to fetch users: {e}"),
-        })),
-    }
+    let users = user_db::get_users_paginated(db.get_ref(), page, limit).await?;
+    let response: Vec<UserResponse> = users.into_iter().map(UserResponse::from).collect();
+    Ok(HttpResponse::Ok().json(response))
 }
 
 /// GET /api/users/{id} — get a single user (requires authentication).
+#[utoipa::path(
+    get,
+    path = "/api/users/{id}",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "The user", body = UserResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn get_user(
     _user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    ttls: web::Data<CacheConfig>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let id = path.into_inner();
     let cache_key = keys::user(&id.to_string());
 
-    // Try to get from cache first
-    match cache.get::<serde_json::Value>(&cache_key).await {
-        Ok(Some(cached)) => {
-            HttpResponse::Ok().json(cached)
-        }
-        Ok(None) => {
-            // Cache miss - fetch from database
-            match user_db::get_user_by_id(db.get_ref(), id).await {
-                Ok(Some(user)) => {
-                    let response = UserResponse::from(user);
-                    // Store in cache (15 minute TTL)
-                    let _ = cache.set(&cache_key, &response, Some(900)).await;
-                    HttpResponse::Ok().json(response)
-                }
-                Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("User {id} not found"),
-                })),
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                })),
-            }
-        }
-        Err(e) => {
-            // Cache error - fallback to database
-            tracing::warn!("Cache error: {}", e);
-            match user_db::get_user_by_id(db.get_ref(), id).await {
-                Ok(Some(user)) => HttpResponse::Ok().json(UserResponse::from(user)),
-                Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("User {id} not found"),
-                })),
-                Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Database error: {e}"),
-                })),
-            }
-        }
-    }
+    let response: UserResponse = cache
+        .cached(&cache_key, ttls.user_ttl, || async {
+            user_db::get_user_by_id(db.get_ref(), id)
+                .await?
+                .map(UserResponse::from)
+                .ok_or_else(|| ApiError::not_found(format!("User {id} not found")))
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(response))
 }
 
 /// PUT /api/users/{id} — update a user (requires authentication).
+#[utoipa::path(
+    put,
+    path = "/api/users/{id}",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User id")),
+    request_body = UpdateUser,
+    responses(
+        (status = 200, description = "The updated user", body = UserResponse),
+        (status = 400, description = "Invalid input", body = ErrorBody),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn update_user(
     auth_user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    user_cache: web::Data<UserCacheData>,
+    audit: Audit,
     path: web::Path<Uuid>,
     body: web::Json<UpdateUser>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let id = path.into_inner();
 
-    // Only allow users to update themselves (or add admin check here).
-    if auth_user.0.id != id {
-        return HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You can only update your own account",
-        }));
-    }
+    // Only allow users to update themselves; admins use /api/admin/users/{id}.
+    let actor = Actor::from(&auth_user.0);
+    Policy::can(&actor, Action::Update, &Resource::User { id })?;
 
-    match user_db::update_user(db.get_ref(), id, body.into_inner()).await {
-        Ok(updated) => {
-            // Invalidate user cache and related caches
-            let _ = cache.delete(&keys::user(&id.to_string())).await;
-            HttpResponse::Ok().json(UserResponse::from(updated))
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to update user: {e}"),
-        })),
+    // Only admins may grant the admin role.
+    if let Some(role) = &body.role {
+        Policy::can(&actor, Action::Update, &Resource::Role(role.clone()))?;
     }
+
+    let before = user_db::get_user_by_id(db.get_ref(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("User {id} not found")))?;
+    let updated = user_db::update_user(db.get_ref(), id, body.into_inner()).await?;
+
+    // Invalidate user cache and related caches
+    let _ = cache.delete(&keys::user(&id.to_string())).await;
+    user_cache.invalidate(id).await;
+
+    let response = UserResponse::from(updated);
+    let event = AuditEvent::new(auth_user.0.id, "user.update", Target::User(id))
+        .before(&UserResponse::from(before))
+        .after(&response);
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(response))
 }
 
 /// DELETE /api/users/{id} — delete a user (requires authentication).
+#[utoipa::path(
+    delete,
+    path = "/api/users/{id}",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "User deleted", body = Confirmation),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBody),
+        (status = 403, description = "Not allowed", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn delete_user(
     auth_user: AuthenticatedUser,
     db: web::Data<DatabaseConnection>,
-    cache: web::Data<Arc<RedisCache>>,
+    cache: web::Data<Arc<CacheStore>>,
+    user_cache: web::Data<UserCacheData>,
+    audit: Audit,
     path: web::Path<Uuid>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let id = path.into_inner();
 
-    // Only allow users to delete themselves (or add admin check here).
-    if auth_user.0.id != id {
-        return HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You can only delete your own account",
-        }));
-    }
+    // Only allow users to delete themselves; admins use /api/admin/users/{id}.
+    let actor = Actor::from(&auth_user.0);
+    Policy::can(&actor, Action::Delete, &Resource::User { id })?;
 
-    match user_db::delete_user(db.get_ref(), id).await {
-        Ok(result) => {
-            if result.rows_affected > 0 {
-                // Invalidate user cache and user's related caches
-                let _ = cache.delete(&keys::user(&id.to_string())).await;
-                let _ = cache.delete(&keys::user_gigs(&id.to_string())).await;
-                let _ = cache.delete(&keys::portfolio(&id.to_string())).await;
-                HttpResponse::Ok().json(serde_json::json!({
-                    "message": format!("User {id} deleted"),
-                }))
-            } else {
-                HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("User {id} not found"),
-                }))
-            }
-        }
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to delete user: {e}"),
-        })),
+    let user = user_db::get_user_by_id(db.get_ref(), id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("User {id} not found")))?;
+    let result = user_db::delete_user(db.get_ref(), id).await?;
+    if result.rows_affected == 0 {
+        return Err(ApiError::not_found(format!("User {id} not found")));
     }
+
+    // Invalidate user cache and user's related caches
+    let _ = cache.delete(&keys::user(&id.to_string())).await;
+    let _ = cache.delete(&keys::portfolio(&id.to_string())).await;
+    let _ = cache.invalidate_tags(&tags::for_user_gigs(id)).await;
+    user_cache.invalidate(id).await;
+
+    let event = AuditEvent::new(auth_user.0.id, "user.delete", Target::User(id))
+        .before(&UserResponse::from(user));
+    audit.record(db.get_ref(), event).await;
+    Ok(HttpResponse::Ok().json(Confirmation::new(format!("User {id} deleted"))))
 }
@@ -1,8 +1,16 @@
+pub mod audit;
 pub mod auth;
 pub mod cache;
 pub mod chat;
+pub mod config;
 pub mod db;
+pub mod error;
 pub mod handlers;
+pub mod metrics;
+pub mod middleware;
 pub mod models;
+pub mod openapi;
+pub mod rate_limit;
+pub mod telemetry;
 
 pub use db::create_pool;
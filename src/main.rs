@@ -1,71 +1,214 @@
-use actix_cors::Cors;
+use actix_web::middleware::from_fn;
 use actix_web::{App, HttpServer, web};
 use dotenv::dotenv;
-use gradwork_backend::auth::jwks::JwksCache;
-use gradwork_backend::cache::RedisCache;
+use gradwork_backend::auth::user_cache::UserCache;
+use gradwork_backend::auth::verifier;
+use gradwork_backend::cache::local::LocalCache;
+use gradwork_backend::cache::memory::MemoryBackend;
+use gradwork_backend::cache::redis_backend::RedisBackend;
+use gradwork_backend::cache::{CacheBackend, CacheStore};
 use gradwork_backend::chat::server::ChatServer;
+use gradwork_backend::config::{AppConfig, CacheBackendKind};
 use gradwork_backend::create_pool;
+use gradwork_backend::error;
 use gradwork_backend::handlers;
+use gradwork_backend::middleware::cors::cors;
+use gradwork_backend::middleware::metrics::track_requests;
+use gradwork_backend::middleware::rate_limit::rate_limit;
+use gradwork_backend::middleware::request_id::request_id;
+use gradwork_backend::rate_limit::RateLimiter;
+use gradwork_backend::telemetry;
 use std::sync::Arc;
-use tracing_subscriber::EnvFilter;
+use std::time::Duration;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
 
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env().add_directive(tracing::Level::INFO.into()))
-        .init();
+    // `gradwork-backend healthcheck` probes a running server, for container
+    // healthchecks in images without curl.
+    if std::env::args().nth(1).as_deref() == Some("healthcheck") {
+        std::process::exit(healthcheck().await);
+    }
 
-    let db = create_pool().await;
+    // Logging is configured by AppConfig, so a bad config can only go to stderr.
+    let config = AppConfig::load().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let _telemetry = telemetry::init(&config).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+
+    let db = create_pool(&config.database).await;
     let db_data = web::Data::new(db);
 
-    // Initialize Redis cache
-    let redis_url = std::env::var("REDIS_URL").expect("REDIS_URL must be set");
-    let redis_cache = RedisCache::new(&redis_url)
-        .await
-        .expect("Failed to connect to Redis");
-    let redis_data = web::Data::new(Arc::new(redis_cache));
-    tracing::info!("Connected to Redis");
+    // Response cache backend. With REDIS_REQUIRED=false a Redis outage at startup
+    // is logged and the app runs uncached until the background reconnect succeeds.
+    let backend: Arc<dyn CacheBackend> = match config.cache.backend {
+        CacheBackendKind::Memory => Arc::new(MemoryBackend::new()),
+        CacheBackendKind::Redis => {
+            let redis = Arc::new(
+                RedisBackend::new(&config.redis.url).expect("redis.url is checked by validate"),
+            );
+            match redis.connect().await {
+                Ok(()) => tracing::info!("Connected to Redis"),
+                Err(e) if config.redis.required => {
+                    tracing::error!("Failed to connect to Redis: {e}");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    tracing::warn!("Redis unavailable, starting without cache: {e}");
+                    redis.spawn_reconnect(Duration::from_secs(config.redis.reconnect_secs));
+                }
+            }
+            redis
+        }
+    };
+    let redis_cache = CacheStore::new(backend);
+    // Optional in-process L1 in front of the backend, kept coherent through pub/sub.
+    let redis_cache = if config.features.cache_local {
+        let local = LocalCache::new(
+            Duration::from_secs(config.cache.local_ttl_secs),
+            config.cache.local_capacity,
+        );
+        let redis_cache = redis_cache.with_local(local);
+        redis_cache.spawn_invalidation_listener();
+        redis_cache
+    } else {
+        redis_cache
+    };
+    let redis_cache = Arc::new(redis_cache);
+    let redis_data = web::Data::new(redis_cache.clone());
+    tracing::info!("Cache backend: {}", redis_cache.backend_name());
+
+    // Per-client request budgets, kept in the cache so every instance shares them.
+    let limiter = RateLimiter::new(config.rate_limit.clone(), redis_cache.clone());
+    let limiter_data = web::Data::new(Arc::new(limiter));
 
-    let supabase_url = std::env::var("SUPABASE_URL").expect("SUPABASE_URL must be set");
-    let project_ref = supabase_url
-        .strip_prefix("https://")
-        .and_then(|s| s.strip_suffix(".supabase.co"))
-        .expect("Invalid SUPABASE_URL format. Expected: https://PROJECT.supabase.co");
+    // Cache of authenticated users so protected requests skip the `users` lookup.
+    let user_cache_data = web::Data::new(Arc::new(UserCache::from_config(
+        &config,
+        redis_cache.clone(),
+    )));
 
-    let supabase_anon_key =
-        std::env::var("SUPABASE_ANON_KEY").expect("SUPABASE_ANON_KEY must be set");
-    let jwks_cache = web::Data::new(Arc::new(JwksCache::new(project_ref, &supabase_anon_key)));
+    // Token verifier selected by AUTH_VERIFIER (JWKS, HS256 secret or static JWKS file).
+    let token_verifier =
+        verifier::from_config(&config.auth).expect("Failed to configure token verifier");
+    let verifier_data = web::Data::new(token_verifier);
 
     // Create the shared chat server (room manager for WebSocket connections).
-    let chat_server = web::Data::new(Arc::new(ChatServer::new()));
-
-    let port = std::env::var("PORT").unwrap_or_else(|_| "8080".to_string());
-    let bind_addr = format!("0.0.0.0:{port}");
-    tracing::info!("Server running at http://{bind_addr}");
-
-    HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
-            .allowed_headers(vec![
-                actix_web::http::header::AUTHORIZATION,
-                actix_web::http::header::CONTENT_TYPE,
-                actix_web::http::header::ACCEPT,
-            ])
-            .max_age(3600);
+    // Suspensions and revoked sessions close sockets on every instance through pub/sub.
+    let chat_server = Arc::new(ChatServer::new().with_pubsub(redis_cache));
+    chat_server.spawn_disconnect_listener();
+    let chat_server = web::Data::new(chat_server);
+    let ws_auth_data = web::Data::new(config.ws_auth());
+    let cache_config_data = web::Data::new(config.cache_config());
+
+    let bind_addr = (config.server.host.clone(), config.server.port);
+    tracing::info!("Server running at http://{}:{}", bind_addr.0, bind_addr.1);
 
+    let workers = config.server.workers;
+    let shutdown_timeout = config.server.shutdown_timeout_secs;
+    let reconnect_after = Duration::from_millis(config.ws.reconnect_after_ms);
+    let drain_timeout = Duration::from_secs(config.ws.drain_timeout_secs);
+    let draining_chat = chat_server.get_ref().clone();
+    let config_data = web::Data::new(config);
+
+    let server = HttpServer::new(move || {
         App::new()
-            .wrap(cors)
+            .wrap(cors(&config_data.cors))
+            .wrap(from_fn(request_id))
+            .wrap(from_fn(track_requests))
+            .app_data(error::json_config())
+            .app_data(error::query_config())
+            .app_data(error::path_config())
+            .app_data(config_data.clone())
             .app_data(db_data.clone())
             .app_data(redis_data.clone())
-            .app_data(jwks_cache.clone())
+            .app_data(cache_config_data.clone())
+            .app_data(verifier_data.clone())
+            .app_data(user_cache_data.clone())
             .app_data(chat_server.clone())
-            .service(web::scope("/api").configure(handlers::init_routes))
+            .app_data(ws_auth_data.clone())
+            .app_data(limiter_data.clone())
+            .configure(handlers::init_health_routes)
+            .configure(handlers::init_metrics_routes)
+            .configure(handlers::init_docs_routes)
+            .service(
+                web::scope("/api")
+                    .wrap(from_fn(rate_limit))
+                    .configure(handlers::init_routes),
+            )
     })
-    .workers(12)
-    .bind(&bind_addr)?
-    .run()
-    .await
+    .workers(workers)
+    .shutdown_timeout(shutdown_timeout)
+    .disable_signals()
+    .bind(bind_addr)?
+    .run();
+
+    // Actix would stop the workers (and with them every chat session) on the
+    // signal itself, so drain chat first and only then stop the server.
+    let handle = server.handle();
+    actix_web::rt::spawn(async move {
+        shutdown_signal().await;
+        tracing::info!("Shutdown requested, draining chat sessions");
+        let report = draining_chat.shutdown(reconnect_after, drain_timeout).await;
+        tracing::info!(
+            notified = report.notified,
+            abandoned_writes = report.abandoned_writes,
+            open_sessions = report.open_sessions,
+            "Chat sessions drained"
+        );
+        handle.stop(true).await;
+    });
+
+    server.await
+}
+
+/// Resolve on Ctrl-C, or SIGTERM on Unix.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Exit code 0 if the server on the configured port reports ready, 1 otherwise.
+///
+/// Loads [`AppConfig`] like the server does, so a port set in the config file
+/// is probed too.
+async fn healthcheck() -> i32 {
+    let config = match AppConfig::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            return 1;
+        }
+    };
+    let url = format!("http://127.0.0.1:{}/health/ready", config.server.port);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .expect("Failed to build HTTP client");
+
+    match client.get(&url).send().await {
+        Ok(res) if res.status().is_success() => 0,
+        Ok(res) => {
+            eprintln!("{url} returned {}", res.status());
+            1
+        }
+        Err(e) => {
+            eprintln!("{url} unreachable: {e}");
+            1
+        }
+    }
 }
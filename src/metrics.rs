@@ -0,0 +1,161 @@
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use sea_orm::metric::Info;
+use std::sync::LazyLock;
+
+/// Process-wide Prometheus metrics, exported at `GET /metrics`.
+pub struct Metrics {
+    registry: Registry,
+    /// Requests by `method`, matched `route` pattern and `status`.
+    pub http_requests: IntCounterVec,
+    pub http_duration: HistogramVec,
+    /// SeaORM statements by `operation` (`select`, `insert`, ...) and `outcome`.
+    pub db_queries: HistogramVec,
+    /// Cache-aside reads by `result`: `local_hit`, `backend_hit`, `miss` or `error`.
+    pub cache_reads: IntCounterVec,
+    /// JWKS fetches by `result`: `ok` or `error`.
+    pub jwks_fetches: IntCounterVec,
+    pub jwks_keys: IntGauge,
+    pub chat_rooms: IntGauge,
+    pub chat_connections: IntGauge,
+    pub chat_messages_broadcast: IntCounter,
+    /// Messages that could not be queued because the receiving session was gone.
+    pub chat_send_failures: IntCounter,
+    /// Requests and chat frames rejected by the rate limiter, by `group`.
+    pub rate_limited: IntCounterVec,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// The global metrics registry.
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("gradwork".to_string()), None)
+            .expect("Invalid metrics prefix");
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let http_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency"),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let db_queries = HistogramVec::new(
+            HistogramOpts::new("db_query_duration_seconds", "Database statement latency").buckets(
+                vec![
+                    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+                ],
+            ),
+            &["operation", "outcome"],
+        )
+        .unwrap();
+        let cache_reads = IntCounterVec::new(
+            Opts::new("cache_reads_total", "Cache-aside reads by result"),
+            &["result"],
+        )
+        .unwrap();
+        let jwks_fetches = IntCounterVec::new(
+            Opts::new("jwks_fetches_total", "JWKS fetches by result"),
+            &["result"],
+        )
+        .unwrap();
+        let jwks_keys = IntGauge::new("jwks_keys", "Signing keys currently trusted").unwrap();
+        let chat_rooms =
+            IntGauge::new("chat_rooms_open", "Contract chats with a connected client").unwrap();
+        let chat_connections =
+            IntGauge::new("chat_connections", "Connected chat WebSockets").unwrap();
+        let chat_messages_broadcast = IntCounter::new(
+            "chat_messages_broadcast_total",
+            "Messages broadcast to contract chats",
+        )
+        .unwrap();
+        let chat_send_failures = IntCounter::new(
+            "chat_send_failures_total",
+            "Chat messages dropped because the session had disconnected",
+        )
+        .unwrap();
+        let rate_limited = IntCounterVec::new(
+            Opts::new(
+                "rate_limited_total",
+                "Requests and chat frames rejected by rate limits",
+            ),
+            &["group"],
+        )
+        .unwrap();
+
+        let collectors: [Box<dyn prometheus::core::Collector>; 11] = [
+            Box::new(http_requests.clone()),
+            Box::new(http_duration.clone()),
+            Box::new(db_queries.clone()),
+            Box::new(cache_reads.clone()),
+            Box::new(jwks_fetches.clone()),
+            Box::new(jwks_keys.clone()),
+            Box::new(chat_rooms.clone()),
+            Box::new(chat_connections.clone()),
+            Box::new(chat_messages_broadcast.clone()),
+            Box::new(chat_send_failures.clone()),
+            Box::new(rate_limited.clone()),
+        ];
+        for collector in collectors {
+            registry
+                .register(collector)
+                .expect("Duplicate metric registration");
+        }
+
+        Self {
+            registry,
+            http_requests,
+            http_duration,
+            db_queries,
+            cache_reads,
+            jwks_fetches,
+            jwks_keys,
+            chat_rooms,
+            chat_connections,
+            chat_messages_broadcast,
+            chat_send_failures,
+            rate_limited,
+        }
+    }
+
+    /// Record one cache-aside read outcome.
+    pub fn cache_read(&self, result: &str) {
+        self.cache_reads.with_label_values(&[result]).inc();
+    }
+
+    /// SeaORM metric callback; install with `DatabaseConnection::set_metric_callback`.
+    pub fn observe_query(&self, info: &Info<'_>) {
+        let outcome = if info.failed { "error" } else { "ok" };
+        self.db_queries
+            .with_label_values(&[statement_operation(&info.statement.sql), outcome])
+            .observe(info.elapsed.as_secs_f64());
+    }
+
+    /// Everything registered, in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .expect("Failed to encode metrics");
+        String::from_utf8(buf).expect("Metrics are not UTF-8")
+    }
+}
+
+/// Lower-cased leading keyword of `sql`, limited to known verbs to keep label
+/// cardinality bounded.
+pub fn statement_operation(sql: &str) -> &'static str {
+    let verb = sql.split_whitespace().next().unwrap_or_default();
+    ["select", "insert", "update", "delete", "with"]
+        .into_iter()
+        .find(|known| verb.eq_ignore_ascii_case(known))
+        .unwrap_or("other")
+}
@@ -0,0 +1,34 @@
+use actix_cors::Cors;
+use actix_web::http::header::{self, HeaderName};
+
+use crate::config::CorsConfig;
+use crate::middleware::request_id::REQUEST_ID_HEADER;
+
+/// W3C trace-context headers, honored on inbound requests.
+const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+const TRACESTATE: HeaderName = HeaderName::from_static("tracestate");
+
+/// Browser access to the API. Besides credentials and content headers, callers
+/// may send a request id and trace context, and may read back the request id
+/// and `Retry-After`.
+pub fn cors(config: &CorsConfig) -> Cors {
+    let mut cors = Cors::default();
+    if config.allows_any_origin() {
+        cors = cors.allow_any_origin();
+    } else {
+        for origin in &config.allowed_origins {
+            cors = cors.allowed_origin(origin);
+        }
+    }
+    cors.allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
+        .allowed_headers(vec![
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::ACCEPT,
+            REQUEST_ID_HEADER,
+            TRACEPARENT,
+            TRACESTATE,
+        ])
+        .expose_headers(vec![REQUEST_ID_HEADER, header::RETRY_AFTER])
+        .max_age(config.max_age_secs)
+}
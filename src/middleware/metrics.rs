@@ -0,0 +1,35 @@
+use actix_web::Error;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use std::time::Instant;
+
+use crate::metrics::metrics;
+
+/// Count requests and record their latency by method, route pattern and status.
+///
+/// Requests that match no route are recorded as `unmatched` so arbitrary paths
+/// cannot blow up label cardinality. Register with
+/// `actix_web::middleware::from_fn(track_requests)`.
+pub async fn track_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let started = Instant::now();
+    let method = req.method().to_string();
+
+    let res = next.call(req).await?;
+    let route = res
+        .request()
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_string());
+    let status = res.status().as_u16().to_string();
+
+    let labels = [method.as_str(), route.as_str(), status.as_str()];
+    let m = metrics();
+    m.http_requests.with_label_values(&labels).inc();
+    m.http_duration
+        .with_label_values(&labels)
+        .observe(started.elapsed().as_secs_f64());
+    Ok(res)
+}
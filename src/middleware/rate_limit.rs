@@ -0,0 +1,65 @@
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{Error, web};
+
+use crate::auth::api_keys::{KEY_PREFIX, hash_key};
+use crate::auth::middleware::{bearer_token, validate_once};
+use crate::auth::principal::api_key_header;
+use crate::error::ApiError;
+use crate::rate_limit::{ClientKey, RateLimiterData, RouteGroup, client_ip};
+
+/// Enforce the [`RateLimiter`](crate::rate_limit::RateLimiter) budget of the
+/// request's route group, answering `429` with `Retry-After` once it is spent.
+///
+/// Requests are counted per user when they carry a valid bearer token, per key
+/// when they carry an API key and per client IP otherwise, so invalid bearer
+/// tokens cannot dodge the limit. A no-op when no limiter is registered as app data. Register with
+/// `actix_web::middleware::from_fn(rate_limit)`.
+pub async fn rate_limit(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let Some(limiter) = req.app_data::<web::Data<RateLimiterData>>().cloned() else {
+        return Ok(next.call(req).await?.map_into_boxed_body());
+    };
+
+    let pattern = req
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_string());
+    let group = RouteGroup::classify(req.method(), &pattern);
+    let client = client_key(&req, limiter.config().trust_proxy).await;
+
+    match limiter.check_route(group, &client).await {
+        Ok(()) => Ok(next.call(req).await?.map_into_boxed_body()),
+        Err(retry_after) => {
+            tracing::debug!(group = group.as_str(), %client, "rate limited");
+            Ok(req.error_response(ApiError::RateLimited { retry_after }))
+        }
+    }
+}
+
+/// The user behind a valid bearer token, the API key in use, else the client IP.
+///
+/// API keys are bucketed by their hash without a lookup; [`Principal`]
+/// rejects unknown keys once the request is admitted.
+///
+/// [`Principal`]: crate::auth::principal::Principal
+async fn client_key(req: &ServiceRequest, trust_proxy: bool) -> ClientKey {
+    if let Ok(token) = bearer_token(req.request())
+        && let Ok(claims) = validate_once(req.request(), token).await
+        && let Ok(user_id) = claims.user_id()
+    {
+        return ClientKey::User(user_id);
+    }
+    if let Some(key) = api_key_header(req.request())
+        && key.starts_with(KEY_PREFIX)
+    {
+        return ClientKey::ApiKey(hash_key(key));
+    }
+
+    let ip = client_ip(&req.connection_info(), trust_proxy)
+        .unwrap_or("unknown")
+        .to_string();
+    ClientKey::Ip(ip)
+}
@@ -0,0 +1,105 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage};
+use std::time::Instant;
+use tracing::Instrument;
+use tracing::field::{Empty, display};
+use uuid::Uuid;
+
+use crate::telemetry;
+
+/// Header carrying the request id, both inbound (from a proxy) and outbound.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest inbound request id that is reused as-is.
+const MAX_INBOUND_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The id of the request being handled, stored in the request extensions.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Request id of the current request, if called while one is being handled.
+///
+/// Lets error responses include the id without access to the `HttpRequest`.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Reuse a well-formed inbound `X-Request-Id` (so ids match the proxy's logs).
+fn inbound_id(req: &ServiceRequest) -> Option<String> {
+    let id = req.headers().get(&REQUEST_ID_HEADER)?.to_str().ok()?;
+    let well_formed = !id.is_empty()
+        && id.len() <= MAX_INBOUND_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'));
+    well_formed.then(|| id.to_string())
+}
+
+/// Attach the authenticated user to the current request's span.
+///
+/// Called by the auth extractors; a no-op outside a request.
+pub fn record_user(user_id: Uuid) {
+    tracing::Span::current().record("user_id", display(user_id));
+}
+
+/// Assign every request an id, expose it to handlers and echo it in `X-Request-Id`.
+///
+/// The request runs inside a `request` span carrying the id, method and route
+/// pattern; the status (and `user_id`, via [`record_user`]) are filled in as
+/// they become known, and a `request completed` event is logged at the end.
+///
+/// Register with `actix_web::middleware::from_fn(request_id)`.
+pub async fn request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let id = inbound_id(&req).unwrap_or_else(|| Uuid::new_v4().to_string());
+    req.extensions_mut().insert(RequestId(id.clone()));
+
+    let route = req
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_string());
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        route = %route,
+        user_id = Empty,
+        status = Empty,
+    );
+    // Join the caller's trace when it sent W3C trace-context headers.
+    telemetry::set_remote_parent(&span, req.headers());
+    let started = Instant::now();
+
+    let result = REQUEST_ID
+        .scope(id.clone(), next.call(req))
+        .instrument(span.clone())
+        .await;
+
+    let status = match &result {
+        Ok(res) => res.status(),
+        Err(e) => e.as_response_error().status_code(),
+    };
+    span.record("status", status.as_u16());
+    span.in_scope(|| {
+        let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+        if status.is_server_error() {
+            tracing::error!(latency_ms, "request completed");
+        } else {
+            tracing::info!(latency_ms, "request completed");
+        }
+    });
+
+    let mut res = result?;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    Ok(res)
+}
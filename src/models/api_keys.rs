@@ -0,0 +1,140 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What an API key is allowed to do. JWT sessions implicitly hold every scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum ApiScope {
+    /// Read gigs.
+    #[serde(rename = "gigs:read")]
+    GigsRead,
+    /// Create, update and delete the key owner's gigs.
+    #[serde(rename = "gigs:write")]
+    GigsWrite,
+    /// Read contracts the key owner is a party to.
+    #[serde(rename = "contracts:read")]
+    ContractsRead,
+}
+
+impl ApiScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GigsRead => "gigs:read",
+            Self::GigsWrite => "gigs:write",
+            Self::ContractsRead => "contracts:read",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "gigs:read" => Some(Self::GigsRead),
+            "gigs:write" => Some(Self::GigsWrite),
+            "contracts:read" => Some(Self::ContractsRead),
+            _ => None,
+        }
+    }
+}
+
+/// SeaORM entity for the `api_keys` table.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// First characters of the key, shown so users can tell keys apart.
+    pub prefix: String,
+    /// Hex-encoded SHA-256 of the full key. The key itself is never stored.
+    #[sea_orm(unique)]
+    pub key_hash: String,
+    /// Space-separated [`ApiScope`] values.
+    #[sea_orm(column_type = "Text")]
+    pub scopes: String,
+    pub expires_at: Option<DateTimeUtc>,
+    pub last_used_at: Option<DateTimeUtc>,
+    pub revoked_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+impl Model {
+    /// The scopes granted to this key (unknown values are ignored).
+    pub fn scope_list(&self) -> Vec<ApiScope> {
+        self.scopes
+            .split_whitespace()
+            .filter_map(ApiScope::parse)
+            .collect()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// ── DTOs ──
+
+/// Used by the `POST /api/auth/api-keys` endpoint.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateApiKey {
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    /// Omit for a key that never expires.
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A safe API key representation for API responses (never includes the hash).
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<ApiScope>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<Model> for ApiKeyResponse {
+    fn from(m: Model) -> Self {
+        Self {
+            scopes: m.scope_list(),
+            id: m.id,
+            user_id: m.user_id,
+            name: m.name,
+            prefix: m.prefix,
+            expires_at: m.expires_at,
+            last_used_at: m.last_used_at,
+            revoked_at: m.revoked_at,
+            created_at: m.created_at,
+        }
+    }
+}
+
+/// Returned once when a key is created; `key` cannot be retrieved again.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CreatedApiKey {
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+}
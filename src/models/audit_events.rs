@@ -0,0 +1,72 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// SeaORM entity for the append-only `audit_events` table.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "audit_events")]
+#[schema(as = AuditEvent)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    /// The user who acted. Kept after the user is deleted.
+    pub actor_id: Option<Uuid>,
+    /// What happened, e.g. `gig.delete` or `contract.status_change`.
+    pub action: String,
+    /// Kind of row acted on, e.g. `gig` (see `audit::Target`).
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    /// Snapshot of the target before the action, if it existed.
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub before: Option<Json>,
+    /// Snapshot of the target after the action, if it still exists.
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub after: Option<Json>,
+    pub ip: Option<String>,
+    pub request_id: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// ── DTOs ──
+
+/// Query params for `GET /api/admin/audit-events`. Every filter is optional;
+/// results are newest first and paginated by `(created_at, id)` cursor.
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    pub actor_id: Option<Uuid>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub action: Option<String>,
+    /// Only events at or after this time.
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only events before this time.
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: Option<u64>,
+    pub cursor_created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub cursor_id: Option<Uuid>,
+}
+
+impl AuditQuery {
+    pub fn limit(&self) -> u64 {
+        self.limit.unwrap_or(20).min(100)
+    }
+}
+
+/// An event to append, built by `audit::Audit::record`.
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub before: Option<Json>,
+    pub after: Option<Json>,
+    pub ip: Option<String>,
+    pub request_id: Option<String>,
+}
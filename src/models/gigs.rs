@@ -1,9 +1,11 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 /// SeaORM entity for the `gigs` table.
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "gigs")]
+#[schema(as = Gig)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
@@ -15,10 +17,12 @@ pub struct Model {
     pub thumbnail_url: Option<String>,
     pub category: Categories,
     pub user_id: Uuid,
-    pub created_at: DateTimeUtc,
+    pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, EnumIter, DeriveActiveEnum)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, EnumIter, DeriveActiveEnum, ToSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
 pub enum Categories {
     #[sea_orm(string_value = "web_development")]
@@ -64,7 +68,7 @@ impl ActiveModelBehavior for ActiveModel {}
 
 // ── DTOs ──
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct CreateGig {
     pub title: String,
     pub description: String,
@@ -73,7 +77,7 @@ pub struct CreateGig {
     pub category: Option<Categories>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct UpdateGig {
     pub title: Option<String>,
     pub description: Option<String>,
@@ -82,7 +86,8 @@ pub struct UpdateGig {
     pub category: Option<Categories>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GigListQuery {
     pub limit: Option<u64>,
     pub cursor_created_at: Option<chrono::DateTime<chrono::Utc>>,
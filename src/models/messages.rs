@@ -1,5 +1,6 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 /// SeaORM entity for the `messages` table.
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
@@ -56,7 +57,7 @@ pub struct CreateMessage {
 }
 
 /// Response DTO for messages sent over WebSocket and REST.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MessageResponse {
     pub id: Uuid,
     pub contract_id: Uuid,
@@ -80,7 +81,8 @@ impl From<Model> for MessageResponse {
 }
 
 /// Query parameters for paginated message history.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct MessageQuery {
     pub limit: Option<u64>,
     pub cursor_created_at: Option<chrono::DateTime<chrono::Utc>>,
@@ -88,7 +90,7 @@ pub struct MessageQuery {
 }
 
 /// Response for the conversations list endpoint.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ConversationSummary {
     pub contract_id: Uuid,
     pub other_user_id: Uuid,
@@ -1,12 +1,16 @@
+pub mod api_keys;
+pub mod audit_events;
 pub mod contracts;
 pub mod gigs;
 pub mod messages;
 pub mod portfolio;
 pub mod users;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PaginationQuery {
     pub page: Option<u64>,
     pub limit: Option<u64>,
@@ -21,3 +25,17 @@ impl PaginationQuery {
         self.limit.unwrap_or(20).min(100)
     }
 }
+
+/// Body of responses that only confirm an action, e.g. a deletion.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Confirmation {
+    pub message: String,
+}
+
+impl Confirmation {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
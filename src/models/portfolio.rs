@@ -1,9 +1,11 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// SeaORM entity for the `portfolios` table.
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "portfolios")]
+#[schema(as = Portfolio)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
@@ -14,7 +16,7 @@ pub struct Model {
     pub thumbnail_url: Option<String>,
     #[sea_orm(column_type = "Double")]
     pub price: f64,
-    pub created_at: DateTimeUtc,
+    pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -37,7 +39,7 @@ impl ActiveModelBehavior for ActiveModel {}
 
 // ── DTOs ──
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct CreatePortfolio {
     pub title: String,
     pub description: String,
@@ -46,7 +48,7 @@ pub struct CreatePortfolio {
     pub price: f64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct UpdatePortfolio {
     pub title: Option<String>,
     pub description: Option<String>,
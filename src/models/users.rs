@@ -1,8 +1,11 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// The `Roles` enum maps to a Postgres TEXT column stored as lowercase strings.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, EnumIter, DeriveActiveEnum)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, EnumIter, DeriveActiveEnum, ToSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
 pub enum Roles {
     #[sea_orm(string_value = "client")]
@@ -13,6 +16,22 @@ pub enum Roles {
     Admin,
 }
 
+/// Moderation state of an account, stored as lowercase strings.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, EnumIter, DeriveActiveEnum, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "lowercase")]
+pub enum AccountStatus {
+    #[sea_orm(string_value = "active")]
+    Active,
+    /// Locked out until `suspended_until` (indefinitely when it is `None`).
+    #[sea_orm(string_value = "suspended")]
+    Suspended,
+    #[sea_orm(string_value = "banned")]
+    Banned,
+}
+
 /// SeaORM entity for the `users` table.
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "users")]
@@ -29,6 +48,29 @@ pub struct Model {
     pub role: Roles,
     pub created_at: DateTimeUtc,
     pub updated_at: Option<DateTimeUtc>,
+    pub status: AccountStatus,
+    pub suspended_until: Option<DateTimeUtc>,
+    pub status_reason: Option<String>,
+    /// When the profile was last updated from JWT claims.
+    pub last_synced_at: Option<DateTimeUtc>,
+    /// Set once the user edits the field; claim sync then leaves it alone.
+    pub email_locked: bool,
+    pub display_name_locked: bool,
+    pub avatar_url_locked: bool,
+}
+
+impl Model {
+    /// Whether the account may use the API right now. A suspension whose
+    /// `suspended_until` has passed no longer applies.
+    pub fn is_active(&self) -> bool {
+        match self.status {
+            AccountStatus::Active => true,
+            AccountStatus::Suspended => self
+                .suspended_until
+                .is_some_and(|until| until <= chrono::Utc::now()),
+            AccountStatus::Banned => false,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -55,11 +97,12 @@ impl ActiveModelBehavior for ActiveModel {}
 
 // ── DTOs (not stored in DB, used for request bodies) ──
 
-/// Used internally by the auth middleware to create a user from JWT claims.
+/// Used internally by the auth middleware to create (or sync) a user from JWT claims.
 #[derive(Debug, Clone)]
 pub struct CreateUserFromAuth {
     pub id: Uuid,
-    pub email: String,
+    /// `None` when the token carries no email (only allowed by a lenient policy).
+    pub email: Option<String>,
     pub display_name: Option<String>,
     pub avatar_url: Option<String>,
     pub auth_provider: String,
@@ -67,7 +110,7 @@ pub struct CreateUserFromAuth {
 }
 
 /// Used by the `POST /api/auth/complete-profile` endpoint.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct CompleteProfile {
     pub username: Option<String>,
     pub role: Option<Roles>,
@@ -76,7 +119,7 @@ pub struct CompleteProfile {
 }
 
 /// Used for admin-level user updates.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct UpdateUser {
     pub email: Option<String>,
     pub username: Option<String>,
@@ -85,8 +128,36 @@ pub struct UpdateUser {
     pub role: Option<Roles>,
 }
 
+/// Used by the `PUT /api/admin/users/{id}/role` endpoint.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateUserRole {
+    pub role: Roles,
+}
+
+/// Used by the `POST /api/admin/users/{id}/suspend` endpoint.
+/// Omitting `until` suspends the account indefinitely.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SuspendUser {
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    pub reason: Option<String>,
+}
+
+/// Used by the `POST /api/admin/users/{id}/ban` endpoint.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct BanUser {
+    pub reason: Option<String>,
+}
+
+/// Returned by `POST /api/admin/users/{id}/revoke-sessions`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RevokedSessions {
+    /// Chat WebSockets closed on the instance that handled the request; the
+    /// other instances close theirs through pub/sub.
+    pub closed_connections: usize,
+}
+
 /// A safe user representation for API responses (never leaks internal fields).
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserResponse {
     pub id: Uuid,
     pub email: String,
@@ -94,8 +165,10 @@ pub struct UserResponse {
     pub display_name: Option<String>,
     pub avatar_url: Option<String>,
     pub role: Roles,
-    pub created_at: DateTimeUtc,
-    pub updated_at: Option<DateTimeUtc>,
+    pub status: AccountStatus,
+    pub suspended_until: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl From<Model> for UserResponse {
@@ -107,6 +180,8 @@ impl From<Model> for UserResponse {
             display_name: m.display_name,
             avatar_url: m.avatar_url,
             role: m.role,
+            status: m.status,
+            suspended_until: m.suspended_until,
             created_at: m.created_at,
             updated_at: m.updated_at,
         }
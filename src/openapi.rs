@@ -0,0 +1,118 @@
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::handlers::{admin, api_keys, auth, chat, contracts, gigs, portfolio, users};
+
+/// The OpenAPI 3 document for every route in [`crate::handlers::init_routes`].
+///
+/// Paths come from the `#[utoipa::path]` attribute on each handler and schemas
+/// from the DTOs they reference; `tests/openapi_test.rs` fails when a route is
+/// registered without being listed here.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Gradwork API",
+        description = "Every `/api` route is rate limited; over-budget requests get \
+                       `429 rate_limited` with `Retry-After`. Errors share the `ErrorBody` envelope."
+    ),
+    paths(
+        auth::me,
+        auth::complete_profile,
+        auth::logout,
+        auth::logout_all,
+        api_keys::list_api_keys,
+        api_keys::create_api_key,
+        api_keys::revoke_api_key,
+        users::get_users,
+        users::get_user,
+        users::update_user,
+        users::delete_user,
+        portfolio::get_portfolios,
+        portfolio::create_portfolio,
+        portfolio::get_portfolio,
+        portfolio::update_portfolio,
+        portfolio::delete_portfolio,
+        portfolio::get_portfolios_by_freelancer,
+        gigs::get_gigs,
+        gigs::create_gig,
+        gigs::get_gig,
+        gigs::update_gig,
+        gigs::delete_gig,
+        gigs::get_gigs_by_category,
+        gigs::get_gigs_by_user_id,
+        gigs::delete_all_gig_by_user_id,
+        contracts::get_contracts,
+        contracts::create_contract,
+        contracts::get_contract,
+        contracts::delete_contract,
+        contracts::update_status,
+        contracts::get_contracts_by_gig,
+        contracts::get_contracts_by_user,
+        admin::list_users,
+        admin::update_user,
+        admin::delete_user,
+        admin::set_user_role,
+        admin::suspend_user,
+        admin::ban_user,
+        admin::unsuspend_user,
+        admin::revoke_user_sessions,
+        admin::list_user_api_keys,
+        admin::create_user_api_key,
+        admin::revoke_api_key,
+        admin::user_cache_stats,
+        admin::cache_stats,
+        admin::list_gigs,
+        admin::update_gig,
+        admin::delete_gig,
+        admin::list_portfolios,
+        admin::update_portfolio,
+        admin::delete_portfolio,
+        admin::list_contracts,
+        admin::update_contract_status,
+        admin::delete_contract,
+        admin::list_audit_events,
+        crate::chat::session::ws_connect,
+        chat::create_ws_ticket,
+        chat::get_conversations,
+        chat::get_messages,
+        chat::mark_message_read,
+    ),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "auth", description = "The caller's session and profile"),
+        (name = "api-keys", description = "Long-lived keys for scripts and integrations"),
+        (name = "users", description = "User profiles"),
+        (name = "portfolios", description = "Freelancer portfolio items"),
+        (name = "gigs", description = "Gigs offered by freelancers"),
+        (name = "contracts", description = "Contract requests between clients and freelancers"),
+        (name = "admin", description = "Moderation; requires the Admin role"),
+        (name = "chat", description = "Per-contract chat over REST and WebSocket"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the two ways to authenticate: a session JWT (`Bearer <jwt>`) and
+/// an API key (`ApiKey <key>`), both in the `Authorization` header.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                "Authorization",
+                "`ApiKey <key>`, as returned by `POST /api/auth/api-keys`",
+            ))),
+        );
+    }
+}
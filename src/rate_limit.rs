@@ -0,0 +1,234 @@
+use actix_web::dev::ConnectionInfo;
+use actix_web::http::Method;
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::cache::memory::MemoryBackend;
+use crate::cache::{CacheBackend, CacheStore, keys};
+use crate::chat::protocol::ClientMessage;
+use crate::config::RateLimitConfig;
+use crate::metrics::metrics;
+
+/// A token bucket holding up to `requests` tokens that refills completely
+/// every `period`, written `<requests>/<s|min|h>` (e.g. `10/min`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct RateLimit {
+    pub requests: u32,
+    pub period: Duration,
+}
+
+impl RateLimit {
+    pub const fn per_minute(requests: u32) -> Self {
+        Self {
+            requests,
+            period: Duration::from_secs(60),
+        }
+    }
+
+    /// Time to regain one token.
+    fn interval(&self) -> Duration {
+        (self.period / self.requests).max(Duration::from_millis(1))
+    }
+}
+
+impl FromStr for RateLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid rate limit '{s}', expected e.g. 10/min");
+        let (requests, unit) = s.split_once('/').ok_or_else(invalid)?;
+        let requests: u32 = requests.trim().parse().map_err(|_| invalid())?;
+        let period = match unit.trim() {
+            "s" => Duration::from_secs(1),
+            "min" => Duration::from_secs(60),
+            "h" => Duration::from_secs(3600),
+            _ => return Err(invalid()),
+        };
+        if requests == 0 {
+            return Err(format!(
+                "invalid rate limit '{s}': requests must be at least 1"
+            ));
+        }
+        Ok(Self { requests, period })
+    }
+}
+
+impl TryFrom<String> for RateLimit {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Whole seconds to send in `Retry-After`, rounded up so clients never retry early.
+pub fn retry_after_secs(wait: Duration) -> u64 {
+    wait.as_millis().div_ceil(1000).max(1) as u64
+}
+
+/// Which [`RateLimitConfig`] policy an API request is counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteGroup {
+    /// `/api/auth/*` and `POST /api/chat/ws-ticket`.
+    Auth,
+    /// Creating, updating or deleting contracts.
+    ContractWrites,
+    /// Gig listings and details, readable without an account.
+    GigReads,
+    /// Any other write.
+    Writes,
+    /// Everything else.
+    Default,
+}
+
+impl RouteGroup {
+    /// Classify a request by method and matched route pattern (see
+    /// `HttpRequest::match_pattern`).
+    pub fn classify(method: &Method, pattern: &str) -> Self {
+        let read = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+        if pattern.starts_with("/api/auth/") || pattern == "/api/chat/ws-ticket" {
+            Self::Auth
+        } else if pattern.starts_with("/api/contracts") && !read {
+            Self::ContractWrites
+        } else if pattern.starts_with("/api/gigs") && read {
+            Self::GigReads
+        } else if !read {
+            Self::Writes
+        } else {
+            Self::Default
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auth => "auth",
+            Self::ContractWrites => "contract_writes",
+            Self::GigReads => "gig_reads",
+            Self::Writes => "writes",
+            Self::Default => "default",
+        }
+    }
+}
+
+/// Who a request is counted for: the user behind a valid bearer token, the
+/// hash of an API key, or else the client address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientKey {
+    User(Uuid),
+    ApiKey(String),
+    Ip(String),
+}
+
+impl fmt::Display for ClientKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User(id) => write!(f, "user:{id}"),
+            Self::ApiKey(hash) => write!(f, "key:{hash}"),
+            Self::Ip(ip) => write!(f, "ip:{ip}"),
+        }
+    }
+}
+
+/// The client address of a request: the peer address, or the address
+/// reported by `Forwarded`/`X-Forwarded-For` when `trust_proxy` is set.
+pub fn client_ip(info: &ConnectionInfo, trust_proxy: bool) -> Option<&str> {
+    if trust_proxy {
+        info.realip_remote_addr()
+    } else {
+        info.peer_addr()
+    }
+}
+
+/// Applies [`RateLimitConfig`] to API requests and chat frames.
+///
+/// Buckets live in the shared cache so limits hold across instances. While
+/// the cache is unavailable each instance falls back to its own in-memory
+/// buckets, so limits still apply, per instance, instead of failing open.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    cache: Arc<CacheStore>,
+    fallback: MemoryBackend,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, cache: Arc<CacheStore>) -> Self {
+        Self {
+            config,
+            cache,
+            fallback: MemoryBackend::new(),
+        }
+    }
+
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Count an API request; `Err` carries how long the client should wait.
+    pub async fn check_route(&self, group: RouteGroup, client: &ClientKey) -> Result<(), Duration> {
+        let limit = match group {
+            RouteGroup::Auth => self.config.auth,
+            RouteGroup::ContractWrites => self.config.contract_writes,
+            RouteGroup::GigReads => self.config.gig_reads,
+            RouteGroup::Writes => self.config.writes,
+            RouteGroup::Default => self.config.default,
+        };
+        self.check(group.as_str(), client, limit).await
+    }
+
+    /// Count a chat frame from `user_id`; `Err` carries how long to wait.
+    /// Typing and stop-typing share one bucket.
+    pub async fn check_chat(&self, msg: &ClientMessage, user_id: Uuid) -> Result<(), Duration> {
+        let (group, limit) = match msg {
+            ClientMessage::SendMessage { .. } => {
+                ("chat_send_message", self.config.chat_send_message)
+            }
+            ClientMessage::MarkRead { .. } => ("chat_mark_read", self.config.chat_mark_read),
+            ClientMessage::Typing | ClientMessage::StopTyping => {
+                ("chat_typing", self.config.chat_typing)
+            }
+        };
+        self.check(group, &ClientKey::User(user_id), limit).await
+    }
+
+    async fn check(
+        &self,
+        group: &str,
+        client: &ClientKey,
+        limit: RateLimit,
+    ) -> Result<(), Duration> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let key = keys::rate_limit(group, &client.to_string());
+        let wait = match self
+            .cache
+            .take_token(&key, limit.requests, limit.interval())
+            .await
+        {
+            Ok(wait) => wait,
+            Err(e) => {
+                tracing::debug!("Rate limiting {group} in memory: {e}");
+                self.fallback
+                    .take_token(&key, limit.requests, limit.interval())
+                    .await
+                    .unwrap_or_default()
+            }
+        };
+
+        if wait.is_zero() {
+            Ok(())
+        } else {
+            metrics().rate_limited.with_label_values(&[group]).inc();
+            Err(wait)
+        }
+    }
+}
+
+/// Wrapper type for Actix-web app data
+pub type RateLimiterData = Arc<RateLimiter>;
@@ -0,0 +1,134 @@
+use actix_web::http::header::{HeaderMap, HeaderName};
+use opentelemetry::propagation::Extractor;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{Context, global};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use std::collections::HashMap;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+use crate::config::{AppConfig, LogConfig, LogFormat, OtelConfig};
+
+/// Keeps the trace exporter alive; flushes buffered spans when dropped.
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take()
+            && let Err(e) = provider.shutdown()
+        {
+            eprintln!("Failed to flush traces: {e}");
+        }
+    }
+}
+
+/// Install the global `tracing` subscriber: logs to stdout as described by
+/// `config.log`, plus OTLP trace export when `config.otel.enabled`.
+///
+/// Hold the returned guard until shutdown so buffered spans are exported.
+pub fn init(config: &AppConfig) -> Result<Telemetry, String> {
+    let provider = if config.otel.enabled {
+        Some(tracer_provider(&config.otel)?)
+    } else {
+        None
+    };
+    let otel_layer = provider.as_ref().map(|provider| {
+        let tracer = provider.tracer(config.otel.service_name.clone());
+        tracing_opentelemetry::layer().with_tracer(tracer)
+    });
+
+    subscriber(&config.log, std::io::stdout)
+        .with(otel_layer)
+        .try_init()
+        .map_err(|e| format!("Failed to install tracing subscriber: {e}"))?;
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    Ok(Telemetry { provider })
+}
+
+/// The log subscriber described by `config`, writing to `writer`.
+///
+/// JSON output flattens each event's fields and includes the fields of its
+/// enclosing span (`request_id`, `user_id`, `contract_id`, ...), so every line
+/// can be correlated without a text-parsing step.
+pub fn subscriber<W>(
+    config: &LogConfig,
+    writer: W,
+) -> impl Subscriber + for<'a> LookupSpan<'a> + Send + Sync
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let fmt = tracing_subscriber::fmt::layer().with_writer(writer);
+    let fmt = match config.format {
+        LogFormat::Text => fmt.boxed(),
+        LogFormat::Json => fmt
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    };
+
+    Registry::default()
+        .with(EnvFilter::new(&config.filter))
+        .with(fmt)
+}
+
+fn tracer_provider(config: &OtelConfig) -> Result<SdkTracerProvider, String> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!(
+            "{}/v1/traces",
+            config.endpoint.trim_end_matches('/')
+        ))
+        .build()
+        .map_err(|e| format!("Failed to build OTLP exporter: {e}"))?;
+
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio)));
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(sampler)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name.clone())
+                .build(),
+        )
+        .build())
+}
+
+/// Continue the trace named by the W3C `traceparent`/`tracestate` headers in
+/// `headers`, if any, by making it the parent of `span`.
+pub fn set_remote_parent(span: &tracing::Span, headers: &HeaderMap) {
+    let parent = global::get_text_map_propagator(|p| p.extract(&HeaderExtractor(headers)));
+    span.set_parent(parent);
+}
+
+/// W3C trace-context headers for the current span, to send on outbound requests.
+pub fn trace_headers() -> HashMap<String, String> {
+    let mut headers = HashMap::new();
+    let cx: Context = tracing::Span::current().context();
+    global::get_text_map_propagator(|p| p.inject_context(&cx, &mut headers));
+    headers
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
@@ -0,0 +1,71 @@
+//! Tests for account status enforcement (suspensions and bans).
+//!
+//! Run with: `cargo test --test account_status_test`
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use gradwork_backend::auth::error::AuthError;
+use gradwork_backend::auth::middleware::ensure_active;
+use gradwork_backend::models::users::{AccountStatus, Model, Roles};
+
+/// Helper: a user with the given moderation state.
+fn user_with_status(
+    status: AccountStatus,
+    suspended_until: Option<chrono::DateTime<Utc>>,
+    reason: Option<&str>,
+) -> Model {
+    Model {
+        id: Uuid::new_v4(),
+        email: "test@example.com".to_string(),
+        username: None,
+        display_name: None,
+        avatar_url: None,
+        auth_provider: "google".to_string(),
+        role: Roles::Client,
+        created_at: Utc::now(),
+        updated_at: None,
+        status,
+        suspended_until,
+        status_reason: reason.map(String::from),
+        last_synced_at: None,
+        email_locked: false,
+        display_name_locked: false,
+        avatar_url_locked: false,
+    }
+}
+
+#[test]
+fn test_active_user_is_allowed() {
+    let user = user_with_status(AccountStatus::Active, None, None);
+    assert!(ensure_active(&user).is_ok());
+}
+
+#[test]
+fn test_suspended_user_is_rejected_until_suspension_ends() {
+    let until = Utc::now() + Duration::hours(1);
+    let user = user_with_status(AccountStatus::Suspended, Some(until), Some("spam"));
+    let err = ensure_active(&user).unwrap_err();
+    assert_eq!(err.code(), "account_suspended");
+    assert!(err.to_string().ends_with(": spam"));
+
+    let indefinite = user_with_status(AccountStatus::Suspended, None, None);
+    assert!(matches!(
+        ensure_active(&indefinite),
+        Err(AuthError::AccountSuspended { until: None, .. })
+    ));
+
+    let expired = user_with_status(
+        AccountStatus::Suspended,
+        Some(Utc::now() - Duration::minutes(1)),
+        None,
+    );
+    assert!(ensure_active(&expired).is_ok());
+}
+
+#[test]
+fn test_banned_user_is_rejected() {
+    let user = user_with_status(AccountStatus::Banned, None, Some("fraud"));
+    let err = ensure_active(&user).unwrap_err();
+    assert_eq!(err.code(), "account_banned");
+    assert_eq!(err.to_string(), "Account is banned: fraud");
+}
@@ -0,0 +1,83 @@
+//! Tests for API key generation, hashing and scope checks.
+//!
+//! Run with: `cargo test --test api_keys_test`
+use chrono::Utc;
+use uuid::Uuid;
+
+use gradwork_backend::auth::api_keys::{KEY_PREFIX, generate, hash_key};
+use gradwork_backend::auth::principal::{Credential, Principal};
+use gradwork_backend::models::api_keys::{self, ApiScope};
+use gradwork_backend::models::users::{AccountStatus, Model, Roles};
+
+/// Helper: an active user.
+fn test_user() -> Model {
+    Model {
+        id: Uuid::new_v4(),
+        email: "integration@example.com".to_string(),
+        username: None,
+        display_name: None,
+        avatar_url: None,
+        auth_provider: "google".to_string(),
+        role: Roles::Freelancer,
+        created_at: Utc::now(),
+        updated_at: None,
+        status: AccountStatus::Active,
+        suspended_until: None,
+        status_reason: None,
+        last_synced_at: None,
+        email_locked: false,
+        display_name_locked: false,
+        avatar_url_locked: false,
+    }
+}
+
+#[test]
+fn test_generated_keys_are_unique_and_hashed() {
+    let first = generate();
+    let second = generate();
+
+    assert!(first.key.starts_with(KEY_PREFIX));
+    assert!(first.key.starts_with(&first.prefix));
+    assert_ne!(first.key, second.key);
+    assert_eq!(first.hash, hash_key(&first.key));
+    assert_ne!(first.hash, first.key);
+}
+
+#[test]
+fn test_scopes_round_trip_through_storage_format() {
+    let scopes: Vec<ApiScope> = serde_json::from_str(r#"["gigs:read", "contracts:read"]"#).unwrap();
+
+    let model = api_keys::Model {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        name: "reporting".to_string(),
+        prefix: "gwk_00000000".to_string(),
+        key_hash: hash_key("gwk_test"),
+        scopes: "gigs:read contracts:read retired:scope".to_string(),
+        expires_at: Some(Utc::now() - chrono::Duration::days(1)),
+        last_used_at: None,
+        revoked_at: None,
+        created_at: Utc::now(),
+    };
+
+    assert_eq!(model.scope_list(), scopes);
+    assert!(model.is_expired());
+}
+
+#[test]
+fn test_principal_scope_enforcement() {
+    let session = Principal(test_user(), Credential::Session);
+    assert!(session.require(ApiScope::GigsWrite).is_ok());
+
+    let api_key = Principal(
+        test_user(),
+        Credential::ApiKey {
+            key_id: Uuid::new_v4(),
+            scopes: vec![ApiScope::GigsRead],
+        },
+    );
+    assert!(api_key.require(ApiScope::GigsRead).is_ok());
+
+    let err = api_key.require(ApiScope::GigsWrite).unwrap_err();
+    assert_eq!(err.code(), "insufficient_scope");
+}
@@ -0,0 +1,89 @@
+//! Tests for audit events and their request context.
+//!
+//! Run with: `cargo test --test audit_test`
+use actix_web::test::TestRequest;
+use actix_web::{FromRequest, HttpMessage, web};
+use serde_json::json;
+use uuid::Uuid;
+
+use gradwork_backend::audit::{Audit, AuditEvent, Target};
+use gradwork_backend::config::AppConfig;
+use gradwork_backend::middleware::request_id::RequestId;
+use gradwork_backend::models::audit_events::AuditQuery;
+
+#[test]
+fn test_target_kind_and_id() {
+    let id = Uuid::new_v4();
+    let cases = [
+        (Target::User(id), "user"),
+        (Target::Gig(id), "gig"),
+        (Target::Portfolio(id), "portfolio"),
+        (Target::Contract(id), "contract"),
+        (Target::ApiKey(id), "api_key"),
+    ];
+    for (target, kind) in cases {
+        assert_eq!(target.kind(), kind);
+        assert_eq!(target.id(), id);
+    }
+}
+
+#[test]
+fn test_event_snapshots() {
+    let actor = Uuid::new_v4();
+    let gig = Uuid::new_v4();
+    let event = AuditEvent::new(actor, "gig.update", Target::Gig(gig))
+        .before(&json!({ "title": "Old" }))
+        .after(&json!({ "title": "New" }));
+
+    assert_eq!(event.actor_id, actor);
+    assert_eq!(event.action, "gig.update");
+    assert_eq!(event.before, Some(json!({ "title": "Old" })));
+    assert_eq!(event.after, Some(json!({ "title": "New" })));
+
+    let event = AuditEvent::new(actor, "auth.logout", Target::User(actor));
+    assert!(event.before.is_none() && event.after.is_none());
+}
+
+#[actix_web::test]
+async fn test_context_has_peer_ip_and_request_id() {
+    let req = TestRequest::default()
+        .peer_addr("10.0.0.1:40000".parse().unwrap())
+        .insert_header(("X-Forwarded-For", "203.0.113.9"))
+        .to_http_request();
+    req.extensions_mut().insert(RequestId("req-1".into()));
+
+    let audit = Audit::extract(&req).await.unwrap();
+    assert_eq!(audit.ip(), Some("10.0.0.1"));
+    assert_eq!(audit.request_id(), Some("req-1"));
+}
+
+#[actix_web::test]
+async fn test_context_trusts_forwarded_ip_behind_proxy() {
+    let mut config = AppConfig::default();
+    config.rate_limit.trust_proxy = true;
+    let req = TestRequest::default()
+        .peer_addr("10.0.0.1:40000".parse().unwrap())
+        .insert_header(("X-Forwarded-For", "203.0.113.9"))
+        .app_data(web::Data::new(config))
+        .to_http_request();
+
+    let audit = Audit::extract(&req).await.unwrap();
+    assert_eq!(audit.ip(), Some("203.0.113.9"));
+    assert_eq!(audit.request_id(), None);
+}
+
+#[test]
+fn test_query_filters_and_limit() {
+    let actor = Uuid::new_v4();
+    let query = web::Query::<AuditQuery>::from_query(&format!(
+        "actor_id={actor}&target_type=gig&from=2025-03-01T00:00:00Z&limit=500"
+    ))
+    .unwrap();
+    assert_eq!(query.actor_id, Some(actor));
+    assert_eq!(query.target_type.as_deref(), Some("gig"));
+    assert!(query.from.is_some() && query.to.is_none());
+    assert_eq!(query.limit(), 100);
+
+    let query = web::Query::<AuditQuery>::from_query("").unwrap();
+    assert_eq!(query.limit(), 20);
+}
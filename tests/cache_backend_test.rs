@@ -0,0 +1,110 @@
+//! Tests for `CacheStore` over the in-memory backend, and its fallback when
+//! Redis is unreachable.
+//!
+//! Run with: `cargo test --test cache_backend_test`
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use gradwork_backend::cache::redis_backend::RedisBackend;
+use gradwork_backend::cache::{CacheError, CacheStore};
+
+const TTL: Duration = Duration::from_secs(60);
+
+#[tokio::test]
+async fn test_concurrent_misses_load_once() {
+    let cache = CacheStore::memory();
+    let loads = Arc::new(AtomicUsize::new(0));
+
+    let tasks: Vec<_> = (0..20)
+        .map(|_| {
+            let (cache, loads) = (cache.clone(), loads.clone());
+            tokio::spawn(async move {
+                cache
+                    .cached("gig:1", TTL, || async {
+                        loads.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok::<_, ()>(42u32)
+                    })
+                    .await
+            })
+        })
+        .collect();
+    for task in tasks {
+        assert_eq!(task.await.unwrap(), Ok(42));
+    }
+
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+    assert_eq!(cache.stats().misses, 1);
+}
+
+#[tokio::test]
+async fn test_take_is_single_use_and_entries_expire() {
+    let cache = CacheStore::memory();
+    cache
+        .set("ws:ticket:abc", &"user-1", Some(60))
+        .await
+        .unwrap();
+    cache.set("gig:1", &1u32, Some(1)).await.unwrap();
+
+    assert_eq!(
+        cache.take::<String>("ws:ticket:abc").await.unwrap(),
+        Some("user-1".into())
+    );
+    assert_eq!(cache.take::<String>("ws:ticket:abc").await.unwrap(), None);
+
+    assert!(cache.exists("gig:1").await.unwrap());
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert!(!cache.exists("gig:1").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_tag_invalidation() {
+    let cache = CacheStore::memory();
+    let all = vec!["gigs:all".to_string()];
+    let design = vec!["gigs:all".to_string(), "gigs:category:design".to_string()];
+    cache
+        .set_tagged("gigs:list:l20:start", &1u32, 60, &all)
+        .await
+        .unwrap();
+    cache
+        .set_tagged("gigs:category:design", &2u32, 60, &design)
+        .await
+        .unwrap();
+    cache.set("gig:1", &3u32, Some(60)).await.unwrap();
+
+    let deleted = cache
+        .invalidate_tags(&["gigs:category:design".into()])
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+    assert_eq!(
+        cache.get::<u32>("gigs:list:l20:start").await.unwrap(),
+        Some(1)
+    );
+    assert_eq!(
+        cache.get::<u32>("gigs:category:design").await.unwrap(),
+        None
+    );
+
+    assert_eq!(cache.invalidate_tags(&all).await.unwrap(), 1);
+    assert_eq!(cache.get::<u32>("gigs:list:l20:start").await.unwrap(), None);
+    assert_eq!(cache.get::<u32>("gig:1").await.unwrap(), Some(3));
+}
+
+#[tokio::test]
+async fn test_unconnected_redis_falls_back_to_loader() {
+    let backend = RedisBackend::new("redis://127.0.0.1:1").unwrap();
+    let cache = CacheStore::new(Arc::new(backend));
+
+    assert!(matches!(cache.ping().await, Err(CacheError::Unavailable)));
+    assert!(matches!(
+        cache.get::<u32>("gig:1").await,
+        Err(CacheError::Unavailable)
+    ));
+
+    let value = cache
+        .cached("gig:1", TTL, || async { Ok::<_, ()>(7u32) })
+        .await;
+    assert_eq!(value, Ok(7));
+}
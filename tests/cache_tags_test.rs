@@ -0,0 +1,73 @@
+//! Tests for the cache tags used to invalidate gig listings.
+//!
+//! Run with: `cargo test --test cache_tags_test`
+use chrono::Utc;
+use uuid::Uuid;
+
+use gradwork_backend::cache::{keys, tags};
+use gradwork_backend::models::gigs::{Categories, Model as Gig};
+
+fn gig(category: Categories) -> Gig {
+    Gig {
+        id: Uuid::new_v4(),
+        title: "Logo design".into(),
+        description: "A logo".into(),
+        price: 50.0,
+        thumbnail_url: None,
+        category,
+        user_id: Uuid::new_v4(),
+        created_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_gig_tags_cover_its_listings() {
+    let gig = gig(Categories::WebDevelopment);
+
+    assert_eq!(
+        tags::for_gig(&gig),
+        [
+            "gigs:all".to_string(),
+            "gigs:category:web_development".to_string(),
+            format!("user:{}", gig.user_id),
+        ]
+    );
+}
+
+#[test]
+fn test_category_change_invalidates_old_and_new_category() {
+    let before = gig(Categories::Design);
+    let after = Gig {
+        category: Categories::VideoEditing,
+        ..before.clone()
+    };
+
+    let tags = tags::for_gig_update(&before, &after);
+
+    assert!(tags.contains(&"gigs:category:design".to_string()));
+    assert!(tags.contains(&"gigs:category:video_editing".to_string()));
+    assert_eq!(tags.len(), 4, "shared tags are not repeated: {tags:?}");
+}
+
+#[test]
+fn test_user_gig_tags_cover_every_category() {
+    let user_id = Uuid::new_v4();
+    let tags = tags::for_user_gigs(user_id);
+
+    assert!(tags.contains(&"gigs:all".to_string()));
+    assert!(tags.contains(&format!("user:{user_id}")));
+    assert_eq!(
+        tags.iter()
+            .filter(|t| t.starts_with("gigs:category:"))
+            .count(),
+        7
+    );
+}
+
+#[test]
+fn test_tag_sets_do_not_collide_with_entries() {
+    let tag = tags::gig_category("design");
+
+    assert_eq!(keys::tag(&tag), "tag:gigs:category:design");
+    assert_ne!(keys::tag(&tag), keys::gigs_by_category("design"));
+}
@@ -0,0 +1,57 @@
+//! Tests for closing a user's chat sessions across instances.
+//!
+//! Run with: `cargo test --test chat_disconnect_test`
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use gradwork_backend::cache::CacheStore;
+use gradwork_backend::chat::protocol::ServerMessage;
+use gradwork_backend::chat::server::ChatServer;
+
+#[tokio::test]
+async fn test_disconnect_reaches_every_instance_once() {
+    let cache = Arc::new(CacheStore::memory());
+    let local = Arc::new(ChatServer::new().with_pubsub(cache.clone()));
+    let remote = Arc::new(ChatServer::new().with_pubsub(cache));
+    local.spawn_disconnect_listener();
+    remote.spawn_disconnect_listener();
+    // Let both listeners subscribe.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let user_id = Uuid::new_v4();
+    let mut here = local.join(Uuid::new_v4(), user_id).await;
+    let mut there = remote.join(Uuid::new_v4(), user_id).await;
+    let mut bystander = remote.join(Uuid::new_v4(), Uuid::new_v4()).await;
+    for rx in [&mut here, &mut there, &mut bystander] {
+        while rx.try_recv().is_ok() {}
+    }
+
+    assert_eq!(local.disconnect_user(user_id, "Account suspended").await, 1);
+
+    for rx in [&mut here, &mut there] {
+        let message = tokio::time::timeout(Duration::from_secs(1), rx.recv()).await;
+        assert!(matches!(
+            message,
+            Ok(Some(ServerMessage::Disconnect { reason })) if reason == "Account suspended"
+        ));
+    }
+    // The publishing instance skips its own message.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(here.try_recv().is_err());
+    assert!(bystander.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_without_pubsub_only_this_instance_disconnects() {
+    let chat_server = ChatServer::new();
+    let user_id = Uuid::new_v4();
+    let mut rx = chat_server.join(Uuid::new_v4(), user_id).await;
+    while rx.try_recv().is_ok() {}
+
+    assert_eq!(chat_server.disconnect_user(user_id, "Signed out").await, 1);
+    assert!(matches!(
+        rx.recv().await,
+        Some(ServerMessage::Disconnect { .. })
+    ));
+}
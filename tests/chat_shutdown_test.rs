@@ -0,0 +1,101 @@
+//! Tests for draining chat sessions on shutdown.
+//!
+//! Run with: `cargo test --test chat_shutdown_test`
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use gradwork_backend::chat::protocol::ServerMessage;
+use gradwork_backend::chat::server::{ChatServer, DrainReport};
+
+const RECONNECT: Duration = Duration::from_millis(1500);
+
+#[tokio::test]
+async fn test_clients_are_told_to_reconnect_then_dropped() {
+    let chat_server = ChatServer::new();
+    let contract_id = Uuid::new_v4();
+    let mut a = chat_server.join(contract_id, Uuid::new_v4()).await;
+    let mut b = chat_server.join(Uuid::new_v4(), Uuid::new_v4()).await;
+    while a.try_recv().is_ok() {}
+
+    let report = chat_server
+        .shutdown(RECONNECT, Duration::from_secs(1))
+        .await;
+    assert_eq!(
+        report,
+        DrainReport {
+            notified: 2,
+            abandoned_writes: 0,
+            open_sessions: 0,
+        }
+    );
+
+    for rx in [&mut a, &mut b] {
+        assert!(matches!(
+            rx.recv().await,
+            Some(ServerMessage::ServerRestarting {
+                reconnect_after_ms: 1500
+            })
+        ));
+        assert!(rx.recv().await.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_nothing_new_is_accepted_while_draining() {
+    let chat_server = ChatServer::new();
+    assert!(chat_server.begin_write().is_some());
+
+    chat_server
+        .shutdown(RECONNECT, Duration::from_secs(1))
+        .await;
+
+    assert!(chat_server.is_draining());
+    assert!(chat_server.begin_write().is_none());
+    let mut rx = chat_server.join(Uuid::new_v4(), Uuid::new_v4()).await;
+    assert!(rx.recv().await.is_none());
+    assert_eq!(chat_server.connection_counts().await, (0, 0));
+}
+
+#[tokio::test]
+async fn test_in_flight_writes_finish_before_sessions_close() {
+    let chat_server = Arc::new(ChatServer::new());
+    let mut rx = chat_server.join(Uuid::new_v4(), Uuid::new_v4()).await;
+    let write = chat_server.begin_write().unwrap();
+
+    let draining = tokio::spawn({
+        let chat_server = chat_server.clone();
+        async move {
+            chat_server
+                .shutdown(RECONNECT, Duration::from_secs(5))
+                .await
+        }
+    });
+
+    assert!(matches!(
+        rx.recv().await,
+        Some(ServerMessage::ServerRestarting { .. })
+    ));
+    // The connection stays open while the write is still running.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(rx.try_recv().is_err());
+    assert!(!draining.is_finished());
+
+    drop(write);
+    assert!(rx.recv().await.is_none());
+    assert_eq!(draining.await.unwrap().abandoned_writes, 0);
+}
+
+#[tokio::test]
+async fn test_stragglers_are_reported_after_the_deadline() {
+    let chat_server = ChatServer::new();
+    let _session = chat_server.session_started();
+    let _write = chat_server.begin_write().unwrap();
+
+    let report = chat_server
+        .shutdown(RECONNECT, Duration::from_millis(50))
+        .await;
+
+    assert_eq!(report.abandoned_writes, 1);
+    assert_eq!(report.open_sessions, 1);
+}
@@ -0,0 +1,223 @@
+//! Tests for loading and validating `AppConfig`.
+//!
+//! Run with: `cargo test --test config_test`
+use std::collections::HashMap;
+use std::time::Duration;
+
+use gradwork_backend::config::{AppConfig, ConfigError, LogFormat, VerifierKind};
+use gradwork_backend::rate_limit::RateLimit;
+
+fn load(toml: Option<&str>, vars: &[(&str, &str)]) -> Result<AppConfig, ConfigError> {
+    let env: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    AppConfig::from_sources(toml, |name| env.get(name).cloned())
+}
+
+const REQUIRED: &[(&str, &str)] = &[
+    ("DATABASE_URL", "postgres://localhost/gradwork"),
+    ("REDIS_URL", "redis://localhost"),
+    ("SUPABASE_URL", "https://project.supabase.co"),
+    ("SUPABASE_ANON_KEY", "anon"),
+];
+
+#[test]
+fn test_defaults_with_required_env() {
+    let config = load(None, REQUIRED).unwrap();
+
+    assert_eq!(config.server.port, 8080);
+    assert_eq!(config.server.shutdown_timeout_secs, 30);
+    assert_eq!(config.ws.drain_timeout_secs, 10);
+    assert_eq!(config.auth.verifier, VerifierKind::Jwks);
+    assert!(config.cors.allowed_origins.is_empty());
+    assert!(!config.features.ws_query_token);
+    assert_eq!(config.cache_config().gig_ttl, Duration::from_secs(600));
+    assert_eq!(
+        config.auth.validation_policy().issuer.as_deref(),
+        Some("https://project.supabase.co/auth/v1")
+    );
+}
+
+#[test]
+fn test_env_overrides_file() {
+    let toml = r#"
+        [server]
+        port = 9000
+        workers = 4
+
+        [cors]
+        allowed_origins = ["https://app.example.com"]
+
+        [cache]
+        gig_list_secs = 120
+    "#;
+    let mut vars = REQUIRED.to_vec();
+    vars.extend([
+        ("PORT", "9100"),
+        (
+            "CORS_ALLOWED_ORIGINS",
+            "https://a.example.com, https://b.example.com",
+        ),
+    ]);
+
+    let config = load(Some(toml), &vars).unwrap();
+
+    assert_eq!(config.server.port, 9100);
+    assert_eq!(config.server.workers, 4);
+    assert_eq!(
+        config.cors.allowed_origins,
+        ["https://a.example.com", "https://b.example.com"]
+    );
+    assert_eq!(config.cache.gig_list_secs, 120);
+}
+
+#[test]
+fn test_log_settings() {
+    let config = load(None, REQUIRED).unwrap();
+    assert_eq!(config.log.format, LogFormat::Text);
+    assert_eq!(config.log.filter, "info");
+
+    let mut vars = REQUIRED.to_vec();
+    vars.extend([
+        ("LOG_FORMAT", "json"),
+        ("RUST_LOG", "warn,gradwork_backend=debug"),
+    ]);
+    let config = load(None, &vars).unwrap();
+    assert_eq!(config.log.format, LogFormat::Json);
+    assert_eq!(config.log.filter, "warn,gradwork_backend=debug");
+
+    let mut vars = REQUIRED.to_vec();
+    vars.extend([("LOG_FORMAT", "xml"), ("RUST_LOG", "info,[")]);
+    let messages = load(None, &vars).unwrap_err().0.join("\n");
+    assert!(
+        messages.contains("LOG_FORMAT: invalid value 'xml'"),
+        "{messages}"
+    );
+    assert!(messages.contains("log.filter (RUST_LOG)"), "{messages}");
+}
+
+#[test]
+fn test_otel_settings() {
+    let config = load(None, REQUIRED).unwrap();
+    assert!(!config.otel.enabled);
+    assert_eq!(config.otel.endpoint, "http://localhost:4318");
+
+    let mut vars = REQUIRED.to_vec();
+    vars.extend([
+        ("OTEL_ENABLED", "true"),
+        ("OTEL_EXPORTER_OTLP_ENDPOINT", "localhost:4318"),
+        ("OTEL_SAMPLE_RATIO", "1.5"),
+    ]);
+    let messages = load(None, &vars).unwrap_err().0.join("\n");
+    assert!(
+        messages.contains("OTEL_EXPORTER_OTLP_ENDPOINT"),
+        "{messages}"
+    );
+    assert!(messages.contains("OTEL_SAMPLE_RATIO"), "{messages}");
+}
+
+#[test]
+fn test_rate_limit_settings() {
+    let config = load(None, REQUIRED).unwrap();
+    assert!(config.rate_limit.enabled);
+    assert_eq!(config.rate_limit.contract_writes, RateLimit::per_minute(10));
+
+    let toml = r#"
+        [rate_limit]
+        gig_reads = "5/s"
+    "#;
+    let mut vars = REQUIRED.to_vec();
+    vars.push(("RATE_LIMIT_CHAT_SEND_MESSAGE", "100/h"));
+    let config = load(Some(toml), &vars).unwrap();
+    assert_eq!(config.rate_limit.gig_reads.requests, 5);
+    assert_eq!(config.rate_limit.gig_reads.period, Duration::from_secs(1));
+    assert_eq!(
+        config.rate_limit.chat_send_message.period,
+        Duration::from_secs(3600)
+    );
+
+    let mut vars = REQUIRED.to_vec();
+    vars.extend([
+        ("RATE_LIMIT_WRITES", "0/min"),
+        ("RATE_LIMIT_AUTH", "30/day"),
+    ]);
+    let messages = load(None, &vars).unwrap_err().0.join("\n");
+    assert!(messages.contains("RATE_LIMIT_WRITES"), "{messages}");
+    assert!(messages.contains("RATE_LIMIT_AUTH"), "{messages}");
+    assert!(load(Some("[rate_limit]\ndefault = \"lots\""), REQUIRED).is_err());
+}
+
+#[test]
+fn test_all_errors_are_reported_together() {
+    let vars = [
+        ("PORT", "not-a-port"),
+        ("AUTH_VERIFIER", "hs256"),
+        ("CACHE_TTL_USERS", "0"),
+        ("JWKS_REFRESH_SECS", "0"),
+        ("CORS_ALLOWED_ORIGINS", "*,https://app.example.com"),
+    ];
+
+    let err = load(None, &vars).unwrap_err();
+    let messages = err.0.join("\n");
+
+    for expected in [
+        "PORT: invalid value 'not-a-port'",
+        "DATABASE_URL",
+        "REDIS_URL",
+        "CACHE_TTL_USERS",
+        "JWKS_REFRESH_SECS) must be greater than 0",
+        "cannot mix \"*\"",
+        "SUPABASE_JWT_SECRET) must be set for verifier hs256",
+    ] {
+        assert!(
+            messages.contains(expected),
+            "missing {expected:?} in:\n{messages}"
+        );
+    }
+    assert!(!messages.contains("SUPABASE_ANON_KEY"), "{messages}");
+}
+
+#[test]
+fn test_file_errors_are_reported_with_env_errors() {
+    let mut vars = REQUIRED.to_vec();
+    vars.push(("PORT", "not-a-port"));
+    let err = load(Some("[server]\nport = \"eighty\"\n"), &vars).unwrap_err();
+    let messages = err.0.join("\n");
+
+    assert!(messages.contains("config file:"), "{messages}");
+    assert!(messages.contains("PORT: invalid value"), "{messages}");
+}
+
+#[test]
+fn test_rejects_malformed_redis_url() {
+    let mut vars = REQUIRED.to_vec();
+    vars.push(("CACHE_BACKEND", "redis"));
+    vars.push(("REDIS_URL", "localhost:6379"));
+    let err = load(None, &vars).unwrap_err();
+    assert!(err.to_string().contains("REDIS_URL"), "{err}");
+
+    // Only the redis backend opens the URL.
+    vars.push(("CACHE_BACKEND", "memory"));
+    assert!(load(None, &vars).is_ok());
+}
+
+#[test]
+fn test_example_file_is_valid() {
+    let toml = include_str!("../config.example.toml");
+    let config = load(Some(toml), &[]).unwrap();
+
+    assert_eq!(config.cors.allowed_origins, ["http://localhost:3000"]);
+    assert_eq!(config.database.max_connections, 10);
+}
+
+#[test]
+fn test_rejects_unknown_keys_and_verifiers() {
+    let err = load(Some("[server]\nprot = 9000\n"), REQUIRED).unwrap_err();
+    assert!(err.to_string().contains("prot"), "{err}");
+
+    let mut vars = REQUIRED.to_vec();
+    vars.push(("AUTH_VERIFIER", "rsa"));
+    let err = load(None, &vars).unwrap_err();
+    assert!(err.to_string().contains("AUTH_VERIFIER"), "{err}");
+}
@@ -0,0 +1,76 @@
+//! Tests for the CORS policy.
+//!
+//! Run with: `cargo test --test cors_test`
+use actix_web::http::{StatusCode, header};
+use actix_web::test::{TestRequest, call_service, init_service};
+use actix_web::{App, HttpResponse, web};
+
+use gradwork_backend::config::CorsConfig;
+use gradwork_backend::middleware::cors::cors;
+
+const ORIGIN: &str = "http://localhost:3000";
+
+fn config() -> CorsConfig {
+    CorsConfig {
+        allowed_origins: vec![ORIGIN.to_string()],
+        ..Default::default()
+    }
+}
+
+#[actix_web::test]
+async fn test_preflight_allows_request_id_and_trace_context() {
+    let app = init_service(
+        App::new()
+            .wrap(cors(&config()))
+            .route("/api/gigs", web::get().to(HttpResponse::Ok)),
+    )
+    .await;
+
+    let req = TestRequest::default()
+        .method(actix_web::http::Method::OPTIONS)
+        .uri("/api/gigs")
+        .insert_header((header::ORIGIN, ORIGIN))
+        .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, "GET"))
+        .insert_header((
+            header::ACCESS_CONTROL_REQUEST_HEADERS,
+            "authorization, x-request-id, traceparent, tracestate",
+        ))
+        .to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let allowed = res
+        .headers()
+        .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_lowercase();
+    for name in ["authorization", "x-request-id", "traceparent", "tracestate"] {
+        assert!(allowed.contains(name), "{name} not in {allowed}");
+    }
+}
+
+#[actix_web::test]
+async fn test_request_id_and_retry_after_are_exposed() {
+    let app = init_service(
+        App::new()
+            .wrap(cors(&config()))
+            .route("/api/gigs", web::get().to(HttpResponse::Ok)),
+    )
+    .await;
+
+    let req = TestRequest::get()
+        .uri("/api/gigs")
+        .insert_header((header::ORIGIN, ORIGIN))
+        .to_request();
+    let res = call_service(&app, req).await;
+    let exposed = res
+        .headers()
+        .get(header::ACCESS_CONTROL_EXPOSE_HEADERS)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_lowercase();
+    assert!(exposed.contains("x-request-id"), "{exposed}");
+    assert!(exposed.contains("retry-after"), "{exposed}");
+}
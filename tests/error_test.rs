@@ -0,0 +1,101 @@
+//! Tests for the API error envelope and error mapping.
+//!
+//! Run with: `cargo test --test error_test`
+use actix_web::middleware::from_fn;
+use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+use actix_web::{App, HttpResponse, ResponseError, http::StatusCode, web};
+use sea_orm::DbErr;
+use std::time::Duration;
+
+use gradwork_backend::auth::error::AuthError;
+use gradwork_backend::auth::policy::Denied;
+use gradwork_backend::error::ApiError;
+use gradwork_backend::middleware::request_id::{REQUEST_ID_HEADER, request_id};
+
+#[rustfmt::skip]
+#[test]
+fn test_error_codes_and_statuses() {
+    let cases = [
+        (ApiError::not_found("x"), StatusCode::NOT_FOUND, "not_found"),
+        (ApiError::forbidden("x"), StatusCode::FORBIDDEN, "forbidden"),
+        (ApiError::conflict("x"), StatusCode::CONFLICT, "conflict"),
+        (ApiError::validation("x"), StatusCode::BAD_REQUEST, "validation_failed"),
+        (ApiError::Unavailable("x".into()), StatusCode::SERVICE_UNAVAILABLE, "service_unavailable"),
+        (ApiError::RateLimited { retry_after: Duration::from_secs(1) }, StatusCode::TOO_MANY_REQUESTS, "rate_limited"),
+        (ApiError::from(AuthError::TokenExpired), StatusCode::UNAUTHORIZED, "token_expired"),
+        (ApiError::Internal("x".into()), StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
+    ];
+
+    for (err, status, code) in cases {
+        assert_eq!(err.status_code(), status, "{err:?}");
+        assert_eq!(err.code(), code, "{err:?}");
+    }
+}
+
+#[test]
+fn test_db_and_policy_errors_are_mapped() {
+    let not_found = ApiError::from(DbErr::RecordNotFound("Gig not found".into()));
+    assert_eq!(not_found.code(), "not_found");
+    assert_eq!(not_found.to_string(), "Gig not found");
+
+    let other = ApiError::from(DbErr::Custom("connection reset".into()));
+    assert_eq!(other.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let denied = ApiError::from(Denied {
+        reason: "You can only delete your own gigs",
+    });
+    assert_eq!(denied.code(), "forbidden");
+    assert_eq!(denied.to_string(), "You can only delete your own gigs");
+}
+
+async fn fail() -> Result<HttpResponse, ApiError> {
+    Err(ApiError::Database(DbErr::Custom("secret detail".into())))
+}
+
+#[actix_web::test]
+async fn test_envelope_carries_request_id_and_hides_internals() {
+    let app = init_service(
+        App::new()
+            .wrap(from_fn(request_id))
+            .route("/fail", web::get().to(fail)),
+    )
+    .await;
+
+    let req = TestRequest::get()
+        .uri("/fail")
+        .insert_header((REQUEST_ID_HEADER, "req-123"))
+        .to_request();
+    let resp = call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(resp.headers().get(REQUEST_ID_HEADER).unwrap(), "req-123");
+
+    let body: serde_json::Value = read_body_json(resp).await;
+    assert_eq!(body["code"], "internal_error");
+    assert_eq!(body["error"], "Internal server error");
+    assert_eq!(body["request_id"], "req-123");
+}
+
+#[actix_web::test]
+async fn test_malformed_inbound_request_id_is_replaced() {
+    let app = init_service(
+        App::new()
+            .wrap(from_fn(request_id))
+            .route("/fail", web::get().to(fail)),
+    )
+    .await;
+
+    let req = TestRequest::get()
+        .uri("/fail")
+        .insert_header((REQUEST_ID_HEADER, "not a valid id!"))
+        .to_request();
+    let resp = call_service(&app, req).await;
+
+    let id = resp
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(uuid::Uuid::parse_str(id).is_ok(), "{id}");
+}
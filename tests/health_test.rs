@@ -0,0 +1,77 @@
+//! Tests for the liveness endpoint and readiness aggregation.
+//!
+//! Run with: `cargo test --test health_test`
+use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+use actix_web::{App, http::StatusCode};
+
+use gradwork_backend::handlers::health::{
+    CheckState, DependencyCheck, HealthReport, Readiness, check,
+};
+use gradwork_backend::handlers::init_health_routes;
+
+async fn up(name: &'static str, required: bool) -> DependencyCheck {
+    check(name, required, async { Ok(None) }).await
+}
+
+async fn down(name: &'static str, required: bool) -> DependencyCheck {
+    check(name, required, async {
+        Err("connection refused".to_string())
+    })
+    .await
+}
+
+#[actix_web::test]
+async fn test_live_endpoint() {
+    let app = init_service(App::new().configure(init_health_routes)).await;
+
+    let res = call_service(&app, TestRequest::get().uri("/health/live").to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: serde_json::Value = read_body_json(res).await;
+    assert_eq!(body["status"], "alive");
+}
+
+#[tokio::test]
+async fn test_check_records_state_and_detail() {
+    let ok = check("jwks", true, async { Ok(Some("2 keys".to_string())) }).await;
+    assert_eq!(ok.state, CheckState::Up);
+    assert_eq!(ok.detail.as_deref(), Some("2 keys"));
+
+    let failed = down("database", true).await;
+    assert_eq!(failed.state, CheckState::Down);
+    assert_eq!(failed.detail.as_deref(), Some("connection refused"));
+    assert!(failed.latency_ms >= 0.0);
+}
+
+#[tokio::test]
+async fn test_readiness_aggregation() {
+    let ready = HealthReport::new(vec![up("database", true).await, up("cache", false).await]);
+    assert_eq!(ready.status, Readiness::Ready);
+
+    let degraded = HealthReport::new(vec![up("database", true).await, down("cache", false).await]);
+    assert_eq!(degraded.status, Readiness::Degraded);
+    assert!(degraded.is_ready());
+
+    let not_ready = HealthReport::new(vec![
+        down("database", true).await,
+        down("cache", false).await,
+    ]);
+    assert_eq!(not_ready.status, Readiness::NotReady);
+    assert!(!not_ready.is_ready());
+}
+
+#[tokio::test]
+async fn test_report_json_shape() {
+    let report = HealthReport::new(vec![
+        up("migrations", true).await,
+        down("cache", true).await,
+    ]);
+    let json = serde_json::to_value(&report).unwrap();
+
+    assert_eq!(json["status"], "not_ready");
+    assert_eq!(json["checks"][0]["name"], "migrations");
+    assert_eq!(json["checks"][0]["state"], "up");
+    assert!(json["checks"][0].get("detail").is_none());
+    assert_eq!(json["checks"][1]["state"], "down");
+    assert_eq!(json["checks"][1]["detail"], "connection refused");
+    assert!(json["checks"][1]["latency_ms"].is_number());
+}
@@ -0,0 +1,107 @@
+//! Tests for how the JWKS cache refetches keys for unknown `kid`s, against a
+//! local JWKS endpoint that counts its fetches.
+//!
+//! Run with: `cargo test --test jwks_test`
+use actix_web::{App, HttpResponse, HttpServer, web};
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use gradwork_backend::auth::error::AuthError;
+use gradwork_backend::auth::jwks::{JwksCache, JwksSettings};
+use gradwork_backend::auth::validation::ValidationPolicy;
+
+const TEST_JWKS: &str = r#"{"keys": [{
+    "kid": "test-key",
+    "kty": "EC",
+    "crv": "P-256",
+    "alg": "ES256",
+    "x": "wXzntkml_9zdItnQWbpf6KWQvy_J-JhN8M0koDC7CZU",
+    "y": "ICbxqCp95SHxz1D-M5JsjPmgNQ74b-gv1d_8QK_Fbbs"
+}]}"#;
+
+/// Serve [`TEST_JWKS`] on a free local port. Returns the base URL and the
+/// number of fetches so far.
+fn serve_jwks() -> (String, Arc<AtomicUsize>) {
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let counter = web::Data::from(fetches.clone());
+    let server = HttpServer::new(move || {
+        App::new().app_data(counter.clone()).route(
+            "/auth/v1/.well-known/jwks.json",
+            web::get().to(|fetches: web::Data<AtomicUsize>| async move {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                HttpResponse::Ok()
+                    .content_type("application/json")
+                    .body(TEST_JWKS)
+            }),
+        )
+    })
+    .workers(1)
+    .listen(listener)
+    .unwrap()
+    .run();
+    actix_web::rt::spawn(server);
+
+    (url, fetches)
+}
+
+fn jwks_cache(url: &str, min_refetch_interval: Duration) -> JwksCache {
+    let settings = JwksSettings {
+        min_refetch_interval,
+        ..Default::default()
+    };
+    JwksCache::with_settings(url, "anon-key", ValidationPolicy::default(), settings)
+}
+
+/// A token whose header names `kid`; the cache rejects it before checking the signature.
+fn token_with_kid(kid: &str) -> String {
+    let mut header = Header::new(Algorithm::HS256);
+    header.kid = Some(kid.to_string());
+    let claims = serde_json::json!({ "sub": "user", "exp": 4_000_000_000u64 });
+    encode(&header, &claims, &EncodingKey::from_secret(b"unused")).unwrap()
+}
+
+async fn assert_unknown(cache: &JwksCache, kid: &str) {
+    let result = cache.validate_token(&token_with_kid(kid)).await;
+    assert!(
+        matches!(&result, Err(AuthError::UnknownKey(k)) if k == kid),
+        "{kid}: {result:?}"
+    );
+}
+
+#[actix_web::test]
+async fn test_unknown_kid_does_not_refetch_on_every_request() {
+    let (url, fetches) = serve_jwks();
+    // No rate limit, so only the negative cache stops repeated fetches.
+    let cache = jwks_cache(&url, Duration::ZERO);
+
+    for _ in 0..5 {
+        assert_unknown(&cache, "forged").await;
+    }
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    assert_eq!(cache.key_count(), 1);
+}
+
+#[actix_web::test]
+async fn test_refetches_are_rate_limited() {
+    let (url, fetches) = serve_jwks();
+    let cache = jwks_cache(&url, Duration::from_millis(300));
+
+    // Distinct kids, sequential and concurrent, share one fetch per interval.
+    assert_unknown(&cache, "kid-1").await;
+    tokio::join!(
+        assert_unknown(&cache, "kid-2"),
+        assert_unknown(&cache, "kid-3"),
+        assert_unknown(&cache, "kid-4"),
+    );
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+    tokio::time::sleep(Duration::from_millis(350)).await;
+    assert_unknown(&cache, "kid-5").await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,62 @@
+//! Tests for the in-process L1 cache and its invalidation messages.
+//!
+//! Run with: `cargo test --test local_cache_test`
+use std::time::Duration;
+
+use gradwork_backend::cache::local::{Invalidation, LocalCache};
+
+fn cache() -> LocalCache {
+    LocalCache::new(Duration::from_secs(60), 100)
+}
+
+#[tokio::test]
+async fn test_values_are_returned_as_stored_type() {
+    let cache = cache();
+    cache
+        .insert("gigs:list:l20:start", vec![1u32, 2, 3], &[])
+        .await;
+
+    let hit: Option<Vec<u32>> = cache.get("gigs:list:l20:start").await;
+    assert_eq!(hit, Some(vec![1, 2, 3]));
+
+    let wrong_type: Option<String> = cache.get("gigs:list:l20:start").await;
+    assert_eq!(wrong_type, None);
+    assert_eq!(cache.get::<u32>("missing").await, None);
+}
+
+#[tokio::test]
+async fn test_key_invalidation() {
+    let cache = cache();
+    cache.insert("gig:1", 1u32, &[]).await;
+    cache.insert("gig:2", 2u32, &[]).await;
+
+    cache.apply(&Invalidation::Key("gig:1".into())).await;
+
+    assert_eq!(cache.get::<u32>("gig:1").await, None);
+    assert_eq!(cache.get::<u32>("gig:2").await, Some(2));
+}
+
+#[tokio::test]
+async fn test_tag_invalidation_only_drops_tagged_entries() {
+    let cache = cache();
+    let all = vec!["gigs:all".to_string()];
+    let design = vec!["gigs:category:design".to_string()];
+    cache.insert("gigs:list:l20:start", 1u32, &all).await;
+    cache.insert("gigs:category:design", 2u32, &design).await;
+    cache.insert("gig:1", 3u32, &[]).await;
+
+    cache.apply(&Invalidation::Tags(design)).await;
+
+    assert_eq!(cache.get::<u32>("gigs:list:l20:start").await, Some(1));
+    assert_eq!(cache.get::<u32>("gigs:category:design").await, None);
+    assert_eq!(cache.get::<u32>("gig:1").await, Some(3));
+}
+
+#[test]
+fn test_invalidation_wire_format() {
+    let msg = Invalidation::Tags(vec!["gigs:all".into()]);
+    let json = serde_json::to_string(&msg).unwrap();
+
+    assert_eq!(json, r#"{"type":"tags","value":["gigs:all"]}"#);
+    assert_eq!(serde_json::from_str::<Invalidation>(&json).unwrap(), msg);
+}
@@ -0,0 +1,108 @@
+//! Tests for request spans and JSON log output.
+//!
+//! Run with: `cargo test --test logging_test`
+use actix_web::middleware::from_fn;
+use actix_web::test::{TestRequest, call_service, init_service};
+use actix_web::{App, HttpResponse, web};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use gradwork_backend::config::{LogConfig, LogFormat};
+use gradwork_backend::middleware::request_id::{REQUEST_ID_HEADER, record_user, request_id};
+use gradwork_backend::telemetry::subscriber;
+
+/// Log sink shared between the subscriber and the test.
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Captured {
+    fn lines(&self) -> Vec<serde_json::Value> {
+        let buf = self.0.lock().unwrap();
+        String::from_utf8_lossy(&buf)
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("log line is not JSON"))
+            .collect()
+    }
+}
+
+fn json_config() -> LogConfig {
+    LogConfig {
+        format: LogFormat::Json,
+        filter: "info".to_string(),
+    }
+}
+
+async fn handler(path: web::Path<Uuid>) -> HttpResponse {
+    record_user(path.into_inner());
+    tracing::info!("handling gig");
+    HttpResponse::Ok().finish()
+}
+
+#[actix_web::test]
+async fn test_request_span_fields_are_logged_as_json() {
+    let sink = Captured::default();
+    let writer = sink.clone();
+    let _guard =
+        tracing::subscriber::set_default(subscriber(&json_config(), move || writer.clone()));
+
+    let app = init_service(
+        App::new()
+            .wrap(from_fn(request_id))
+            .route("/gigs/{id}", web::get().to(handler)),
+    )
+    .await;
+    let user_id = Uuid::new_v4();
+    let req = TestRequest::get()
+        .uri(&format!("/gigs/{user_id}"))
+        .insert_header((REQUEST_ID_HEADER, "trace-123"))
+        .to_request();
+    call_service(&app, req).await;
+
+    let lines = sink.lines();
+    let handled = lines
+        .iter()
+        .find(|l| l["message"] == "handling gig")
+        .expect("handler event missing");
+    assert_eq!(handled["span"]["request_id"], "trace-123");
+    assert_eq!(handled["span"]["route"], "/gigs/{id}");
+    assert_eq!(handled["span"]["user_id"], user_id.to_string());
+
+    let completed = lines
+        .iter()
+        .find(|l| l["message"] == "request completed")
+        .expect("completion event missing");
+    assert_eq!(completed["span"]["status"], 200);
+    assert_eq!(completed["span"]["method"], "GET");
+    assert!(completed["latency_ms"].is_number());
+}
+
+#[actix_web::test]
+async fn test_unmatched_routes_are_labelled() {
+    let sink = Captured::default();
+    let writer = sink.clone();
+    let _guard =
+        tracing::subscriber::set_default(subscriber(&json_config(), move || writer.clone()));
+
+    let app = init_service(App::new().wrap(from_fn(request_id))).await;
+    call_service(&app, TestRequest::get().uri("/nope").to_request()).await;
+
+    let completed = sink
+        .lines()
+        .into_iter()
+        .find(|l| l["message"] == "request completed")
+        .expect("completion event missing");
+    assert_eq!(completed["span"]["route"], "unmatched");
+    assert_eq!(completed["span"]["status"], 404);
+}
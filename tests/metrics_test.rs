@@ -0,0 +1,86 @@
+//! Tests for the Prometheus metrics endpoint and its instrumentation.
+//!
+//! Run with: `cargo test --test metrics_test`
+use actix_web::middleware::from_fn;
+use actix_web::test::{TestRequest, call_service, init_service, read_body};
+use actix_web::{App, HttpResponse, web};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use gradwork_backend::cache::CacheStore;
+use gradwork_backend::chat::server::ChatServer;
+use gradwork_backend::handlers::init_metrics_routes;
+use gradwork_backend::metrics::{metrics, statement_operation};
+use gradwork_backend::middleware::metrics::track_requests;
+
+async fn scrape(chat_server: Arc<ChatServer>) -> String {
+    let app = init_service(
+        App::new()
+            .app_data(web::Data::new(chat_server))
+            .configure(init_metrics_routes),
+    )
+    .await;
+    let res = call_service(&app, TestRequest::get().uri("/metrics").to_request()).await;
+    assert!(res.status().is_success());
+    String::from_utf8(read_body(res).await.to_vec()).unwrap()
+}
+
+#[actix_web::test]
+async fn test_requests_are_labelled_by_route_pattern() {
+    let app = init_service(
+        App::new()
+            .wrap(from_fn(track_requests))
+            .route("/gigs/{id}", web::get().to(HttpResponse::Ok)),
+    )
+    .await;
+    call_service(&app, TestRequest::get().uri("/gigs/42").to_request()).await;
+    call_service(&app, TestRequest::get().uri("/nope/7").to_request()).await;
+
+    let body = scrape(Arc::new(ChatServer::new())).await;
+    assert!(
+        body.contains(
+            r#"gradwork_http_requests_total{method="GET",route="/gigs/{id}",status="200"}"#
+        )
+    );
+    assert!(body.contains(r#"route="unmatched",status="404""#));
+    assert!(!body.contains("/gigs/42"));
+}
+
+#[actix_web::test]
+async fn test_chat_gauges_are_sampled_at_scrape() {
+    let chat_server = Arc::new(ChatServer::new());
+    let contract_id = Uuid::new_v4();
+    let _a = chat_server.join(contract_id, Uuid::new_v4()).await;
+    let _b = chat_server.join(contract_id, Uuid::new_v4()).await;
+
+    let body = scrape(chat_server).await;
+    assert!(body.contains("gradwork_chat_rooms_open 1"));
+    assert!(body.contains("gradwork_chat_connections 2"));
+}
+
+#[tokio::test]
+async fn test_cache_reads_are_counted() {
+    let read = |result: &str| metrics().cache_reads.with_label_values(&[result]).get();
+    let (misses, hits) = (read("miss"), read("backend_hit"));
+
+    let cache = CacheStore::memory();
+    let key = format!("metrics:{}", Uuid::new_v4());
+    for _ in 0..2 {
+        let value: Result<u32, ()> = cache
+            .cached(&key, Duration::from_secs(60), || async { Ok(7) })
+            .await;
+        assert_eq!(value, Ok(7));
+    }
+
+    assert!(read("miss") > misses);
+    assert!(read("backend_hit") > hits);
+}
+
+#[test]
+fn test_statement_operation() {
+    assert_eq!(statement_operation("SELECT * FROM gigs"), "select");
+    assert_eq!(statement_operation("  insert INTO users"), "insert");
+    assert_eq!(statement_operation("VACUUM"), "other");
+    assert_eq!(statement_operation(""), "other");
+}
@@ -0,0 +1,158 @@
+//! Tests for the generated OpenAPI document and the routes serving it.
+//!
+//! Run with: `cargo test --test openapi_test`
+use actix_web::http::{StatusCode, header};
+use actix_web::test::{TestRequest, call_service, init_service, read_body, read_body_json};
+use actix_web::{App, web};
+use std::collections::BTreeSet;
+use utoipa::OpenApi;
+
+use gradwork_backend::handlers;
+use gradwork_backend::openapi::ApiDoc;
+
+/// Every `(METHOD, /api/...)` pair registered by `handlers::init_routes`,
+/// read from its source: actix has no public way to list an app's routes.
+fn registered_routes() -> BTreeSet<(String, String)> {
+    let source = include_str!("../src/handlers/mod.rs");
+    let start = source.find("pub fn init_routes").unwrap();
+    let body: String = source[start..].split_whitespace().collect();
+
+    let mut routes = BTreeSet::new();
+    let mut base = String::new();
+    let mut rest = body.as_str();
+    while let Some(at) = ["web::scope(\"", "web::resource(\"", ".route("]
+        .iter()
+        .filter_map(|token| rest.find(token).map(|i| (i, *token)))
+        .min()
+    {
+        let (i, token) = at;
+        rest = &rest[i + token.len()..];
+        if token != ".route(" {
+            let end = rest.find('"').unwrap();
+            base = rest[..end].to_string();
+            continue;
+        }
+        let path = match rest.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').unwrap();
+                format!("{base}{}", &quoted[..end])
+            }
+            None => base.clone(),
+        };
+        let method_at = rest.find("web::").unwrap() + "web::".len();
+        let method_end = method_at + rest[method_at..].find('(').unwrap();
+        let method = rest[method_at..method_end].to_uppercase();
+        routes.insert((method, format!("/api{path}")));
+    }
+    routes
+}
+
+fn documented_routes(doc: &serde_json::Value) -> BTreeSet<(String, String)> {
+    doc["paths"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .flat_map(|(path, item)| {
+            item.as_object()
+                .unwrap()
+                .keys()
+                .map(move |method| (method.to_uppercase(), path.clone()))
+        })
+        .collect()
+}
+
+fn collect_refs(value: &serde_json::Value, refs: &mut BTreeSet<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                match value.as_str() {
+                    Some(target) if key == "$ref" => {
+                        refs.insert(target.to_string());
+                    }
+                    _ => collect_refs(value, refs),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+    }
+}
+
+#[test]
+fn test_every_route_is_documented() {
+    let registered = registered_routes();
+    assert!(registered.len() > 40, "parsed only {registered:?}");
+
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let documented = documented_routes(&doc);
+
+    let missing: Vec<_> = registered.difference(&documented).collect();
+    assert!(
+        missing.is_empty(),
+        "routes without a #[utoipa::path]: {missing:?}"
+    );
+    let stale: Vec<_> = documented.difference(&registered).collect();
+    assert!(
+        stale.is_empty(),
+        "documented routes that are not registered: {stale:?}"
+    );
+}
+
+#[test]
+fn test_every_schema_reference_resolves() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schemas = doc["components"]["schemas"].as_object().unwrap();
+
+    let mut refs = BTreeSet::new();
+    collect_refs(&doc, &mut refs);
+    assert!(refs.contains("#/components/schemas/ErrorBody"));
+    for target in refs {
+        let name = target.strip_prefix("#/components/schemas/").unwrap();
+        assert!(schemas.contains_key(name), "unresolved {target}");
+    }
+}
+
+#[test]
+fn test_operations_declare_security() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schemes = doc["components"]["securitySchemes"].as_object().unwrap();
+    assert!(schemes.contains_key("bearer") && schemes.contains_key("api_key"));
+
+    // Public routes, and the WebSocket which authenticates by ticket.
+    let public = [
+        "/api/gigs",
+        "/api/gigs/category/{category}",
+        "/api/chat/ws/{contract_id}",
+    ];
+    for (method, path) in documented_routes(&doc) {
+        let operation = &doc["paths"][&path][method.to_lowercase()];
+        let secured = operation.get("security").is_some();
+        let expect_public = public.contains(&path.as_str()) && method == "GET";
+        assert_eq!(secured, !expect_public, "{method} {path}");
+    }
+}
+
+#[actix_web::test]
+async fn test_document_and_viewer_are_served() {
+    let app = init_service(
+        App::new()
+            .configure(handlers::init_docs_routes)
+            .service(web::scope("/api").configure(handlers::init_routes)),
+    )
+    .await;
+
+    let req = TestRequest::get().uri("/api/openapi.json").to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let doc: serde_json::Value = read_body_json(res).await;
+    assert!(doc["openapi"].as_str().unwrap().starts_with("3."));
+    assert_eq!(doc["info"]["title"], "Gradwork API");
+
+    let req = TestRequest::get().uri("/api/docs").to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let content_type = res.headers().get(header::CONTENT_TYPE).unwrap();
+    assert!(content_type.to_str().unwrap().starts_with("text/html"));
+    let html = read_body(res).await;
+    assert!(String::from_utf8_lossy(&html).contains("Gradwork API"));
+}
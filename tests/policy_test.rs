@@ -0,0 +1,170 @@
+//! Table-driven tests for the authorization policy.
+//!
+//! Run with: `cargo test --test policy_test`
+use uuid::Uuid;
+
+use gradwork_backend::auth::policy::{Action, Actor, ContractParties, Policy, Resource};
+use gradwork_backend::models::contracts::Status;
+use gradwork_backend::models::users::Roles;
+
+/// The people every case is built from.
+struct Cast {
+    client: Actor,
+    freelancer: Actor,
+    stranger: Actor,
+    admin: Actor,
+}
+
+impl Cast {
+    fn new() -> Self {
+        let actor = |role| Actor {
+            id: Uuid::new_v4(),
+            role,
+        };
+        Self {
+            client: actor(Roles::Client),
+            freelancer: actor(Roles::Freelancer),
+            stranger: actor(Roles::Freelancer),
+            admin: actor(Roles::Admin),
+        }
+    }
+
+    fn contract(&self, status: Status) -> ContractParties {
+        ContractParties {
+            client_id: self.client.id,
+            freelancer_id: self.freelancer.id,
+            status,
+        }
+    }
+}
+
+/// Assert every `(name, actor, action, resource, allowed)` row, naming the failing row.
+fn check(cases: Vec<(&str, &Actor, Action, Resource, bool)>) {
+    for (name, actor, action, resource, allowed) in cases {
+        let result = Policy::can(actor, action, &resource);
+        assert_eq!(result.is_ok(), allowed, "{name}: {result:?}");
+    }
+}
+
+#[rustfmt::skip]
+#[test]
+fn test_user_and_role_rules() {
+    let c = Cast::new();
+    let own = Resource::User { id: c.client.id };
+
+    check(vec![
+        ("anyone reads a user",            &c.stranger,   Action::Read,   own.clone(), true),
+        ("self updates",                   &c.client,     Action::Update, own.clone(), true),
+        ("other updates",                  &c.stranger,   Action::Update, own.clone(), false),
+        ("admin updates via user routes",  &c.admin,      Action::Update, own.clone(), false),
+        ("self deletes",                   &c.client,     Action::Delete, own.clone(), true),
+        ("other deletes",                  &c.stranger,   Action::Delete, own.clone(), false),
+        ("users are not created directly", &c.client,     Action::Create, own,         false),
+        ("client picks freelancer",        &c.client,     Action::Update, Resource::Role(Roles::Freelancer), true),
+        ("client grants admin",            &c.client,     Action::Update, Resource::Role(Roles::Admin), false),
+        ("freelancer grants admin",        &c.freelancer, Action::Update, Resource::Role(Roles::Admin), false),
+        ("admin grants admin",             &c.admin,      Action::Update, Resource::Role(Roles::Admin), true),
+    ]);
+}
+
+#[rustfmt::skip]
+#[test]
+fn test_gig_portfolio_and_api_key_ownership() {
+    let c = Cast::new();
+    let gig = Resource::Gig { owner_id: c.freelancer.id };
+    let gigs = Resource::UserGigs { owner_id: c.freelancer.id };
+    let item = Resource::Portfolio { freelancer_id: c.freelancer.id };
+    let key = Resource::ApiKey { owner_id: c.freelancer.id };
+
+    check(vec![
+        ("anyone reads a gig",                &c.client,     Action::Read,   gig.clone(),  true),
+        ("owner creates own gig",             &c.freelancer, Action::Create, gig.clone(),  true),
+        ("gig created for someone else",      &c.client,     Action::Create, gig.clone(),  false),
+        ("owner updates gig",                 &c.freelancer, Action::Update, gig.clone(),  true),
+        ("other updates gig",                 &c.stranger,   Action::Update, gig.clone(),  false),
+        ("owner deletes gig",                 &c.freelancer, Action::Delete, gig.clone(),  true),
+        ("admin deletes gig via user routes", &c.admin,      Action::Delete, gig,          false),
+        ("owner bulk-deletes gigs",           &c.freelancer, Action::Delete, gigs.clone(), true),
+        ("other bulk-deletes gigs",           &c.stranger,   Action::Delete, gigs,         false),
+        ("anyone reads portfolio",            &c.client,     Action::Read,   item.clone(), true),
+        ("owner creates portfolio",           &c.freelancer, Action::Create, item.clone(), true),
+        ("other creates portfolio",           &c.stranger,   Action::Create, item.clone(), false),
+        ("owner updates portfolio",           &c.freelancer, Action::Update, item.clone(), true),
+        ("other updates portfolio",           &c.client,     Action::Update, item.clone(), false),
+        ("owner deletes portfolio",           &c.freelancer, Action::Delete, item.clone(), true),
+        ("other deletes portfolio",           &c.stranger,   Action::Delete, item,         false),
+        ("owner revokes key",                 &c.freelancer, Action::Delete, key.clone(),  true),
+        ("other reads key",                   &c.stranger,   Action::Read,   key.clone(),  false),
+        ("admin revokes key via user routes", &c.admin,      Action::Delete, key,          false),
+    ]);
+}
+
+#[rustfmt::skip]
+#[test]
+fn test_contract_rules() {
+    let c = Cast::new();
+    let pending = Resource::Contract(c.contract(Status::Pending));
+    let own_gig = Resource::Contract(ContractParties {
+        client_id: c.freelancer.id,
+        ..c.contract(Status::Pending)
+    });
+    let gig_contracts = Resource::GigContracts { owner_id: c.freelancer.id };
+    let client_contracts = Resource::UserContracts { client_id: c.client.id };
+
+    check(vec![
+        ("client reads",                   &c.client,     Action::Read,   pending.clone(),  true),
+        ("freelancer reads",               &c.freelancer, Action::Read,   pending.clone(),  true),
+        ("stranger reads",                 &c.stranger,   Action::Read,   pending.clone(),  false),
+        ("client sends",                   &c.client,     Action::Create, pending.clone(),  true),
+        ("sent on behalf of someone else", &c.stranger,   Action::Create, pending.clone(),  false),
+        ("sent on own gig",                &c.freelancer, Action::Create, own_gig,          false),
+        ("freelancer responds",            &c.freelancer, Action::Update, pending.clone(),  true),
+        ("client responds",                &c.client,     Action::Update, pending.clone(),  false),
+        ("client withdraws",               &c.client,     Action::Delete, pending.clone(),  true),
+        ("freelancer withdraws",           &c.freelancer, Action::Delete, pending,          false),
+        ("owner lists gig contracts",      &c.freelancer, Action::Read,   gig_contracts.clone(), true),
+        ("other lists gig contracts",      &c.client,     Action::Read,   gig_contracts,    false),
+        ("client lists own contracts",     &c.client,     Action::Read,   client_contracts.clone(), true),
+        ("other lists client contracts",   &c.freelancer, Action::Read,   client_contracts, false),
+    ]);
+}
+
+#[rustfmt::skip]
+#[test]
+fn test_chat_and_message_rules() {
+    let c = Cast::new();
+    let accepted = c.contract(Status::Accepted);
+    let chat = Resource::Chat(accepted.clone());
+    let pending_chat = Resource::Chat(c.contract(Status::Pending));
+    let rejected_chat = Resource::Chat(c.contract(Status::Rejected));
+    let from_client = Resource::Message { sender_id: c.client.id, contract: accepted };
+
+    check(vec![
+        ("client joins",         &c.client,     Action::Read,   chat.clone(),        true),
+        ("freelancer sends",     &c.freelancer, Action::Create, chat.clone(),        true),
+        ("stranger joins",       &c.stranger,   Action::Read,   chat.clone(),        false),
+        ("admin joins",          &c.admin,      Action::Read,   chat,                false),
+        ("pending chat",         &c.client,     Action::Read,   pending_chat,        false),
+        ("rejected chat",        &c.freelancer, Action::Read,   rejected_chat,       false),
+        ("recipient marks read", &c.freelancer, Action::Update, from_client.clone(), true),
+        ("sender marks read",    &c.client,     Action::Update, from_client.clone(), false),
+        ("stranger marks read",  &c.stranger,   Action::Update, from_client.clone(), false),
+        ("party reads message",  &c.client,     Action::Read,   from_client,         true),
+    ]);
+}
+
+#[test]
+fn test_denials_explain_the_rule() {
+    let c = Cast::new();
+    let pending_chat = Resource::Chat(c.contract(Status::Pending));
+
+    let denied = Policy::can(&c.stranger, Action::Read, &pending_chat).unwrap_err();
+    assert_eq!(
+        denied.reason,
+        "Chat is only available for accepted contracts"
+    );
+
+    let accepted_chat = Resource::Chat(c.contract(Status::Accepted));
+    let denied = Policy::can(&c.stranger, Action::Read, &accepted_chat).unwrap_err();
+    assert_eq!(denied.to_string(), "You are not a party to this contract");
+}
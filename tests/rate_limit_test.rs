@@ -0,0 +1,308 @@
+//! Tests for the rate limiter, its middleware and chat frame limits.
+//!
+//! Run with: `cargo test --test rate_limit_test`
+use actix_web::http::{Method, StatusCode, header};
+use actix_web::middleware::from_fn;
+use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+use actix_web::{App, HttpResponse, web};
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use gradwork_backend::auth::validation::ValidationPolicy;
+use gradwork_backend::auth::verifier::{HmacVerifier, VerifierData};
+use gradwork_backend::cache::redis_backend::RedisBackend;
+use gradwork_backend::cache::{CacheBackend, CacheStore};
+use gradwork_backend::chat::protocol::ClientMessage;
+use gradwork_backend::config::RateLimitConfig;
+use gradwork_backend::middleware::rate_limit::rate_limit;
+use gradwork_backend::rate_limit::{ClientKey, RateLimit, RateLimiter, RouteGroup};
+
+const SECRET: &str = "test-secret-at-least-256-bits-long-for-hs256-xxxxxxx";
+
+fn limiter(config: RateLimitConfig) -> Arc<RateLimiter> {
+    Arc::new(RateLimiter::new(config, Arc::new(CacheStore::memory())))
+}
+
+fn token(user_id: Uuid) -> String {
+    let claims = serde_json::json!({
+        "sub": user_id.to_string(),
+        "exp": chrono::Utc::now().timestamp() + 3600,
+    });
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+fn gigs_from(ip: &str) -> TestRequest {
+    TestRequest::get()
+        .uri("/api/gigs")
+        .peer_addr(format!("{ip}:40000").parse().unwrap())
+}
+
+#[test]
+fn test_parse_rate_limit() {
+    let limit: RateLimit = "10/min".parse().unwrap();
+    assert_eq!(limit, RateLimit::per_minute(10));
+    assert_eq!(
+        "5/s".parse::<RateLimit>().unwrap().period,
+        Duration::from_secs(1)
+    );
+    for bad in ["0/min", "10", "10/day", "ten/min"] {
+        assert!(bad.parse::<RateLimit>().is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn test_route_groups() {
+    let cases = [
+        (Method::GET, "/api/gigs", RouteGroup::GigReads),
+        (Method::GET, "/api/gigs/{id}", RouteGroup::GigReads),
+        (Method::POST, "/api/gigs", RouteGroup::Writes),
+        (Method::POST, "/api/contracts", RouteGroup::ContractWrites),
+        (
+            Method::PUT,
+            "/api/contracts/{id}/status",
+            RouteGroup::ContractWrites,
+        ),
+        (Method::GET, "/api/contracts", RouteGroup::Default),
+        (Method::GET, "/api/auth/me", RouteGroup::Auth),
+        (Method::POST, "/api/chat/ws-ticket", RouteGroup::Auth),
+        (Method::GET, "unmatched", RouteGroup::Default),
+    ];
+    for (method, pattern, group) in cases {
+        assert_eq!(
+            RouteGroup::classify(&method, pattern),
+            group,
+            "{method} {pattern}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_bucket_refills_over_time() {
+    let cache = CacheStore::memory();
+    let interval = Duration::from_millis(100);
+    for _ in 0..3 {
+        assert!(cache.take_token("b", 3, interval).await.unwrap().is_zero());
+    }
+
+    let wait = cache.take_token("b", 3, interval).await.unwrap();
+    assert!(!wait.is_zero() && wait <= interval, "{wait:?}");
+    tokio::time::sleep(wait).await;
+    assert!(cache.take_token("b", 3, interval).await.unwrap().is_zero());
+}
+
+#[actix_web::test]
+async fn test_requests_over_budget_get_429_per_client() {
+    let config = RateLimitConfig {
+        gig_reads: "2/min".parse().unwrap(),
+        ..Default::default()
+    };
+    let app = init_service(
+        App::new()
+            .app_data(web::Data::new(limiter(config)))
+            .service(
+                web::scope("/api")
+                    .wrap(from_fn(rate_limit))
+                    .route("/gigs", web::get().to(HttpResponse::Ok)),
+            ),
+    )
+    .await;
+
+    for _ in 0..2 {
+        let res = call_service(&app, gigs_from("10.0.0.1").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+    let res = call_service(&app, gigs_from("10.0.0.1").to_request()).await;
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = res
+        .headers()
+        .get(header::RETRY_AFTER)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=30).contains(&retry_after), "{retry_after}");
+    let body: serde_json::Value = read_body_json(res).await;
+    assert_eq!(body["code"], "rate_limited");
+
+    let res = call_service(&app, gigs_from("10.0.0.2").to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_valid_tokens_are_counted_per_user() {
+    let config = RateLimitConfig {
+        gig_reads: "1/min".parse().unwrap(),
+        ..Default::default()
+    };
+    let policy = ValidationPolicy {
+        issuer: None,
+        audiences: Vec::new(),
+        require_email: false,
+        ..Default::default()
+    };
+    let verifier: VerifierData = Arc::new(HmacVerifier::new(SECRET, policy));
+    let app = init_service(
+        App::new()
+            .app_data(web::Data::new(limiter(config)))
+            .app_data(web::Data::new(verifier))
+            .service(
+                web::scope("/api")
+                    .wrap(from_fn(rate_limit))
+                    .route("/gigs", web::get().to(HttpResponse::Ok)),
+            ),
+    )
+    .await;
+
+    // Same address, different users: each has its own budget.
+    for _ in 0..2 {
+        let req = gigs_from("10.0.0.1").insert_header((
+            header::AUTHORIZATION,
+            format!("Bearer {}", token(Uuid::new_v4())),
+        ));
+        let res = call_service(&app, req.to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    // An invalid token falls back to the address, whatever it claims.
+    let forged = gigs_from("10.0.0.3").insert_header((header::AUTHORIZATION, "Bearer forged"));
+    assert_eq!(
+        call_service(&app, forged.to_request()).await.status(),
+        StatusCode::OK
+    );
+    let forged = gigs_from("10.0.0.3").insert_header((header::AUTHORIZATION, "Bearer other"));
+    assert_eq!(
+        call_service(&app, forged.to_request()).await.status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[actix_web::test]
+async fn test_api_keys_are_counted_per_key() {
+    let config = RateLimitConfig {
+        gig_reads: "1/min".parse().unwrap(),
+        ..Default::default()
+    };
+    let app = init_service(
+        App::new()
+            .app_data(web::Data::new(limiter(config)))
+            .service(
+                web::scope("/api")
+                    .wrap(from_fn(rate_limit))
+                    .route("/gigs", web::get().to(HttpResponse::Ok)),
+            ),
+    )
+    .await;
+
+    // Counted without a lookup: same address, different keys, separate budgets.
+    let statuses = [
+        ("gwk_first", StatusCode::OK),
+        ("gwk_second", StatusCode::OK),
+        ("gwk_first", StatusCode::TOO_MANY_REQUESTS),
+    ];
+    for (key, status) in statuses {
+        let req =
+            gigs_from("10.0.0.4").insert_header((header::AUTHORIZATION, format!("ApiKey {key}")));
+        assert_eq!(call_service(&app, req.to_request()).await.status(), status);
+    }
+
+    // Anything without the key prefix is counted per address.
+    let req = gigs_from("10.0.0.5").insert_header((header::AUTHORIZATION, "ApiKey bogus"));
+    assert_eq!(
+        call_service(&app, req.to_request()).await.status(),
+        StatusCode::OK
+    );
+    let req = gigs_from("10.0.0.5").insert_header((header::AUTHORIZATION, "ApiKey other"));
+    assert_eq!(
+        call_service(&app, req.to_request()).await.status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[tokio::test]
+async fn test_chat_frames_have_their_own_budgets() {
+    let config = RateLimitConfig {
+        chat_send_message: "1/min".parse().unwrap(),
+        chat_typing: "2/min".parse().unwrap(),
+        ..Default::default()
+    };
+    let limiter = limiter(config);
+    let user_id = Uuid::new_v4();
+    let send = ClientMessage::SendMessage {
+        content: "hi".into(),
+    };
+
+    assert!(limiter.check_chat(&send, user_id).await.is_ok());
+    assert!(limiter.check_chat(&send, user_id).await.is_err());
+    assert!(limiter.check_chat(&send, Uuid::new_v4()).await.is_ok());
+
+    // Typing and stop-typing share a bucket.
+    assert!(
+        limiter
+            .check_chat(&ClientMessage::Typing, user_id)
+            .await
+            .is_ok()
+    );
+    assert!(
+        limiter
+            .check_chat(&ClientMessage::StopTyping, user_id)
+            .await
+            .is_ok()
+    );
+    assert!(
+        limiter
+            .check_chat(&ClientMessage::Typing, user_id)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_limits_hold_in_memory_while_redis_is_down() {
+    let redis: Arc<dyn CacheBackend> = Arc::new(RedisBackend::new("redis://127.0.0.1:1").unwrap());
+    let config = RateLimitConfig {
+        writes: "1/min".parse().unwrap(),
+        ..Default::default()
+    };
+    let limiter = RateLimiter::new(config, Arc::new(CacheStore::new(redis)));
+    let client = ClientKey::Ip("10.0.0.1".into());
+
+    assert!(
+        limiter
+            .check_route(RouteGroup::Writes, &client)
+            .await
+            .is_ok()
+    );
+    assert!(
+        limiter
+            .check_route(RouteGroup::Writes, &client)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_disabled_limiter_allows_everything() {
+    let config = RateLimitConfig {
+        enabled: false,
+        writes: "1/min".parse().unwrap(),
+        ..Default::default()
+    };
+    let limiter = limiter(config);
+    let client = ClientKey::Ip("10.0.0.1".into());
+    for _ in 0..5 {
+        assert!(
+            limiter
+                .check_route(RouteGroup::Writes, &client)
+                .await
+                .is_ok()
+        );
+    }
+}
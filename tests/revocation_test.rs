@@ -0,0 +1,60 @@
+//! Tests for token revocation rules.
+//!
+//! Run with: `cargo test --test revocation_test`
+use chrono::Utc;
+use uuid::Uuid;
+
+use gradwork_backend::auth::error::AuthError;
+use gradwork_backend::auth::jwt::Claims;
+use gradwork_backend::auth::revocation::issued_before_cutoff;
+
+/// Helper: minimal claims with the given token identifiers.
+fn claims_with_ids(jti: Option<&str>, session_id: Option<&str>) -> Claims {
+    let now = Utc::now().timestamp() as usize;
+    Claims {
+        sub: Uuid::new_v4().to_string(),
+        exp: now + 3600,
+        iat: Some(now),
+        iss: None,
+        aud: None,
+        email: None,
+        role: None,
+        user_metadata: None,
+        app_metadata: None,
+        jti: jti.map(String::from),
+        session_id: session_id.map(String::from),
+    }
+}
+
+#[test]
+fn test_tokens_issued_before_the_cutoff_are_revoked() {
+    let cutoff = 1_700_000_000;
+    assert!(issued_before_cutoff(Some(1_699_999_999), cutoff));
+    // A token from the same second may postdate the cutoff, so it is kept.
+    assert!(!issued_before_cutoff(Some(1_700_000_000), cutoff));
+    assert!(!issued_before_cutoff(Some(1_700_000_001), cutoff));
+    // Without `iat` a token can't prove it postdates the cutoff.
+    assert!(issued_before_cutoff(None, cutoff));
+}
+
+#[test]
+fn test_revocation_id_prefers_jti_over_session_id() {
+    assert_eq!(
+        claims_with_ids(Some("jti-1"), Some("sess-1")).revocation_id(),
+        Some("jti-1")
+    );
+    assert_eq!(
+        claims_with_ids(None, Some("sess-1")).revocation_id(),
+        Some("sess-1")
+    );
+    assert_eq!(claims_with_ids(None, None).revocation_id(), None);
+}
+
+#[test]
+fn test_revoked_token_error_is_unauthorized() {
+    use actix_web::ResponseError;
+
+    let err = AuthError::TokenRevoked;
+    assert_eq!(err.code(), "token_revoked");
+    assert_eq!(err.status_code(), actix_web::http::StatusCode::UNAUTHORIZED);
+}
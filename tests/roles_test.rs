@@ -0,0 +1,30 @@
+//! Tests for the role sets behind the `RequireRole` / `RequireAnyRole` extractors.
+//!
+//! Run with: `cargo test --test roles_test`
+use gradwork_backend::auth::error::AuthError;
+use gradwork_backend::auth::roles::{Admin, Client, Freelancer, RoleSet, check_role};
+use gradwork_backend::models::users::Roles;
+
+#[test]
+fn test_single_role_only_allows_that_role() {
+    assert!(Admin::allows(&Roles::Admin));
+    assert!(!Admin::allows(&Roles::Freelancer));
+    assert!(!Admin::allows(&Roles::Client));
+}
+
+#[test]
+fn test_role_tuple_allows_any_member() {
+    assert!(<(Freelancer, Admin)>::allows(&Roles::Freelancer));
+    assert!(<(Freelancer, Admin)>::allows(&Roles::Admin));
+    assert!(!<(Freelancer, Admin)>::allows(&Roles::Client));
+    assert!(<(Client, Freelancer, Admin)>::allows(&Roles::Client));
+}
+
+#[test]
+fn test_check_role_reports_required_roles() {
+    assert!(check_role::<Admin>(&Roles::Admin).is_ok());
+
+    let err = check_role::<(Freelancer, Admin)>(&Roles::Client).unwrap_err();
+    assert_eq!(err.code(), "insufficient_role");
+    assert!(matches!(err, AuthError::InsufficientRole(roles) if roles == "freelancer, admin"));
+}
@@ -0,0 +1,43 @@
+//! Tests for the per-key single-flight locks behind `CacheStore::cached`.
+//!
+//! Run with: `cargo test --test single_flight_test`
+use std::time::Duration;
+
+use gradwork_backend::cache::single_flight::SingleFlight;
+
+#[tokio::test]
+async fn test_different_keys_do_not_wait_on_each_other() {
+    let flights = SingleFlight::new();
+
+    let _a = flights.acquire("gig:1").await;
+    let b = tokio::time::timeout(Duration::from_millis(100), flights.acquire("gig:2")).await;
+    assert!(b.is_ok());
+
+    let same = tokio::time::timeout(Duration::from_millis(50), flights.acquire("gig:1")).await;
+    assert!(same.is_err());
+}
+
+#[tokio::test]
+async fn test_released_flights_are_removed() {
+    let flights = SingleFlight::new();
+
+    let first = flights.acquire("gig:1").await;
+    assert_eq!(flights.in_flight(), 1);
+    drop(first);
+    assert_eq!(flights.in_flight(), 0);
+
+    // A waiter that takes over the flight also removes it when done.
+    let holder = flights.acquire("user:1").await;
+    tokio::join!(
+        async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(holder);
+        },
+        async {
+            let waiter = flights.acquire("user:1").await;
+            assert_eq!(flights.in_flight(), 1);
+            drop(waiter);
+        },
+    );
+    assert_eq!(flights.in_flight(), 0);
+}
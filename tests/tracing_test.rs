@@ -0,0 +1,71 @@
+//! Tests for W3C trace-context propagation into request spans and outbound calls.
+//!
+//! Run with: `cargo test --test tracing_test`
+use actix_web::middleware::from_fn;
+use actix_web::test::{TestRequest, call_service, init_service};
+use actix_web::{App, HttpResponse, web};
+use opentelemetry::global;
+use opentelemetry::trace::{SpanId, TraceId, TracerProvider as _};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+use tracing_subscriber::layer::SubscriberExt;
+
+use gradwork_backend::config::LogConfig;
+use gradwork_backend::middleware::request_id::request_id;
+use gradwork_backend::telemetry::{subscriber, trace_headers};
+
+const TRACE_ID: &str = "4bf92f3577b34a4736d2a2d1e5d0b0c1";
+const PARENT_ID: &str = "00f067aa0ba902b7";
+
+async fn handler() -> HttpResponse {
+    // Outbound calls made while handling the request continue its trace.
+    let traceparent = trace_headers().remove("traceparent").unwrap_or_default();
+    HttpResponse::Ok().body(traceparent)
+}
+
+#[actix_web::test]
+async fn test_incoming_traceparent_is_continued() {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let otel = tracing_opentelemetry::layer().with_tracer(provider.tracer("test"));
+    let _guard = tracing::subscriber::set_default(
+        subscriber(&LogConfig::default(), std::io::sink).with(otel),
+    );
+
+    let app = init_service(
+        App::new()
+            .wrap(from_fn(request_id))
+            .route("/gigs", web::get().to(handler)),
+    )
+    .await;
+    let req = TestRequest::get()
+        .uri("/gigs")
+        .insert_header(("traceparent", format!("00-{TRACE_ID}-{PARENT_ID}-01")))
+        .to_request();
+    let res = call_service(&app, req).await;
+    let outbound = actix_web::test::read_body(res).await;
+
+    let spans = exporter.get_finished_spans().unwrap();
+    let span = spans
+        .iter()
+        .find(|s| s.name == "request")
+        .expect("request span not exported");
+    assert_eq!(
+        span.span_context.trace_id(),
+        TraceId::from_hex(TRACE_ID).unwrap()
+    );
+    assert_eq!(span.parent_span_id, SpanId::from_hex(PARENT_ID).unwrap());
+
+    let outbound = String::from_utf8(outbound.to_vec()).unwrap();
+    assert!(
+        outbound.starts_with(&format!("00-{TRACE_ID}-")),
+        "{outbound}"
+    );
+    assert!(
+        !outbound.contains(PARENT_ID),
+        "outbound parent should be the request span"
+    );
+}
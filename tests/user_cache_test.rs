@@ -0,0 +1,59 @@
+//! Tests for the in-process authenticated-user cache.
+//!
+//! Run with: `cargo test --test user_cache_test`
+use chrono::Utc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use gradwork_backend::auth::user_cache::UserCache;
+use gradwork_backend::models::users::{AccountStatus, Model, Roles};
+
+/// Helper: a minimal user row.
+fn test_user() -> Model {
+    Model {
+        id: Uuid::new_v4(),
+        email: "test@example.com".to_string(),
+        username: None,
+        display_name: None,
+        avatar_url: None,
+        auth_provider: "google".to_string(),
+        role: Roles::Client,
+        created_at: Utc::now(),
+        updated_at: None,
+        status: AccountStatus::Active,
+        suspended_until: None,
+        status_reason: None,
+        last_synced_at: None,
+        email_locked: false,
+        display_name_locked: false,
+        avatar_url_locked: false,
+    }
+}
+
+#[tokio::test]
+async fn test_hit_after_insert_and_miss_after_invalidate() {
+    let cache = UserCache::new(Duration::from_secs(30), 100, None);
+    let user = test_user();
+
+    assert!(cache.get(user.id).await.is_none());
+    cache.insert(&user).await;
+    assert_eq!(cache.get(user.id).await.map(|u| u.id), Some(user.id));
+
+    cache.invalidate(user.id).await;
+    assert!(cache.get(user.id).await.is_none());
+
+    let stats = cache.stats();
+    assert_eq!(stats.local_hits, 1);
+    assert_eq!(stats.redis_hits, 0);
+    assert_eq!(stats.misses, 2);
+}
+
+#[tokio::test]
+async fn test_entries_expire_after_ttl() {
+    let cache = UserCache::new(Duration::from_millis(50), 100, None);
+    let user = test_user();
+
+    cache.insert(&user).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(cache.get(user.id).await.is_none());
+}
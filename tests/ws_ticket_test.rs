@@ -0,0 +1,45 @@
+//! Tests for WebSocket handshake credentials (tickets and the bearer subprotocol).
+//!
+//! Run with: `cargo test --test ws_ticket_test`
+use uuid::Uuid;
+
+use gradwork_backend::chat::ticket::{WsTicket, bearer_from_protocols, generate_ticket_id};
+
+#[test]
+fn test_bearer_token_is_read_from_subprotocols() {
+    assert_eq!(
+        bearer_from_protocols("bearer, abc.def.ghi"),
+        Some("abc.def.ghi")
+    );
+    assert_eq!(
+        bearer_from_protocols("chat,bearer,abc.def.ghi"),
+        Some("abc.def.ghi")
+    );
+    assert_eq!(bearer_from_protocols("bearer"), None);
+    assert_eq!(bearer_from_protocols("chat, abc.def.ghi"), None);
+}
+
+#[test]
+fn test_ticket_is_bound_to_its_contract() {
+    let contract_id = Uuid::new_v4();
+    let bound = WsTicket {
+        user_id: Uuid::new_v4(),
+        contract_id: Some(contract_id),
+    };
+    assert!(bound.allows(contract_id));
+    assert!(!bound.allows(Uuid::new_v4()));
+
+    let unbound = WsTicket {
+        contract_id: None,
+        ..bound
+    };
+    assert!(unbound.allows(Uuid::new_v4()));
+}
+
+#[test]
+fn test_ticket_ids_are_random() {
+    let a = generate_ticket_id();
+    let b = generate_ticket_id();
+    assert_eq!(a.len(), 32);
+    assert_ne!(a, b);
+}